@@ -17,8 +17,14 @@ struct Synth {
     // Input continued (16 bytes)
     input_phase: f32,
     input_rotation: f32,
-    _pad0: f32,
-    _pad1: f32,
+    input_phase_speed: f32,
+    input_noise_seed: u32,
+
+    // Input oscillator and shapes (16 bytes)
+    input_osc_waveform: u32,
+    input_osc_duty: f32,
+    input_shape_size: f32,
+    input_shape_softness: f32,
 
     // Geometry stage (32 bytes)
     geo_wobbulate_h: f32,
@@ -31,6 +37,12 @@ struct Synth {
     geo_rotation: f32,
     geo_scale: f32,
 
+    // Geometry continued (16 bytes)
+    geo_rotate_center_x: f32,
+    geo_rotate_center_y: f32,
+    geo_spin_speed: f32,
+    input_spin_speed: f32, // input stage, in a free slot
+
     // Amplitude stage (32 bytes)
     amp_fold_gain: f32,
     amp_fold_mix: f32,
@@ -40,7 +52,13 @@ struct Synth {
     amp_soft_clip: f32,
     amp_solarize: f32,
     amp_gate_threshold: f32,
-    amp_invert: f32,
+    amp_invert_mask: u32,
+
+    // Amplitude continued (16 bytes)
+    amp_solarize_mask: u32,
+    amp_dither: f32,
+    amp_norm_offset: f32,
+    amp_norm_scale: f32,
 
     // Colorize stage (32 bytes)
     color_mode: u32,
@@ -49,11 +67,15 @@ struct Synth {
     color_levels: f32,
 
     color_gradient_start: vec3<f32>,
-    _pad2: f32,
+    color_palette_size: u32,
 
     // Colorize gradient end (16 bytes)
     color_gradient_end: vec3<f32>,
-    _pad3: f32,
+    _pad0: f32,
+
+    // Colorize continued (16 bytes)
+    color_mono_tint: vec3<f32>,
+    color_gradient_source: u32,
 
     // Mixer stage (16 bytes)
     mixer_feedback_mix: f32,
@@ -64,8 +86,8 @@ struct Synth {
     // Mixer continued (16 bytes)
     mixer_key_invert: f32,
     mixer_layer_opacity: f32,
-    _pad4: f32,
-    _pad5: f32,
+    mixer_normalize: f32,
+    mixer_hold: f32,
 
     // Feedback stage (32 bytes)
     fb_enabled: f32,
@@ -78,6 +100,17 @@ struct Synth {
     fb_offset_y: f32,
     fb_saturation: f32,
 
+    // Feedback second tap (32 bytes)
+    fb_tap2_zoom: f32,
+    fb_tap2_rotation: f32,
+    fb_tap2_offset_x: f32,
+    fb_tap2_offset_y: f32,
+
+    fb_tap_mix: f32,
+    fb_wrap: u32,
+    out_luma_bandwidth: f32,
+    out_chroma_bandwidth: f32,
+
     // Output stage (32 bytes)
     out_vhs_enabled: f32,
     out_cable_enabled: f32,
@@ -96,26 +129,61 @@ struct Synth {
     out_ghosting: f32,
 
     out_cable_noise: f32,
-    _pad6a: f32,
-    _pad6b: f32,
-    _pad6c: f32,
+    out_order: u32,
+    pixel_scale: f32,
+    out_noise_persistence: f32,
 
-    // Timing (16 bytes)
+    // Output continued (16 bytes)
+    out_vignette_color: vec3<f32>,
+    loop_length: f32,
+
+    // Timing + master (16 bytes)
     time: f32,
     frame: u32,
-    _pad7: f32,
-    _pad8: f32,
+    master_fade: f32,
+    signal_probe: f32,
+
+    // Synth buffer + feedback echo (16 bytes)
+    aspect: f32,
+    fb_echo_mix: f32,
+    output_height: f32,
+    _pad2: f32,
+
+    // Threshold palette (256 bytes, rgb + pad)
+    color_palette: array<vec4<f32>, 16>,
 }
 
 @group(0) @binding(0)
 var<uniform> synth: Synth;
 
+// Palette colorize lookup table (256x1), read by index
+@group(0) @binding(1)
+var palette_lut: texture_2d<f32>;
+
 @group(1) @binding(0)
 var feedback_texture: texture_2d<f32>;
 
 @group(1) @binding(1)
 var feedback_sampler: sampler;
 
+// Oldest frame in the feedback ring, for the echo tap
+@group(1) @binding(2)
+var echo_texture: texture_2d<f32>;
+
+// Still image for the Image input source (grey until one is loaded)
+@group(2) @binding(0)
+var input_image: texture_2d<f32>;
+
+@group(2) @binding(1)
+var input_image_sampler: sampler;
+
+// Latest webcam frame for the Camera source (grey without a camera)
+@group(3) @binding(0)
+var camera_texture: texture_2d<f32>;
+
+@group(3) @binding(1)
+var camera_sampler: sampler;
+
 // ============================================
 // VERTEX SHADER
 // ============================================
@@ -172,6 +240,31 @@ fn noise_value(p: vec2<f32>) -> f32 {
     return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
 }
 
+// Octave-summed noise with a power-law spectrum.
+// Each octave is value noise, which is roughly band-limited around its cell
+// frequency, so summing octaves at doubling frequencies with a per-octave
+// amplitude `gain` approximates amplitude ~ f^(-b/2), i.e. power ~ 1/f^b:
+//   pink  (1/f,   b = 1): gain = 1/sqrt(2)
+//   brown (1/f^2, b = 2): gain = 1/2
+// Octaves span 2 to 256 cells across the frame (down to ~pixel scale at 640
+// wide). The sum is divided by the RMS octave amplitude so contrast matches a
+// single octave instead of flattening toward gray as octaves are added.
+fn noise_power_law(uv: vec2<f32>, offset: f32, gain: f32) -> f32 {
+    var sum = 0.0;
+    var energy = 0.0;
+    var amplitude = 1.0;
+    var freq = 2.0;
+    for (var i = 0; i < 8; i++) {
+        // Decorrelate octaves so their lattices don't line up at the origin
+        let shift = vec2<f32>(f32(i) * 17.13, f32(i) * 31.71);
+        sum += (noise_value(uv * freq + shift + offset) - 0.5) * amplitude;
+        energy += amplitude * amplitude;
+        amplitude *= gain;
+        freq *= 2.0;
+    }
+    return clamp(0.5 + sum / sqrt(energy), 0.0, 1.0);
+}
+
 // ============================================
 // STAGE 1: INPUT SOURCES
 // ============================================
@@ -187,58 +280,125 @@ const SRC_SHAPE_CIRCLE: u32 = 7u;
 const SRC_SHAPE_RECT: u32 = 8u;
 const SRC_SHAPE_DIAMOND: u32 = 9u;
 const SRC_CHECKERBOARD: u32 = 10u;
+const SRC_IMAGE: u32 = 11u;
+const SRC_CAMERA: u32 = 12u;
+
+// Input frequency at which the image fills the frame exactly once
+const IMAGE_BASE_FREQUENCY: f32 = 4.0;
+
+// Buffer aspect the patterns were designed at; wider buffers show more of
+// them instead of stretching
+const BASE_ASPECT: f32 = 1.33333333;
+
+const WAVE_TRIANGLE: u32 = 1u;
+const WAVE_SAW: u32 = 2u;
+const WAVE_SQUARE: u32 = 3u;
+const WAVE_PULSE: u32 = 4u;
+
+// Oscillator waveform at `cycles` (-1 to 1), phased like sin(cycles * TAU):
+// zero and rising at the start of each cycle, high for the first half
+fn osc_wave(cycles: f32) -> f32 {
+    let t = fract(cycles);
+    if synth.input_osc_waveform == WAVE_TRIANGLE {
+        return 1.0 - 4.0 * abs(fract(t + 0.25) - 0.5);
+    } else if synth.input_osc_waveform == WAVE_SAW {
+        return 2.0 * fract(t + 0.5) - 1.0;
+    } else if synth.input_osc_waveform == WAVE_SQUARE {
+        return select(-1.0, 1.0, t < 0.5);
+    } else if synth.input_osc_waveform == WAVE_PULSE {
+        return select(-1.0, 1.0, t < synth.input_osc_duty);
+    }
+    return sin(cycles * TAU);
+}
+
+// Per-patch offset into the noise field (R2 sequence, so seed 0 = no offset)
+fn noise_seed_offset() -> vec2<f32> {
+    let s = f32(synth.input_noise_seed);
+    return fract(vec2<f32>(s * 0.7548776662, s * 0.5698402910)) * 64.0;
+}
+
+// Contrast left in a pattern of `cycles` per pixel: fades to flat grey as it
+// nears Nyquist, so high frequencies blur out instead of aliasing
+fn pattern_contrast(cycles: f32) -> f32 {
+    return 1.0 - smoothstep(0.25, 0.5, cycles);
+}
+
+// Cycles completed at `rate` per second. In loop mode (loop_length > 0,
+// time wrapping at it) the rate is rounded to whole cycles per loop, so
+// periodic motion lines up across the wrap.
+fn loop_cycles(time: f32, rate: f32) -> f32 {
+    if synth.loop_length > 0.0 {
+        return time / synth.loop_length * round(rate * synth.loop_length);
+    }
+    return time * rate;
+}
+
+// Shape edge width at full softness `max_width`; kept above zero so
+// smoothstep stays defined with hard edges
+fn shape_edge(max_width: f32) -> f32 {
+    return max(synth.input_shape_softness * max_width, 0.0005);
+}
+
+// uv_width: UV change across one pixel (fwidth, taken in uniform control flow)
+fn generate_source(source: u32, uv: vec2<f32>, uv_width: vec2<f32>, time: f32, frequency: f32, phase: f32) -> f32 {
+    // Patterns and shapes keep their proportions at any aspect; ramps and
+    // images still span the frame
+    let stretch = synth.aspect / BASE_ASPECT;
+    let wide_uv = vec2<f32>((uv.x - 0.5) * stretch + 0.5, uv.y);
+    let wide_width = vec2<f32>(uv_width.x * stretch, uv_width.y);
 
-fn generate_source(source: u32, uv: vec2<f32>, time: f32, frequency: f32, phase: f32) -> f32 {
     if source == SRC_RAMP_H {
-        return uv.x;
+        return fract(uv.x + phase);
     } else if source == SRC_RAMP_V {
-        return uv.y;
+        return fract(uv.y + phase);
     } else if source == SRC_OSC_H {
-        return sin((uv.x * frequency + phase + time * 0.5) * TAU) * 0.5 + 0.5;
+        let contrast = pattern_contrast(wide_width.x * frequency);
+        return osc_wave(wide_uv.x * frequency + phase + loop_cycles(time, 0.5)) * 0.5 * contrast + 0.5;
     } else if source == SRC_OSC_V {
-        return sin((uv.y * frequency + phase + time * 0.5) * TAU) * 0.5 + 0.5;
+        let contrast = pattern_contrast(uv_width.y * frequency);
+        return osc_wave(uv.y * frequency + phase + loop_cycles(time, 0.5)) * 0.5 * contrast + 0.5;
     } else if source == SRC_NOISE_WHITE {
-        return hash(uv * 1000.0 + time * 100.0);
+        return hash(wide_uv * 1000.0 + time * 100.0 + noise_seed_offset());
     } else if source == SRC_NOISE_PINK {
-        var value = 0.0;
-        var amplitude = 0.5;
-        var freq = 1.0;
-        let offset = time * 0.5;
-        for (var i = 0; i < 5; i++) {
-            value += noise_value(uv * freq + offset) * amplitude;
-            amplitude *= 0.5;
-            freq *= 2.0;
-        }
-        return value;
+        return noise_power_law(wide_uv + noise_seed_offset(), time * 0.5, 0.70710678);
     } else if source == SRC_NOISE_BROWN {
-        var value = 0.0;
-        var amplitude = 0.6;
-        var freq = 0.5;
-        let offset = time * 0.2;
-        for (var i = 0; i < 3; i++) {
-            value += noise_value(uv * freq + offset) * amplitude;
-            amplitude *= 0.4;
-            freq *= 2.0;
-        }
-        return value;
+        return noise_power_law(wide_uv + noise_seed_offset(), time * 0.2, 0.5);
     } else if source == SRC_SHAPE_CIRCLE {
         let center = vec2<f32>(0.5);
-        let d = length(uv - center);
-        return 1.0 - smoothstep(0.25, 0.3, d);
+        let d = length(wide_uv - center);
+        let radius = 0.25 * synth.input_shape_size;
+        return 1.0 - smoothstep(radius, radius + shape_edge(0.2), d);
     } else if source == SRC_SHAPE_RECT {
         let center = vec2<f32>(0.5);
-        let d = abs(uv - center);
-        let outside = max(d.x - 0.2, d.y - 0.15);
-        return 1.0 - smoothstep(0.0, 0.02, outside);
+        let d = abs(wide_uv - center);
+        let outside = max(d.x - 0.2 * synth.input_shape_size, d.y - 0.15 * synth.input_shape_size);
+        return 1.0 - smoothstep(0.0, shape_edge(0.08), outside);
     } else if source == SRC_SHAPE_DIAMOND {
         let center = vec2<f32>(0.5);
-        let d = abs(uv - center);
+        let d = abs(wide_uv - center);
         let manhattan = d.x + d.y;
-        return 1.0 - smoothstep(0.25, 0.3, manhattan);
+        let radius = 0.25 * synth.input_shape_size;
+        return 1.0 - smoothstep(radius, radius + shape_edge(0.2), manhattan);
     } else if source == SRC_CHECKERBOARD {
         let scale = frequency;
-        let check = floor(uv.x * scale) + floor(uv.y * scale);
-        return fract(check * 0.5) * 2.0;
+        let check = floor(wide_uv.x * scale + phase) + floor(wide_uv.y * scale);
+        // Squares are half a cycle wide
+        let contrast = pattern_contrast(max(wide_width.x, wide_width.y) * scale * 0.5);
+        return mix(0.5, fract(check * 0.5) * 2.0, contrast);
+    } else if source == SRC_IMAGE {
+        // Frequency zooms (tiling above the base), phase pans across; the
+        // explicit LOD keeps this legal outside uniform control flow
+        let tiles = frequency / IMAGE_BASE_FREQUENCY;
+        let image_uv = (uv - 0.5) * tiles + 0.5 + vec2<f32>(phase, 0.0);
+        let color = textureSampleLevel(input_image, input_image_sampler, image_uv, 0.0).rgb;
+        return dot(color, vec3<f32>(0.299, 0.587, 0.114));
+    } else if source == SRC_CAMERA {
+        // Same framing as the image; the clamping sampler stretches the
+        // edge pixels instead of tiling when zoomed out
+        let tiles = frequency / IMAGE_BASE_FREQUENCY;
+        let camera_uv = (uv - 0.5) * tiles + 0.5 + vec2<f32>(phase, 0.0);
+        let color = textureSampleLevel(camera_texture, camera_sampler, camera_uv, 0.0).rgb;
+        return dot(color, vec3<f32>(0.299, 0.587, 0.114));
     }
     return 0.5;
 }
@@ -256,9 +416,13 @@ fn apply_input_rotation(uv: vec2<f32>, rotation: f32) -> vec2<f32> {
 }
 
 fn stage_input(uv: vec2<f32>, time: f32) -> f32 {
-    let rotated_uv = apply_input_rotation(uv, synth.input_rotation);
-    let source_a = generate_source(synth.input_source_a, rotated_uv, time, synth.input_frequency, synth.input_phase);
-    let source_b = generate_source(synth.input_source_b, rotated_uv, time, synth.input_frequency, synth.input_phase);
+    // Auto-spin: rotation advances on its own at input_spin_speed turns/sec
+    let rotated_uv = apply_input_rotation(uv, fract(synth.input_rotation + loop_cycles(time, synth.input_spin_speed)));
+    // Auto-scroll: phase advances on its own at input_phase_speed cycles/sec
+    let phase = fract(synth.input_phase + loop_cycles(time, synth.input_phase_speed));
+    let uv_width = fwidth(rotated_uv);
+    let source_a = generate_source(synth.input_source_a, rotated_uv, uv_width, time, synth.input_frequency, phase);
+    let source_b = generate_source(synth.input_source_b, rotated_uv, uv_width, time, synth.input_frequency, phase);
     return mix(source_a, source_b, synth.input_mix);
 }
 
@@ -269,14 +433,15 @@ fn stage_input(uv: vec2<f32>, time: f32) -> f32 {
 fn stage_geometry(uv: vec2<f32>, signal: f32, time: f32) -> vec2<f32> {
     var modified_uv = uv;
 
-    // Center for transformations
-    let centered = uv - 0.5;
+    // Pivot for scale and rotation
+    let pivot = vec2<f32>(synth.geo_rotate_center_x, synth.geo_rotate_center_y);
+    let centered = uv - pivot;
 
     // Apply scale
     var transformed = centered / synth.geo_scale;
 
     // Apply rotation
-    let rot_angle = synth.geo_rotation * TAU;
+    let rot_angle = fract(synth.geo_rotation + loop_cycles(time, synth.geo_spin_speed)) * TAU;
     let cos_r = cos(rot_angle);
     let sin_r = sin(rot_angle);
     transformed = vec2<f32>(
@@ -285,21 +450,23 @@ fn stage_geometry(uv: vec2<f32>, signal: f32, time: f32) -> vec2<f32> {
     );
 
     // Apply Lissajous modulation
-    let liss_phase = time * 0.5;
-    transformed.x += sin(liss_phase * 3.0 + uv.y * TAU) * synth.geo_lissajous_x * 0.1;
-    transformed.y += sin(liss_phase * 2.0 + uv.x * TAU) * synth.geo_lissajous_y * 0.1;
+    let liss_x = loop_cycles(time, 1.5 / TAU) * TAU;
+    let liss_y = loop_cycles(time, 1.0 / TAU) * TAU;
+    transformed.x += sin(liss_x + uv.y * TAU) * synth.geo_lissajous_x * 0.1;
+    transformed.y += sin(liss_y + uv.x * TAU) * synth.geo_lissajous_y * 0.1;
 
     // Apply wobbulation (Paik/Abe style)
-    let wobble_phase = time * synth.geo_wobble_freq;
-    transformed.x += sin(uv.y * 10.0 + wobble_phase) * synth.geo_wobbulate_h * 0.1;
-    transformed.y += sin(uv.x * 10.0 + wobble_phase * 1.3) * synth.geo_wobbulate_v * 0.1;
+    let wobble_h = loop_cycles(time, synth.geo_wobble_freq / TAU) * TAU;
+    let wobble_v = loop_cycles(time, synth.geo_wobble_freq * 1.3 / TAU) * TAU;
+    transformed.x += sin(uv.y * 10.0 + wobble_h) * synth.geo_wobbulate_h * 0.1;
+    transformed.y += sin(uv.x * 10.0 + wobble_v) * synth.geo_wobbulate_v * 0.1;
 
     // Apply Z-axis displacement (Rutt/Etra style)
     // Luminance displaces vertical position
     let displacement = (signal - 0.5) * synth.geo_z_displacement;
     transformed.y += displacement;
 
-    return transformed + 0.5;
+    return transformed + pivot;
 }
 
 // ============================================
@@ -316,6 +483,24 @@ fn quantize(x: f32, levels: f32) -> f32 {
     return floor(x * levels) / (levels - 1.0);
 }
 
+// 4x4 Bayer ordered-dither threshold, centered on 0 (-0.47 to 0.47)
+fn bayer4(pixel: vec2<f32>) -> f32 {
+    var matrix = array<f32, 16>(
+        0.0, 8.0, 2.0, 10.0,
+        12.0, 4.0, 14.0, 6.0,
+        3.0, 11.0, 1.0, 9.0,
+        15.0, 7.0, 13.0, 5.0
+    );
+    let p = vec2<u32>(pixel) % vec2<u32>(4u);
+    return (matrix[p.y * 4u + p.x] + 0.5) / 16.0 - 0.5;
+}
+
+// Offset added before quantizing to `levels` steps: up to one step of
+// ordered dither at full amount, so bands break into a retro pattern
+fn dither_offset(pixel: vec2<f32>, levels: f32) -> f32 {
+    return bayer4(pixel) * synth.amp_dither / max(levels, 1.0);
+}
+
 fn soft_clip(x: f32) -> f32 {
     return x / (1.0 + abs(x));
 }
@@ -327,7 +512,31 @@ fn solarize(x: f32, threshold: f32) -> f32 {
     return x;
 }
 
-fn stage_amplitude(signal: f32) -> f32 {
+// Solarize, gate and invert for one output channel (bit 0/1/2 = R/G/B)
+fn amplitude_channel(signal: f32, channel: u32) -> f32 {
+    var value = signal;
+    let bit = 1u << channel;
+
+    // Apply solarize
+    if synth.amp_solarize < 0.999 && (synth.amp_solarize_mask & bit) != 0u {
+        value = solarize(value, synth.amp_solarize);
+    }
+
+    // Apply gate
+    if synth.amp_gate_threshold > 0.001 {
+        value = select(0.0, value, value > synth.amp_gate_threshold);
+    }
+
+    // Apply invert
+    if (synth.amp_invert_mask & bit) != 0u {
+        value = 1.0 - value;
+    }
+
+    return clamp(value, 0.0, 1.0);
+}
+
+// Returns one signal per output channel (identical unless channel flags differ)
+fn stage_amplitude(signal: f32, pixel: vec2<f32>) -> vec3<f32> {
     var value = signal;
 
     // Apply folding
@@ -338,7 +547,8 @@ fn stage_amplitude(signal: f32) -> f32 {
 
     // Apply quantization
     if synth.amp_quantize_mix > 0.001 {
-        let quantized = quantize(value, synth.amp_quantize_levels);
+        let dithered = value + dither_offset(pixel, synth.amp_quantize_levels);
+        let quantized = quantize(dithered, synth.amp_quantize_levels);
         value = mix(value, quantized, synth.amp_quantize_mix);
     }
 
@@ -348,22 +558,11 @@ fn stage_amplitude(signal: f32) -> f32 {
         value = mix(value, clipped, synth.amp_soft_clip);
     }
 
-    // Apply solarize
-    if synth.amp_solarize < 0.999 {
-        value = solarize(value, synth.amp_solarize);
-    }
-
-    // Apply gate
-    if synth.amp_gate_threshold > 0.001 {
-        value = select(0.0, value, value > synth.amp_gate_threshold);
-    }
-
-    // Apply invert
-    if synth.amp_invert > 0.5 {
-        value = 1.0 - value;
-    }
-
-    return clamp(value, 0.0, 1.0);
+    return vec3<f32>(
+        amplitude_channel(value, 0u),
+        amplitude_channel(value, 1u),
+        amplitude_channel(value, 2u)
+    );
 }
 
 // ============================================
@@ -374,6 +573,11 @@ const COLOR_SPECTRUM: u32 = 0u;
 const COLOR_THRESHOLD: u32 = 1u;
 const COLOR_GRADIENT: u32 = 2u;
 const COLOR_MONOCHROME: u32 = 3u;
+const COLOR_PALETTE: u32 = 4u;
+
+const GRADIENT_LUMINANCE: u32 = 0u;
+const GRADIENT_RADIAL: u32 = 1u;
+const GRADIENT_ANGLE: u32 = 2u;
 
 fn colorize_spectrum(luma: f32) -> vec3<f32> {
     let h = luma * 6.0;
@@ -391,24 +595,67 @@ fn colorize_spectrum(luma: f32) -> vec3<f32> {
     return vec3<f32>(1.0, 0.0, q);
 }
 
-fn colorize_threshold(luma: f32, levels: f32) -> vec3<f32> {
-    let level = floor(luma * levels);
+fn colorize_threshold(luma: f32, levels: f32, pixel: vec2<f32>) -> vec3<f32> {
+    let level = floor(clamp(luma + dither_offset(pixel, levels), 0.0, 1.0) * levels);
     let t = level / levels;
+    if synth.color_palette_size > 0u {
+        // Imported palette: bands spread evenly over its colors
+        let size = synth.color_palette_size;
+        return synth.color_palette[min(u32(t * f32(size)), size - 1u)].rgb;
+    }
     return colorize_spectrum(t);
 }
 
-fn stage_colorize(signal: f32) -> vec3<f32> {
-    var color: vec3<f32>;
+// Lookup palette: luma picks an entry directly, so each color is a hard band
+fn colorize_lut(luma: f32) -> vec3<f32> {
+    let size = textureDimensions(palette_lut).x;
+    let index = min(u32(clamp(luma, 0.0, 1.0) * f32(size)), size - 1u);
+    return textureLoad(palette_lut, vec2<u32>(index, 0u), 0).rgb;
+}
+
+// Gradient lookup. Position sources pick the color from the (warped) frame
+// position and let the signal set brightness instead.
+fn colorize_gradient(signal: f32, uv: vec2<f32>) -> vec3<f32> {
+    if synth.color_gradient_source == GRADIENT_LUMINANCE {
+        return mix(synth.color_gradient_start, synth.color_gradient_end, signal);
+    }
 
+    let centered = uv - 0.5;
+    var t: f32;
+    if synth.color_gradient_source == GRADIENT_RADIAL {
+        t = clamp(length(centered) / 0.70710678, 0.0, 1.0);
+    } else {
+        t = atan2(centered.y, centered.x) / TAU + 0.5;
+    }
+    return mix(synth.color_gradient_start, synth.color_gradient_end, t) * signal;
+}
+
+fn colorize_signal(signal: f32, uv: vec2<f32>, pixel: vec2<f32>) -> vec3<f32> {
     if synth.color_mode == COLOR_SPECTRUM {
-        color = colorize_spectrum(signal);
+        return colorize_spectrum(signal);
     } else if synth.color_mode == COLOR_THRESHOLD {
-        color = colorize_threshold(signal, synth.color_levels);
+        return colorize_threshold(signal, synth.color_levels, pixel);
     } else if synth.color_mode == COLOR_GRADIENT {
-        color = mix(synth.color_gradient_start, synth.color_gradient_end, signal);
+        return colorize_gradient(signal, uv);
+    } else if synth.color_mode == COLOR_PALETTE {
+        return colorize_lut(signal);
+    }
+    // Monochrome (single phosphor tint)
+    return synth.color_mono_tint * signal;
+}
+
+fn stage_colorize(signal: vec3<f32>, uv: vec2<f32>, pixel: vec2<f32>) -> vec3<f32> {
+    var color: vec3<f32>;
+
+    if all(signal == signal.rrr) {
+        color = colorize_signal(signal.r, uv, pixel);
     } else {
-        // Monochrome
-        color = vec3<f32>(signal);
+        // Per-channel signals: each channel takes its own colorized component
+        color = vec3<f32>(
+            colorize_signal(signal.r, uv, pixel).r,
+            colorize_signal(signal.g, uv, pixel).g,
+            colorize_signal(signal.b, uv, pixel).b
+        );
     }
 
     // Apply hue offset
@@ -454,14 +701,9 @@ fn luma_key(color: vec3<f32>, threshold: f32, softness: f32) -> f32 {
 }
 
 fn stage_mixer(color: vec3<f32>, feedback: vec3<f32>) -> vec3<f32> {
+    // Power curve for smoother fade-in at low values
     let raw_mix = synth.mixer_feedback_mix;
-
-    if raw_mix < 0.001 {
-        return color;
-    }
-
-    // Apply power curve for smoother fade-in at low values
-    let mix_amount = raw_mix * raw_mix;
+    let fb_weight = raw_mix * raw_mix;
 
     var blended: vec3<f32>;
     let mode = synth.mixer_blend_mode;
@@ -495,34 +737,66 @@ fn stage_mixer(color: vec3<f32>, feedback: vec3<f32>) -> vec3<f32> {
         blended = feedback;
     }
 
-    // Mix between original color and blended result based on mix amount
-    var result = mix(color, blended, mix_amount);
-
-    // Layer opacity acts as an overall output gain (usually leave at 1.0)
-    if synth.mixer_layer_opacity < 0.999 {
-        result = result * synth.mixer_layer_opacity;
+    // Normalized: crossfade to the blend, opacity as output gain (see MixerStage docs)
+    if synth.mixer_normalize > 0.5 {
+        return mix(color, blended, fb_weight) * synth.mixer_layer_opacity;
     }
 
-    return result;
+    // Independent: weighted sum of new signal and blended feedback
+    let layer_weight = synth.mixer_layer_opacity;
+    return color * layer_weight + blended * fb_weight;
 }
 
 // ============================================
 // STAGE 6: FEEDBACK (sample previous frame)
 // ============================================
 
-fn stage_feedback(uv: vec2<f32>) -> vec3<f32> {
-    if synth.fb_enabled < 0.5 {
-        return vec3<f32>(0.0);
+const WRAP_REPEAT: u32 = 1u;
+const WRAP_MIRROR: u32 = 2u;
+
+// Fold a texel index outside the buffer back in, per the edge mode
+fn feedback_texel(texel: vec2<i32>, size: vec2<i32>) -> vec2<i32> {
+    if synth.fb_wrap == WRAP_REPEAT {
+        return ((texel % size) + size) % size;
+    } else if synth.fb_wrap == WRAP_MIRROR {
+        let period = size * 2;
+        let folded = ((texel % period) + period) % period;
+        return select(folded, period - 1 - folded, folded >= size);
     }
+    return clamp(texel, vec2<i32>(0), size - 1);
+}
+
+// Bilinear lookup of a feedback frame with full f32 weights. Hardware
+// filtering rounds the weights to a few bits of subpixel precision (8 on
+// most GPUs), so slow offsets and zooms crept along in visible steps.
+fn sample_frame(frame: texture_2d<f32>, uv: vec2<f32>) -> vec3<f32> {
+    let size = vec2<i32>(textureDimensions(frame));
+    let pos = uv * vec2<f32>(size) - 0.5;
+    let base = vec2<i32>(floor(pos));
+    let f = pos - floor(pos);
+
+    let a = textureLoad(frame, feedback_texel(base, size), 0).rgb;
+    let b = textureLoad(frame, feedback_texel(base + vec2<i32>(1, 0), size), 0).rgb;
+    let c = textureLoad(frame, feedback_texel(base + vec2<i32>(0, 1), size), 0).rgb;
+    let d = textureLoad(frame, feedback_texel(base + vec2<i32>(1, 1), size), 0).rgb;
+    return mix(mix(a, b, f.x), mix(c, d, f.x), f.y);
+}
+
+fn sample_feedback(uv: vec2<f32>) -> vec3<f32> {
+    return sample_frame(feedback_texture, uv);
+}
 
-    // Transform UV for feedback sampling
+// Map a UV through one zoom/rotate/offset transform (UV space, so
+// independent of the feedback buffer resolution; smaller buffers are
+// filtered up when sampled)
+fn feedback_uv(uv: vec2<f32>, zoom: f32, rotation: f32, offset: vec2<f32>) -> vec2<f32> {
     var centered = uv - 0.5;
 
     // Apply zoom
-    centered = centered / synth.fb_zoom;
+    centered = centered / zoom;
 
     // Apply rotation
-    let rot_angle = synth.fb_rotation * PI * 0.25; // Map to +/- pi/8
+    let rot_angle = rotation * PI * 0.25; // 1.0 = 45 degrees per frame
     let cos_r = cos(rot_angle);
     let sin_r = sin(rot_angle);
     centered = vec2<f32>(
@@ -531,10 +805,39 @@ fn stage_feedback(uv: vec2<f32>) -> vec3<f32> {
     );
 
     // Apply offset
-    var fb_uv = centered + 0.5 + vec2<f32>(synth.fb_offset_x, synth.fb_offset_y);
+    return centered + 0.5 + offset;
+}
+
+// Sample the previous frame through one transform
+fn feedback_tap(uv: vec2<f32>, zoom: f32, rotation: f32, offset: vec2<f32>) -> vec3<f32> {
+    return sample_feedback(feedback_uv(uv, zoom, rotation, offset));
+}
+
+fn stage_feedback(uv: vec2<f32>) -> vec3<f32> {
+    // Feedback off: the mixer blends against black, or the held frame (the
+    // buffer stops updating while held)
+    var bypass = vec3<f32>(0.0);
+    if synth.mixer_hold > 0.5 {
+        bypass = textureSample(feedback_texture, feedback_sampler, uv).rgb;
+    }
+    if synth.fb_enabled < 0.001 {
+        return bypass;
+    }
+
+    // Sample feedback, blending in the second tap if used
+    var fb_color = feedback_tap(uv, synth.fb_zoom, synth.fb_rotation, vec2<f32>(synth.fb_offset_x, synth.fb_offset_y));
+    if synth.fb_tap_mix > 0.001 {
+        let tap2_offset = vec2<f32>(synth.fb_tap2_offset_x, synth.fb_tap2_offset_y);
+        let tap2 = feedback_tap(uv, synth.fb_tap2_zoom, synth.fb_tap2_rotation, tap2_offset);
+        fb_color = mix(fb_color, tap2, synth.fb_tap_mix);
+    }
 
-    // Sample feedback
-    var fb_color = textureSample(feedback_texture, feedback_sampler, fb_uv).rgb;
+    // Echo: the oldest frame in the ring, through the main transform
+    if synth.fb_echo_mix > 0.001 {
+        let offset = vec2<f32>(synth.fb_offset_x, synth.fb_offset_y);
+        let echo = sample_frame(echo_texture, feedback_uv(uv, synth.fb_zoom, synth.fb_rotation, offset));
+        fb_color = mix(fb_color, echo, synth.fb_echo_mix);
+    }
 
     // Apply hue shift
     if synth.fb_hue_shift > 0.001 {
@@ -545,10 +848,17 @@ fn stage_feedback(uv: vec2<f32>) -> vec3<f32> {
     let luma = dot(fb_color, vec3<f32>(0.299, 0.587, 0.114));
     fb_color = mix(vec3<f32>(luma), fb_color, synth.fb_saturation);
 
-    // Apply decay
-    fb_color = fb_color * synth.fb_decay;
+    // Apply decay. Above 1 trails brighten instead; growth eases off as a
+    // channel nears white, so buildup glows rather than running away
+    if synth.fb_decay > 1.0 {
+        let headroom = clamp(vec3<f32>(1.0) - fb_color, vec3<f32>(0.0), vec3<f32>(1.0));
+        fb_color = fb_color * (1.0 + (synth.fb_decay - 1.0) * headroom);
+    } else {
+        fb_color = fb_color * synth.fb_decay;
+    }
 
-    return fb_color;
+    // fb_enabled ramps 0-1 when feedback is toggled, crossfading from bypass
+    return mix(bypass, fb_color, synth.fb_enabled);
 }
 
 // ============================================
@@ -559,7 +869,8 @@ fn stage_feedback(uv: vec2<f32>) -> vec3<f32> {
 const EFFECT_ON: f32 = 0.5;
 
 fn scanlines(uv: vec2<f32>, intensity: f32) -> f32 {
-    let line = sin(uv.y * 480.0 * PI) * 0.5 + 0.5;
+    // One dark line every other output row, whatever the resolution or supersampling
+    let line = sin(uv.y * synth.output_height * PI) * 0.5 + 0.5;
     return mix(1.0, line, intensity);
 }
 
@@ -593,11 +904,11 @@ fn vhs_wobble(uv: vec2<f32>, time: f32, amount: f32) -> vec2<f32> {
     var modified = uv;
 
     // Slow tape wobble
-    let wobble = sin(time * 0.5 + uv.y * 10.0) * amount * 0.005;
+    let wobble = sin(loop_cycles(time, 0.5 / TAU) * TAU + uv.y * 10.0) * amount * 0.005;
     modified.x += wobble;
 
     // Faster flutter
-    let flutter = sin(time * 30.0 + uv.y * 100.0) * amount * 0.001;
+    let flutter = sin(loop_cycles(time, 30.0 / TAU) * TAU + uv.y * 100.0) * amount * 0.001;
     modified.x += flutter;
 
     return modified;
@@ -643,6 +954,44 @@ fn cable_bandwidth_limit(uv: vec2<f32>, color: vec3<f32>, bandwidth: f32, use_fe
     }
 }
 
+// Composite encoding: luma and chroma (YIQ) low-passed separately along the
+// line, chroma over a wider span, so color bleeds past edges that stay sharp
+fn cable_composite_bandwidth(uv: vec2<f32>, color: vec3<f32>, luma_bw: f32, chroma_bw: f32, use_feedback: bool) -> vec3<f32> {
+    let to_yiq = mat3x3<f32>(
+        vec3<f32>(0.299, 0.596, 0.211),
+        vec3<f32>(0.587, -0.274, -0.523),
+        vec3<f32>(0.114, -0.322, 0.312),
+    );
+    let to_rgb = mat3x3<f32>(
+        vec3<f32>(1.0, 1.0, 1.0),
+        vec3<f32>(0.956, -0.272, -1.106),
+        vec3<f32>(0.621, -0.647, 1.703),
+    );
+    let center = to_yiq * color;
+
+    if !use_feedback {
+        // No neighbors to blur: fade chroma and soften luma contrast instead
+        let y = mix(center.x, 0.5, (1.0 - luma_bw) * 0.2);
+        return to_rgb * vec3<f32>(y, center.yz * mix(0.4, 1.0, chroma_bw));
+    }
+
+    let luma_span = (1.0 - luma_bw) * 0.006;
+    let chroma_span = (1.0 - chroma_bw) * 0.03;
+    var y = center.x;
+    var iq = center.yz;
+    // Two taps either side, from the previous frame like the other cable effects
+    for (var i = 1; i <= 2; i++) {
+        let t = f32(i) * 0.5;
+        let luma_l = to_yiq * textureSample(feedback_texture, feedback_sampler, uv - vec2<f32>(luma_span * t, 0.0)).rgb;
+        let luma_r = to_yiq * textureSample(feedback_texture, feedback_sampler, uv + vec2<f32>(luma_span * t, 0.0)).rgb;
+        let chroma_l = to_yiq * textureSample(feedback_texture, feedback_sampler, uv - vec2<f32>(chroma_span * t, 0.0)).rgb;
+        let chroma_r = to_yiq * textureSample(feedback_texture, feedback_sampler, uv + vec2<f32>(chroma_span * t, 0.0)).rgb;
+        y += luma_l.x + luma_r.x;
+        iq += chroma_l.yz + chroma_r.yz;
+    }
+    return to_rgb * vec3<f32>(y / 5.0, iq / 5.0);
+}
+
 fn cable_ghosting(uv: vec2<f32>, color: vec3<f32>, amount: f32, use_feedback: bool) -> vec3<f32> {
     if use_feedback {
         // RF ghosting using feedback texture
@@ -656,77 +1005,143 @@ fn cable_ghosting(uv: vec2<f32>, color: vec3<f32>, amount: f32, use_feedback: bo
     }
 }
 
-fn stage_output(uv: vec2<f32>, color: vec3<f32>, time: f32) -> vec3<f32> {
-    var modified_uv = uv;
-    var output_color = color;
+// Picture and position moving through the output chain (VHS bends the UV
+// that later effects see)
+struct OutputSignal {
+    uv: vec2<f32>,
+    color: vec3<f32>,
+}
 
-    // Check if feedback is actually active (enabled AND mix > 0)
-    let feedback_active = synth.fb_enabled > EFFECT_ON && synth.mixer_feedback_mix > 0.01;
+const EFFECT_VHS: u32 = 0u;
+const EFFECT_CABLE: u32 = 1u;
+const EFFECT_CRT: u32 = 2u;
+
+// Snow for VHS/cable noise, -1 to 1. With persistence the field is redrawn
+// less often and crossfaded between draws, so grain lingers like tape instead
+// of strobing; 0 keeps a fresh draw every frame.
+fn tape_noise(p: vec2<f32>, time: f32, speed: f32) -> f32 {
+    let persistence = synth.out_noise_persistence;
+    if persistence < 0.001 {
+        return hash(p + time * speed) * 2.0 - 1.0;
+    }
 
-    // Effects applied in order: VHS -> Cable -> CRT
+    // Redraws per second, 60 down to 2
+    let rate = mix(60.0, 2.0, sqrt(persistence));
+    let t = time * rate;
+    let step = floor(t);
+    let w = smoothstep(0.0, 1.0, t - step);
+    let a = hash(p + step * speed / rate) * 2.0 - 1.0;
+    let b = hash(p + (step + 1.0) * speed / rate) * 2.0 - 1.0;
+    // Renormalize so the crossfade doesn't dim the grain mid-blend
+    return mix(a, b, w) / sqrt((1.0 - w) * (1.0 - w) + w * w);
+}
 
-    // === VHS EFFECTS ===
-    if synth.out_vhs_enabled > EFFECT_ON {
-        // VHS tracking errors
-        if synth.out_tracking > 0.001 {
-            modified_uv = vhs_tracking(modified_uv, time, synth.out_tracking);
-        }
+fn output_vhs(signal: OutputSignal, time: f32, feedback_active: bool) -> OutputSignal {
+    var out = signal;
 
-        // VHS tape wobble
-        if synth.out_tape_wobble > 0.001 {
-            modified_uv = vhs_wobble(modified_uv, time, synth.out_tape_wobble);
-        }
+    // VHS tracking errors
+    if synth.out_tracking > 0.001 {
+        out.uv = vhs_tracking(out.uv, time, synth.out_tracking);
+    }
 
-        // VHS chroma/luma separation
-        if synth.out_chroma_shift > 0.0001 {
-            output_color = vhs_chroma_shift(modified_uv, output_color, synth.out_chroma_shift, feedback_active);
-        }
+    // VHS tape wobble
+    if synth.out_tape_wobble > 0.001 {
+        out.uv = vhs_wobble(out.uv, time, synth.out_tape_wobble);
+    }
 
-        // VHS noise
-        if synth.out_vhs_noise > 0.001 {
-            let noise = hash(modified_uv * 300.0 + time * 50.0) * 2.0 - 1.0;
-            output_color = output_color + vec3<f32>(noise * synth.out_vhs_noise);
-        }
+    // VHS chroma/luma separation
+    if synth.out_chroma_shift > 0.0001 {
+        out.color = vhs_chroma_shift(out.uv, out.color, synth.out_chroma_shift, feedback_active);
     }
 
-    // === CABLE EFFECTS ===
-    if synth.out_cable_enabled > EFFECT_ON {
-        // Bandwidth limiting
-        if synth.out_bandwidth < 0.999 {
-            output_color = cable_bandwidth_limit(modified_uv, output_color, synth.out_bandwidth, feedback_active);
-        }
+    // VHS noise
+    if synth.out_vhs_noise > 0.001 {
+        let noise = tape_noise(out.uv * 300.0, time, 50.0);
+        out.color = out.color + vec3<f32>(noise * synth.out_vhs_noise);
+    }
 
-        // RF ghosting
-        if synth.out_ghosting > 0.001 {
-            output_color = cable_ghosting(modified_uv, output_color, synth.out_ghosting, feedback_active);
-        }
+    return out;
+}
 
-        // Cable noise
-        if synth.out_cable_noise > 0.001 {
-            let noise = hash(modified_uv * 400.0 + time * 80.0) * 2.0 - 1.0;
-            output_color = output_color + vec3<f32>(noise * synth.out_cable_noise);
-        }
+fn output_cable(signal: OutputSignal, time: f32, feedback_active: bool) -> OutputSignal {
+    var out = signal;
+
+    // Bandwidth limiting
+    if synth.out_bandwidth < 0.999 {
+        out.color = cable_bandwidth_limit(out.uv, out.color, synth.out_bandwidth, feedback_active);
     }
 
-    // === CRT EFFECTS ===
-    if synth.out_crt_enabled > EFFECT_ON {
-        // Scanlines
-        if synth.out_scanlines > 0.001 {
-            output_color = output_color * scanlines(modified_uv, synth.out_scanlines);
-        }
+    // Separate luma/chroma bandwidth (NTSC color smear)
+    if synth.out_luma_bandwidth < 0.999 || synth.out_chroma_bandwidth < 0.999 {
+        out.color = cable_composite_bandwidth(
+            out.uv,
+            out.color,
+            synth.out_luma_bandwidth,
+            synth.out_chroma_bandwidth,
+            feedback_active,
+        );
+    }
 
-        // Vignette
-        if synth.out_vignette > 0.001 {
-            output_color = output_color * vignette(modified_uv, synth.out_vignette);
-        }
+    // RF ghosting
+    if synth.out_ghosting > 0.001 {
+        out.color = cable_ghosting(out.uv, out.color, synth.out_ghosting, feedback_active);
+    }
+
+    // Cable noise
+    if synth.out_cable_noise > 0.001 {
+        let noise = tape_noise(out.uv * 400.0, time, 80.0);
+        out.color = out.color + vec3<f32>(noise * synth.out_cable_noise);
+    }
 
-        // Bloom
-        if synth.out_bloom > 0.001 {
-            output_color = phosphor_bloom(output_color, synth.out_bloom);
+    return out;
+}
+
+fn output_crt(signal: OutputSignal) -> OutputSignal {
+    var out = signal;
+
+    // Scanlines
+    if synth.out_scanlines > 0.001 {
+        out.color = out.color * scanlines(out.uv, synth.out_scanlines);
+    }
+
+    // Vignette
+    if synth.out_vignette > 0.001 {
+        // Fade edges toward the vignette color (black = plain darkening)
+        out.color = mix(synth.out_vignette_color, out.color, vignette(out.uv, synth.out_vignette));
+    }
+
+    // Bloom
+    if synth.out_bloom > 0.001 {
+        out.color = phosphor_bloom(out.color, synth.out_bloom);
+    }
+
+    return out;
+}
+
+// Crossfade an effect in by its enable amount (ramped when toggled)
+fn mix_signal(dry: OutputSignal, wet: OutputSignal, amount: f32) -> OutputSignal {
+    return OutputSignal(mix(dry.uv, wet.uv, amount), mix(dry.color, wet.color, amount));
+}
+
+fn stage_output(uv: vec2<f32>, color: vec3<f32>, time: f32) -> vec3<f32> {
+    var signal = OutputSignal(uv, color);
+
+    // Check if feedback is actually active (enabled AND mix > 0)
+    let feedback_active = synth.fb_enabled > EFFECT_ON && synth.mixer_feedback_mix > 0.01;
+
+    // Effects applied in the patch's chain order (2 bits per slot, first lowest)
+    for (var slot = 0u; slot < 3u; slot++) {
+        let effect = (synth.out_order >> (slot * 2u)) & 3u;
+        if effect == EFFECT_VHS && synth.out_vhs_enabled > 0.001 {
+            signal = mix_signal(signal, output_vhs(signal, time, feedback_active), synth.out_vhs_enabled);
+        } else if effect == EFFECT_CABLE && synth.out_cable_enabled > 0.001 {
+            signal = mix_signal(signal, output_cable(signal, time, feedback_active), synth.out_cable_enabled);
+        } else if effect == EFFECT_CRT && synth.out_crt_enabled > 0.001 {
+            signal = mix_signal(signal, output_crt(signal), synth.out_crt_enabled);
         }
     }
 
-    return clamp(output_color, vec3<f32>(0.0), vec3<f32>(1.0));
+    return clamp(signal.color, vec3<f32>(0.0), vec3<f32>(1.0));
 }
 
 // ============================================
@@ -737,6 +1152,8 @@ fn stage_output(uv: vec2<f32>, color: vec3<f32>, time: f32) -> vec3<f32> {
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let uv = in.uv;
     let time = synth.time;
+    // Output pixel, so dither patterns keep their size when supersampling
+    let pixel = in.clip_position.xy / synth.pixel_scale;
 
     // Stage 1: Generate input signal
     let input_signal = stage_input(uv, time);
@@ -748,10 +1165,18 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let geo_signal = stage_input(geo_uv, time);
 
     // Stage 3: Apply amplitude processing
-    let amp_signal = stage_amplitude(geo_signal);
+    let raw_amp = stage_amplitude(geo_signal, pixel);
+
+    // Probe pass: the raw amplitude signal, read back to drive auto-normalize
+    if synth.signal_probe > 0.5 {
+        return vec4<f32>(raw_amp, 1.0);
+    }
+
+    // Auto-normalize: stretch the measured signal range to 0-1 (identity when off)
+    let amp_signal = clamp((raw_amp - synth.amp_norm_offset) * synth.amp_norm_scale, vec3<f32>(0.0), vec3<f32>(1.0));
 
     // Stage 4: Colorize
-    let color = stage_colorize(amp_signal);
+    let color = stage_colorize(amp_signal, geo_uv, pixel);
 
     // Stage 6: Get feedback (needs to happen before mixer)
     let feedback = stage_feedback(uv);
@@ -762,5 +1187,13 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // Stage 7: Output emulation
     let output = stage_output(uv, mixed, time);
 
-    return vec4<f32>(output, 1.0);
+    // Master fade (independent of all stages)
+    return vec4<f32>(output * synth.master_fade, 1.0);
+}
+
+// Resample a texture into a target of a different size (feedback downscale).
+// The source is bound in place of the feedback texture.
+@fragment
+fn fs_blit(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(textureSample(feedback_texture, feedback_sampler, in.uv).rgb, 1.0);
 }
@@ -0,0 +1,34 @@
+// Fallback shader used when lite.wgsl fails to compile: same entry points and
+// bindings, drawing a plain horizontal ramp so the app stays usable
+
+@group(1) @binding(0)
+var feedback_texture: texture_2d<f32>;
+
+@group(1) @binding(1)
+var feedback_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(position, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(vec3<f32>(in.uv.x), 1.0);
+}
+
+@fragment
+fn fs_blit(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(textureSample(feedback_texture, feedback_sampler, in.uv).rgb, 1.0);
+}
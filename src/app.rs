@@ -1,8 +1,22 @@
 //! Application state management
 
-use crate::automation::AutomationState;
-use crate::presets::{builtin_presets, Preset};
-use crate::synth::SynthState;
+use crate::audio::AudioInput;
+use crate::automation::{apply_mod_routes, AutomationState, GestureRecorder, ModRoute, MAX_MOD_ROUTES};
+use crate::camera::Camera;
+#[cfg(feature = "shell")]
+use crate::config::AppConfig;
+use crate::midi::{MidiInput, MidiMap};
+use crate::osc::{OscServer, OscValue};
+use crate::texture_share::TextureSender;
+use crate::presets::{builtin_presets, Preset, Scenes, Session, MIDI_FILE, SCENES_FILE};
+use crate::synth::{
+    AmplitudeStage, ColorMode, ColorizeStage, EffectMix, FeedbackStage, FrameParams, GeometryStage, InputSolo, InputSource,
+    InputStage, MixerStage, OutputStage, ParamSpec, SynthState, SynthUniforms,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 
 /// Which stage panel is currently selected in the UI
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,8 +36,61 @@ impl Default for SelectedStage {
     }
 }
 
-/// Bezel position settings
+/// One stage panel's settings, copied to paste into the same stage of
+/// this or another patch
 #[derive(Clone)]
+pub enum StageClipboard {
+    Input(InputStage),
+    Geometry(GeometryStage),
+    Amplitude(AmplitudeStage),
+    Colorize(ColorizeStage),
+    /// The FB panel holds both the mixer and feedback controls
+    Feedback(MixerStage, FeedbackStage),
+    Output(OutputStage),
+}
+
+impl StageClipboard {
+    /// Copy the settings shown on `stage`'s panel
+    fn copy(synth: &SynthState, stage: SelectedStage) -> Self {
+        match stage {
+            SelectedStage::Input => Self::Input(synth.input),
+            SelectedStage::Geometry => Self::Geometry(synth.geometry),
+            SelectedStage::Amplitude => Self::Amplitude(synth.amplitude),
+            SelectedStage::Colorize => Self::Colorize(synth.colorize.clone()),
+            SelectedStage::Mixer | SelectedStage::Feedback => Self::Feedback(synth.mixer, synth.feedback),
+            SelectedStage::Output => Self::Output(synth.output),
+        }
+    }
+
+    /// Whether this was copied from a panel of the same kind as `stage`
+    pub fn fits(&self, stage: SelectedStage) -> bool {
+        matches!(
+            (self, stage),
+            (Self::Input(_), SelectedStage::Input)
+                | (Self::Geometry(_), SelectedStage::Geometry)
+                | (Self::Amplitude(_), SelectedStage::Amplitude)
+                | (Self::Colorize(_), SelectedStage::Colorize)
+                | (Self::Feedback(..), SelectedStage::Mixer | SelectedStage::Feedback)
+                | (Self::Output(_), SelectedStage::Output)
+        )
+    }
+
+    /// Stage name for the Paste button's hover text
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Input(_) => "input",
+            Self::Geometry(_) => "geometry",
+            Self::Amplitude(_) => "amplitude",
+            Self::Colorize(_) => "colorize",
+            Self::Feedback(..) => "mixer and feedback",
+            Self::Output(_) => "output",
+        }
+    }
+}
+
+/// Bezel position settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct BezelSettings {
     pub left: f32,
     pub top: f32,
@@ -48,6 +115,524 @@ impl Default for BezelSettings {
     }
 }
 
+/// Most feedback steps run in one displayed frame
+const MAX_FEEDBACK_STEPS: u32 = 4;
+
+/// Seconds feedback and output effects take to fade in or out when toggled
+const EFFECT_FADE_SECS: f32 = 0.3;
+
+/// Synth resolutions offered in Settings, at 4:3 like the bezel screen;
+/// other aspects keep the height
+pub const RESOLUTIONS: [[u32; 2]; 4] = [[320, 240], [640, 480], [960, 720], [1280, 960]];
+
+/// Shape of the synth buffer; the resolution setting picks its height
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SynthAspect {
+    /// 4:3, filling the bezel screen
+    Standard,
+    /// 16:9, for widescreen projection (letterboxed in the bezel)
+    Wide,
+}
+
+impl Default for SynthAspect {
+    fn default() -> Self {
+        Self::Standard
+    }
+}
+
+impl SynthAspect {
+    pub const ALL: [Self; 2] = [Self::Standard, Self::Wide];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Standard => "4:3",
+            Self::Wide => "16:9",
+        }
+    }
+
+    /// Buffer size with `height` lines, the width rounded to an even number
+    /// (854x480, 1280x720 at 16:9)
+    pub fn size(self, height: u32) -> [u32; 2] {
+        let (w, h) = match self {
+            Self::Standard => (4, 3),
+            Self::Wide => (16, 9),
+        };
+        [(height * w / h + 1) / 2 * 2, height]
+    }
+}
+
+/// Seconds a toast message stays up
+const TOAST_SECS: f32 = 3.0;
+
+/// Short-lived status message
+#[derive(Clone)]
+pub struct Toast {
+    pub message: String,
+    /// Seconds left on screen (wall time, so toasts still clear while paused)
+    pub remaining: f32,
+}
+
+/// Scanline scope settings
+#[derive(Clone)]
+pub struct ScopeSettings {
+    pub enabled: bool,
+    /// Output row that gets read back and plotted
+    pub row: u32,
+}
+
+impl Default for ScopeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            row: 240,
+        }
+    }
+}
+
+/// Accessibility limiter: caps how fast overall brightness can rise, so
+/// strobes and hard cuts to bright frames fade in instead of flashing
+#[derive(Clone)]
+pub struct FlashLimiter {
+    pub enabled: bool,
+    /// Fastest allowed rise in mean brightness, in full-range per second
+    pub max_rise: f32,
+    /// Brightness currently allowed on screen
+    level: f32,
+    /// Gain applied to this frame (1 = untouched)
+    pub gain: f32,
+}
+
+impl Default for FlashLimiter {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_rise: 1.0,
+            level: 0.0,
+            gain: 1.0,
+        }
+    }
+}
+
+impl FlashLimiter {
+    /// Set the gain for a frame whose unlimited mean brightness is `mean`.
+    /// Drops pass straight through; rises are slowed to `max_rise`.
+    pub fn update(&mut self, mean: f32, dt: f32) {
+        self.level = mean.min(self.level + self.max_rise * dt);
+        self.gain = if mean > self.level { self.level / mean } else { 1.0 };
+    }
+}
+
+/// Feedback controls most likely to blow out when yanked
+const JUMP_GUARDED_PARAMS: [&str; 6] = [
+    "mixer.feedback_mix",
+    "feedback.zoom",
+    "feedback.rotation",
+    "feedback.decay",
+    "feedback.tap2_zoom",
+    "feedback.tap2_rotation",
+];
+
+/// Single-frame change (fraction of the slider range) that counts as a jump
+const JUMP_THRESHOLD: f32 = 0.1;
+
+/// Feedback gain right after a jump, recovering to 1 at `JUMP_RECOVERY` per second
+const JUMP_DAMP: f32 = 0.7;
+const JUMP_RECOVERY: f32 = 2.0;
+
+/// Damps feedback briefly when a risky feedback control jumps in one frame,
+/// so a hard drag fades the trails a little instead of spiking
+#[derive(Clone)]
+pub struct JumpGuard {
+    pub enabled: bool,
+    /// Values of `JUMP_GUARDED_PARAMS` last frame
+    last: [f32; JUMP_GUARDED_PARAMS.len()],
+    /// Current feedback gain (1 = untouched)
+    pub damp: f32,
+}
+
+impl Default for JumpGuard {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            last: [f32::NAN; JUMP_GUARDED_PARAMS.len()],
+            damp: 1.0,
+        }
+    }
+}
+
+impl JumpGuard {
+    pub fn update(&mut self, synth: &SynthState, dt: f32) {
+        self.damp = (self.damp + JUMP_RECOVERY * dt).min(1.0);
+
+        for (key, last) in JUMP_GUARDED_PARAMS.iter().zip(&mut self.last) {
+            let (Some(spec), Some(value)) = (ParamSpec::find(key), synth.param(key)) else {
+                continue;
+            };
+            // NaN on the first frame never counts as a jump
+            if (value - *last).abs() > JUMP_THRESHOLD * (spec.max - spec.min) {
+                self.damp = self.damp.min(JUMP_DAMP);
+            }
+            *last = value;
+        }
+    }
+}
+
+/// One-pole smoothing of every continuous parameter, so jumps from MIDI,
+/// OSC, stepped LFOs or a fast drag glide instead of popping. Switches and
+/// menus (anything not in `PARAMS`) change at once.
+#[derive(Clone, Default)]
+pub struct SmoothedState {
+    /// Time constant in seconds (0 = off, render the patch as is)
+    pub secs: f32,
+    /// Patch as rendered, easing toward the live one (None while off)
+    state: Option<SynthState>,
+}
+
+impl SmoothedState {
+    /// Ease toward `target` by `dt` seconds
+    pub fn update(&mut self, target: &SynthState, dt: f32) {
+        if self.secs <= 0.0 {
+            self.state = None;
+            return;
+        }
+
+        let mut next = target.clone();
+        if let Some(current) = &self.state {
+            let k = 1.0 - (-dt / self.secs).exp();
+            for spec in crate::synth::PARAMS {
+                if let (Some(from), Some(value)) = (current.param(spec.key), next.param_mut(spec.key)) {
+                    *value = from + (*value - from) * k;
+                }
+            }
+        }
+        self.state = Some(next);
+    }
+
+    /// Jump straight to the next target (for hard cuts)
+    pub fn snap(&mut self) {
+        self.state = None;
+    }
+
+    /// The smoothed patch, or `target` itself while smoothing is off
+    pub fn get<'a>(&'a self, target: &'a SynthState) -> &'a SynthState {
+        self.state.as_ref().unwrap_or(target)
+    }
+}
+
+/// Tap intervals averaged for tap tempo
+const TAP_INTERVALS: usize = 4;
+
+/// A gap this long (seconds) between taps starts a new count
+const TAP_RESET_SECS: f32 = 2.0;
+
+/// Tap tempo: the beat from the last few taps of the TAP button
+#[derive(Clone, Default)]
+pub struct TapTempo {
+    /// Recent taps, oldest first (at most `TAP_INTERVALS` + 1)
+    taps: Vec<instant::Instant>,
+}
+
+impl TapTempo {
+    /// Record a tap at `now`; returns the tempo once there are two taps in a
+    /// row, averaged over up to the last `TAP_INTERVALS` intervals
+    pub fn tap(&mut self, now: instant::Instant) -> Option<f32> {
+        if self
+            .taps
+            .last()
+            .is_some_and(|&last| now.duration_since(last).as_secs_f32() > TAP_RESET_SECS)
+        {
+            self.taps.clear();
+        }
+        self.taps.push(now);
+        if self.taps.len() > TAP_INTERVALS + 1 {
+            self.taps.remove(0);
+        }
+
+        let (first, last) = (self.taps.first()?, self.taps.last()?);
+        let intervals = self.taps.len() - 1;
+        if intervals == 0 {
+            return None;
+        }
+        let beat = last.duration_since(*first).as_secs_f32() / intervals as f32;
+        Some((60.0 / beat).clamp(30.0, 240.0))
+    }
+}
+
+/// Chaos macro response per parameter: (key, value at full chaos, curve
+/// exponent). Exponents above 1 hold a parameter back until the knob is well
+/// up, so the ride builds: fold and feedback first, wobble next, snow last.
+const CHAOS_CURVES: [(&str, f32, f32); 6] = [
+    ("amplitude.fold_mix", 1.0, 1.0),
+    ("mixer.feedback_mix", 0.95, 1.5),
+    ("geometry.wobbulate_h", 0.6, 2.0),
+    ("geometry.wobbulate_v", 0.6, 2.0),
+    ("output.vhs_noise", 0.3, 3.0),
+    ("output.cable_noise", 0.12, 3.0),
+];
+
+/// Number of bins in the output histogram
+pub const HISTOGRAM_BINS: usize = 64;
+
+/// Output rows sampled for the histogram (kept low so readback stays cheap)
+pub const HISTOGRAM_ROWS: u32 = 48;
+
+/// Output levels histogram, normalized so each channel sums to 1
+#[derive(Clone)]
+pub struct Histogram {
+    pub luma: [f32; HISTOGRAM_BINS],
+    pub rgb: [[f32; HISTOGRAM_BINS]; 3],
+    /// Fraction of samples at full black / full white
+    pub clipped_black: f32,
+    pub clipped_white: f32,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            luma: [0.0; HISTOGRAM_BINS],
+            rgb: [[0.0; HISTOGRAM_BINS]; 3],
+            clipped_black: 0.0,
+            clipped_white: 0.0,
+        }
+    }
+}
+
+impl Histogram {
+    /// Build from read-back rows, taking every `stride`th pixel
+    pub fn from_rows<'a>(rows: impl IntoIterator<Item = &'a Vec<[f32; 3]>>, stride: usize) -> Self {
+        let bin = |v: f32| ((v.clamp(0.0, 1.0) * HISTOGRAM_BINS as f32) as usize).min(HISTOGRAM_BINS - 1);
+
+        let mut hist = Self::default();
+        let mut count = 0usize;
+        for row in rows {
+            for &px in row.iter().step_by(stride.max(1)) {
+                let luma = crate::renderer::luminance(px);
+                hist.luma[bin(luma)] += 1.0;
+                for (channel, &v) in hist.rgb.iter_mut().zip(px.iter()) {
+                    channel[bin(v)] += 1.0;
+                }
+                if luma <= 0.002 {
+                    hist.clipped_black += 1.0;
+                } else if luma >= 0.998 {
+                    hist.clipped_white += 1.0;
+                }
+                count += 1;
+            }
+        }
+
+        if count > 0 {
+            let scale = 1.0 / count as f32;
+            for v in hist.luma.iter_mut().chain(hist.rgb.iter_mut().flatten()) {
+                *v *= scale;
+            }
+            hist.clipped_black *= scale;
+            hist.clipped_white *= scale;
+        }
+        hist
+    }
+}
+
+/// Frames averaged for the performance overlay's rolling average
+const PERF_WINDOW: usize = 120;
+
+/// How quickly the overlay's smoothed FPS follows the frame rate (per second)
+const PERF_SMOOTHING: f32 = 4.0;
+
+/// Frame timing for the performance overlay
+#[derive(Clone, Default)]
+pub struct PerfStats {
+    pub enabled: bool,
+    /// Smoothed wall-clock frame time, in seconds
+    pub frame_secs: f32,
+    /// Last PERF_WINDOW frame times, oldest first
+    frames: VecDeque<f32>,
+    /// Synth render pass time from the last timed frame
+    pub gpu_ms: Option<f32>,
+    /// Whether the GPU supports timestamp queries for gpu_ms
+    pub gpu_supported: bool,
+}
+
+impl PerfStats {
+    /// Record one frame's wall-clock time
+    pub fn update(&mut self, dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+        if self.frame_secs <= 0.0 {
+            self.frame_secs = dt;
+        } else {
+            let k = 1.0 - (-PERF_SMOOTHING * dt).exp();
+            self.frame_secs += (dt - self.frame_secs) * k;
+        }
+        if self.frames.len() == PERF_WINDOW {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(dt);
+    }
+
+    /// Smoothed frames per second
+    pub fn fps(&self) -> f32 {
+        if self.frame_secs > 0.0 { 1.0 / self.frame_secs } else { 0.0 }
+    }
+
+    /// Average frames per second over the last PERF_WINDOW frames
+    pub fn average_fps(&self) -> f32 {
+        let total: f32 = self.frames.iter().sum();
+        if total > 0.0 { self.frames.len() as f32 / total } else { 0.0 }
+    }
+}
+
+/// An in-progress morph from the patch as it was toward a preset
+#[derive(Debug, Clone)]
+pub struct Morph {
+    pub from: SynthState,
+    /// The preset's patch (palette loaded)
+    pub to: SynthState,
+    /// Preset index, selected once the morph completes
+    pub target: usize,
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+impl Morph {
+    /// 0 at the start, 1 when complete
+    pub fn progress(&self) -> f32 {
+        (self.elapsed / self.duration).min(1.0)
+    }
+}
+
+/// Auto-morph playlist: holds each preset for `hold_secs`, then morphs to
+/// the next one (wrapping around)
+#[derive(Debug, Clone)]
+pub struct Playlist {
+    pub hold_secs: f32,
+    /// Time spent on the current preset since the last morph finished
+    pub elapsed: f32,
+}
+
+/// Patch `t` of the way from `from` to `to`: numbers (including colors)
+/// interpolate, while enums, switches and integer fields such as the noise
+/// seed switch over at the halfway mark
+fn morph_state(from: &SynthState, to: &SynthState, t: f32) -> SynthState {
+    fn blend(a: &Value, b: &Value, t: f64) -> Value {
+        match (a, b) {
+            (Value::Number(x), Value::Number(y)) if x.is_f64() && y.is_f64() => {
+                let (x, y) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+                Value::from(x + (y - x) * t)
+            }
+            (Value::Array(xs), Value::Array(ys)) if xs.len() == ys.len() => {
+                Value::Array(xs.iter().zip(ys).map(|(x, y)| blend(x, y, t)).collect())
+            }
+            (Value::Object(xs), Value::Object(ys)) => Value::Object(
+                xs.iter()
+                    .map(|(key, x)| (key.clone(), ys.get(key).map_or_else(|| x.clone(), |y| blend(x, y, t))))
+                    .collect(),
+            ),
+            _ if t < 0.5 => a.clone(),
+            _ => b.clone(),
+        }
+    }
+
+    let (Ok(a), Ok(b)) = (serde_json::to_value(from), serde_json::to_value(to)) else {
+        return to.clone();
+    };
+    let mut state: SynthState = serde_json::from_value(blend(&a, &b, t as f64)).unwrap_or_else(|_| to.clone());
+    // The loaded palettes aren't serialized; they follow the paths' switch
+    let palette_side = if t < 0.5 { from } else { to };
+    state.colorize.palette = palette_side.colorize.palette.clone();
+    state.colorize.lut = palette_side.colorize.lut.clone();
+    state
+}
+
+/// An in-progress image sequence export
+#[derive(Debug, Clone)]
+pub struct SequenceExport {
+    /// Folder the numbered PNGs go into
+    pub dir: PathBuf,
+    /// Next frame to write, and how many in all
+    pub frame: u32,
+    pub frames: u32,
+    pub fps: f32,
+}
+
+/// Longest GIF recording, in seconds (frames are held in memory)
+pub const GIF_MAX_SECS: f32 = 5.0;
+
+/// Frames wider than this are scaled down before they're stored
+const GIF_MAX_WIDTH: u32 = 640;
+
+/// Memory the stored frames may take; a recording stops and saves at this
+/// or `GIF_MAX_SECS`, whichever comes first
+const GIF_MAX_BYTES: usize = 256 << 20;
+
+/// A GIF being recorded: frames captured at `fps`, at most `GIF_MAX_WIDTH` wide
+pub struct GifRecording {
+    frames: Vec<image::RgbaImage>,
+    /// Bytes held in `frames`
+    bytes: usize,
+    pub fps: f32,
+    /// Seconds recorded so far
+    pub elapsed: f32,
+    /// Seconds until the next capture
+    next_capture: f32,
+}
+
+/// Where a file kept between runs (`SCENES_FILE`, `MIDI_FILE`) is saved:
+/// beside the config, or the working directory without the shell
+#[cfg(feature = "shell")]
+fn user_file(name: &str) -> PathBuf {
+    crate::config::user_file(name)
+}
+
+#[cfg(not(feature = "shell"))]
+fn user_file(name: &str) -> PathBuf {
+    PathBuf::from(name)
+}
+
+/// A saved `user_file` to load, falling back to the working directory
+/// earlier versions saved it in
+fn find_user_file(name: &str) -> Option<PathBuf> {
+    [user_file(name), PathBuf::from(name)].into_iter().find(|path| path.is_file())
+}
+
+fn create_parent(path: &Path) -> Result<(), std::io::Error> {
+    match path.parent() {
+        Some(dir) => std::fs::create_dir_all(dir),
+        None => Ok(()),
+    }
+}
+
+/// Write `frames` as an endlessly looping GIF, each quantized to its own
+/// 256-color palette
+fn encode_gif(path: &Path, frames: Vec<image::RgbaImage>, fps: f32) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(first) = frames.first() else {
+        return Ok(());
+    };
+    let (width, height) = (first.width() as u16, first.height() as u16);
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = gif::Encoder::new(file, width, height, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+
+    // GIF delays are in hundredths of a second
+    let delay = (100.0 / fps).round() as u16;
+    for image in frames {
+        let mut pixels = image.into_raw();
+        let mut frame = gif::Frame::from_rgba_speed(width, height, &mut pixels, 10);
+        frame.delay = delay;
+        encoder.write_frame(&frame)?;
+    }
+    Ok(())
+}
+
+/// An in-progress master fade driven by the app clock
+#[derive(Debug, Clone, Copy)]
+pub struct MasterFade {
+    /// Level the fade ends at
+    pub target: f32,
+    /// Change in level per second
+    pub rate: f32,
+}
+
 /// Main application state
 pub struct App {
     /// Current synthesizer state
@@ -74,11 +659,224 @@ pub struct App {
     /// Automation state (LFOs)
     pub automation: AutomationState,
 
+    /// Taps of the TAP button, setting the global BPM
+    pub tap_tempo: TapTempo,
+
+    /// Audio input feeding audio binds (None = no input device)
+    pub audio: Option<AudioInput>,
+
     /// Show settings menu
     pub show_settings: bool,
 
     /// Bezel position settings
     pub bezel: BezelSettings,
+
+    /// Still sampled by the Image input source (None = neutral grey)
+    pub input_image: Option<PathBuf>,
+
+    /// Webcam capture, open while a source uses the Camera input
+    pub camera: Option<Camera>,
+
+    /// Camera device the Camera source uses, and how many there are
+    pub camera_device: u32,
+    pub camera_count: usize,
+
+    /// Device that last failed to open (not retried until another is picked)
+    camera_failed: Option<u32>,
+
+    /// Bezel image loaded from disk (None = the built-in PVM bezel)
+    pub bezel_file: Option<PathBuf>,
+
+    /// Why the last bezel file couldn't be used
+    pub bezel_error: Option<String>,
+
+    /// Window background behind the bezel (sRGB)
+    pub background: [u8; 3],
+
+    /// Master output level (1 = full, 0 = black)
+    pub master_fade: f32,
+
+    /// Active timed fade, if any
+    pub fade: Option<MasterFade>,
+
+    /// Duration used by the fade in/out buttons
+    pub fade_secs: f32,
+
+    /// Active preset morph, if any
+    pub morph: Option<Morph>,
+
+    /// Duration used by the Morph menu
+    pub morph_secs: f32,
+
+    /// Running auto-morph playlist, if any
+    pub playlist: Option<Playlist>,
+
+    /// Synth output resolution (width, height); the renderer follows it
+    pub output_size: [u32; 2],
+
+    /// Aspect the resolution setting applies to output_size
+    pub aspect: SynthAspect,
+
+    /// Installation mode: fullscreen output only, no UI
+    pub kiosk: bool,
+
+    /// Draw the panels (off in F11 fullscreen: only the bezel and output)
+    pub show_ui: bool,
+
+    /// Set when the feedback buffers should be wiped before the next frame
+    pub clear_feedback: bool,
+
+    /// Feedback buffer resolution relative to the output (1, 1/2, 1/4)
+    pub feedback_scale: f32,
+
+    /// Render the synth at 2x and downsample (cleaner edges, 4x the GPU work)
+    pub supersample: bool,
+
+    /// Keep feedback in 16-bit float so slow drifts and long decays don't
+    /// stall on 8-bit rounding
+    pub float_feedback: bool,
+
+    /// Scanline scope settings
+    pub scope: ScopeSettings,
+
+    /// Luminance of the scope row from the last frame (0-1 per pixel)
+    pub scope_samples: Vec<f32>,
+
+    /// Show the output histogram
+    pub show_histogram: bool,
+
+    /// Also plot per-channel R/G/B levels in the histogram
+    pub histogram_rgb: bool,
+
+    /// Output histogram from the last frame
+    pub histogram: Histogram,
+
+    /// FPS / GPU time overlay (Settings)
+    pub perf: PerfStats,
+
+    /// Parameter that arrow / page keys nudge
+    /// ("stage.param" key of the last slider touched)
+    pub focused_param: Option<String>,
+
+    /// Status message shown over the preview
+    pub toast: Option<Toast>,
+
+    /// Preview bypasses feedback and output emulation (patch unchanged)
+    pub signal_only: bool,
+
+    /// Phase (0-1) LFOs are held at for still captures (None = as they are)
+    pub still_lfo_phase: Option<f32>,
+
+    /// Save the next rendered frame as a PNG (set by the Screenshot button)
+    pub screenshot_requested: bool,
+
+    /// Running image sequence export; the clock steps by 1/fps per frame
+    /// instead of following real time until it finishes
+    pub sequence_export: Option<SequenceExport>,
+
+    /// Length and frame rate used by Export Sequence
+    pub sequence_secs: f32,
+    pub sequence_fps: f32,
+
+    /// Running GIF recording, and the frame rate new recordings use
+    pub gif_recording: Option<GifRecording>,
+    pub gif_fps: f32,
+
+    /// Reports from the background GIF encoder (saved path or error)
+    gif_encoding: Option<std::sync::mpsc::Receiver<Result<PathBuf, String>>>,
+
+    /// Patch time, automation and feedback are frozen
+    pub paused: bool,
+
+    /// Run a single frame on the next update while paused
+    pub step_frame: bool,
+
+    /// Feedback damping on sudden jumps of feedback controls (Settings)
+    pub jump_guard: JumpGuard,
+
+    /// Glide toward parameter changes instead of jumping (Settings)
+    pub smoothing: SmoothedState,
+
+    /// Stage settings last copied with a panel's Copy button
+    pub stage_clipboard: Option<StageClipboard>,
+
+    /// Chaos macro, 0 (patch as set) to 1 (see `CHAOS_CURVES`)
+    pub chaos: f32,
+
+    /// Seed of the last randomized patch (typing it in recalls the patch)
+    pub random_seed: u64,
+
+    /// Recorded slider moves, looped after LFOs
+    pub gestures: GestureRecorder,
+
+    /// Keyboard shortcut list (?)
+    pub show_key_help: bool,
+
+    /// Patches stored for instant recall (F1-F8), kept in `SCENES_FILE`
+    pub scenes: Scenes,
+
+    /// MIDI controller input (None = no port)
+    pub midi: Option<MidiInput>,
+
+    /// CC mappings, kept in `MIDI_FILE`
+    pub midi_map: MidiMap,
+
+    /// MIDI learn: the next CC to arrive maps to the next slider clicked
+    pub midi_learn: bool,
+
+    /// Listen for OSC on `osc_port` (the binary turns this on at startup)
+    pub osc_enabled: bool,
+    pub osc_port: u16,
+    pub osc: Option<OscServer>,
+
+    /// Port that last failed to bind (not retried until another is picked)
+    osc_failed: Option<u16>,
+
+    /// Values last sent to the OSC controller (0-1), to send only changes
+    osc_sent: HashMap<&'static str, f32>,
+
+    /// Share the output with other apps (Spout) under `share_name`
+    pub share_enabled: bool,
+    pub share_name: String,
+    pub share: Option<TextureSender>,
+
+    /// Name that failed to open, so it isn't retried every frame
+    share_failed: Option<String>,
+
+    /// Parameter-to-parameter modulation, applied after LFOs
+    pub mod_routes: Vec<ModRoute>,
+    pub show_mod_matrix: bool,
+
+    /// Accessibility flash limiter (Settings)
+    pub flash_limiter: FlashLimiter,
+
+    /// Slowly adapting (lo, hi) of the amplitude signal, for auto-normalize
+    pub signal_range: (f32, f32),
+
+    /// Input source held solo (mix restored on release)
+    pub input_solo: Option<InputSolo>,
+
+    /// Scale the preview with nearest-neighbor filtering (crisp pixels)
+    pub nearest_preview: bool,
+
+    /// Last shader compile error, shown until the shader builds again
+    pub shader_error: Option<String>,
+
+    /// Feedback steps per second, independent of the display refresh rate
+    pub feedback_rate: f32,
+
+    /// Fraction of a feedback step carried over to the next frame
+    pub feedback_step_accum: f32,
+
+    /// Feedback and output effect enables, ramped toward the patch's
+    /// switches so toggles crossfade
+    pub effect_mix: EffectMix,
+
+    /// Loop mode length in feedback steps (None = free running): time, LFOs
+    /// and steppers repeat exactly every this many steps
+    pub loop_frames: Option<u32>,
+    /// Feedback steps into the loop
+    loop_position: f64,
 }
 
 impl Default for App {
@@ -98,46 +896,1140 @@ impl App {
             time: 0.0,
             show_preset_browser: false,
             automation: AutomationState::new(),
+            tap_tempo: TapTempo::default(),
+            audio: None,
             show_settings: false,
             bezel: BezelSettings::default(),
+            input_image: None,
+            camera: None,
+            camera_device: 0,
+            camera_count: 0,
+            camera_failed: None,
+            bezel_file: None,
+            bezel_error: None,
+            background: [25, 25, 25],
+            master_fade: 1.0,
+            fade: None,
+            fade_secs: 4.0,
+            morph: None,
+            morph_secs: 10.0,
+            playlist: None,
+            output_size: [640, 480],
+            aspect: SynthAspect::Standard,
+            kiosk: false,
+            show_ui: true,
+            clear_feedback: false,
+            feedback_scale: 1.0,
+            supersample: false,
+            float_feedback: false,
+            scope: ScopeSettings::default(),
+            scope_samples: Vec::new(),
+            show_histogram: false,
+            histogram_rgb: false,
+            histogram: Histogram::default(),
+            perf: PerfStats::default(),
+            focused_param: None,
+            toast: None,
+            signal_only: false,
+            still_lfo_phase: None,
+            screenshot_requested: false,
+            sequence_export: None,
+            sequence_secs: 10.0,
+            sequence_fps: 30.0,
+            gif_recording: None,
+            gif_fps: 15.0,
+            gif_encoding: None,
+            paused: false,
+            step_frame: false,
+            jump_guard: JumpGuard::default(),
+            smoothing: SmoothedState::default(),
+            stage_clipboard: None,
+            chaos: 0.0,
+            random_seed: 0,
+            gestures: GestureRecorder::default(),
+            show_key_help: false,
+            scenes: Default::default(),
+            midi: None,
+            midi_map: MidiMap::new(),
+            midi_learn: false,
+            osc_enabled: false,
+            osc_port: crate::osc::DEFAULT_PORT,
+            osc: None,
+            osc_failed: None,
+            osc_sent: HashMap::new(),
+            share_enabled: false,
+            share_name: crate::texture_share::DEFAULT_NAME.to_string(),
+            share: None,
+            share_failed: None,
+            mod_routes: Vec::new(),
+            show_mod_matrix: false,
+            flash_limiter: FlashLimiter::default(),
+            signal_range: (0.0, 1.0),
+            input_solo: None,
+            nearest_preview: false,
+            shader_error: None,
+            feedback_rate: 60.0,
+            feedback_step_accum: 0.0,
+            effect_mix: EffectMix::target(&SynthState::default()),
+            loop_frames: None,
+            loop_position: 0.0,
         }
     }
 
     /// Update timing and apply automation
-    pub fn update(&mut self, dt: f32) {
-        self.time += dt;
-        self.frame = self.frame.wrapping_add(1);
+    /// Returns the time the patch advanced: `dt`, or while paused 0 (or one
+    /// feedback step when a frame step is pending)
+    pub fn update(&mut self, dt: f32) -> f32 {
+        // Advance timed master fade
+        if let Some(fade) = self.fade {
+            let step = fade.rate * dt;
+            if (fade.target - self.master_fade).abs() <= step {
+                self.master_fade = fade.target;
+                self.fade = None;
+            } else {
+                self.master_fade += step * (fade.target - self.master_fade).signum();
+            }
+        }
+
+        // Crossfade toggled effects (runs while paused: controls stay live)
+        self.effect_mix.ramp_toward(EffectMix::target(&self.synth), dt / EFFECT_FADE_SECS);
+
+        // MIDI controllers (runs while paused: controls stay live)
+        self.apply_midi();
+        self.sync_osc();
+        self.apply_osc();
+        self.sync_camera();
+        self.sync_share();
+
+        // Report a finished GIF; a dropped sender means the encoder thread died
+        if let Some(rx) = &self.gif_encoding {
+            let result = match rx.try_recv() {
+                Ok(result) => Some(result),
+                Err(std::sync::mpsc::TryRecvError::Empty) => None,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    Some(Err("encoder stopped unexpectedly".to_string()))
+                }
+            };
+            if let Some(result) = result {
+                self.gif_encoding = None;
+                match result {
+                    Ok(path) => self.show_toast(format!("Saved GIF {}", path.display())),
+                    Err(e) => self.show_toast(format!("GIF failed: {e}")),
+                }
+            }
+        }
+
+        // Drop an expired toast
+        if let Some(toast) = &mut self.toast {
+            toast.remaining -= dt;
+            if toast.remaining <= 0.0 {
+                self.toast = None;
+            }
+        }
+
+        // Paused: the patch holds still unless stepping a single frame
+        let synth_dt = if !self.paused {
+            dt
+        } else if std::mem::take(&mut self.step_frame) {
+            1.0 / self.feedback_rate
+        } else {
+            // Controls stay live, so keep gliding toward them on wall time
+            self.smoothing.update(&self.synth, dt);
+            return 0.0;
+        };
+
+        // Step a preset morph (completing it selects the preset)
+        if let Some(mut morph) = self.morph.take() {
+            morph.elapsed += synth_dt;
+            if morph.progress() >= 1.0 {
+                self.load_preset(morph.target);
+            } else {
+                self.synth = morph_state(&morph.from, &morph.to, morph.progress());
+                self.morph = Some(morph);
+            }
+        }
+
+        // Hold, then morph on to the next preset
+        let morphing = self.morph.is_some();
+        let advance = self.playlist.as_mut().is_some_and(|playlist| {
+            if !morphing {
+                playlist.elapsed += synth_dt;
+            }
+            let due = playlist.elapsed >= playlist.hold_secs;
+            if due {
+                playlist.elapsed = 0.0;
+            }
+            due
+        });
+        if advance && !self.presets.is_empty() {
+            let next = self.current_preset.map_or(0, |i| (i + 1) % self.presets.len());
+            self.morph_to(next, self.morph_secs);
+        }
+
+        if let Some(total) = self.loop_frames {
+            // Loop mode: time and frame wrap together every `total` steps
+            let total = total.max(1) as f64;
+            let rate = self.feedback_rate as f64;
+            self.loop_position = (self.loop_position + synth_dt as f64 * rate) % total;
+            self.time = (self.loop_position / rate) as f32;
+            self.frame = self.loop_position.round() as u32 % total as u32;
+            self.automation.set_loop_position(Some(self.loop_position / total), total / rate);
+        } else {
+            self.time += synth_dt;
+            self.frame = self.frame.wrapping_add(1);
+        }
 
         // Apply LFO automation
+        self.automation.advance(synth_dt);
+        if let Some(audio) = &mut self.audio {
+            self.automation.audio_levels = audio.update(synth_dt);
+        }
         self.automation.apply(&mut self.synth);
+        self.gestures.update(&mut self.synth, &self.automation, synth_dt);
+
+        self.jump_guard.update(&self.synth, dt);
+        self.smoothing.update(&self.synth, dt);
+        synth_dt
+    }
+
+    /// Patch to render for a still capture: LFO-driven parameters held at
+    /// `still_lfo_phase` if set, otherwise the live patch
+    pub fn still_state(&self) -> SynthState {
+        let mut synth = self.synth.clone();
+        if let Some(phase) = self.still_lfo_phase {
+            self.automation.apply_at_phase(&mut synth, phase);
+        }
+        apply_mod_routes(&self.mod_routes, &mut synth);
+        self.apply_chaos(&mut synth);
+        synth
+    }
+
+    /// Patch as rendered this frame: the live patch (LFOs already applied,
+    /// then smoothed) with the mod matrix and chaos macro on top. Neither
+    /// touches `synth`, so modulation never accumulates and removing a route
+    /// or bringing the chaos knob back to 0 returns the patch exactly.
+    pub fn render_state(&self) -> SynthState {
+        let mut synth = self.smoothing.get(&self.synth).clone();
+        apply_mod_routes(&self.mod_routes, &mut synth);
+        self.apply_chaos(&mut synth);
+        synth
+    }
+
+    /// Push each `CHAOS_CURVES` parameter toward its extreme. A parameter
+    /// already past its target is left alone.
+    fn apply_chaos(&self, synth: &mut SynthState) {
+        if self.chaos <= 0.0 {
+            return;
+        }
+        for &(key, target, exponent) in &CHAOS_CURVES {
+            if let Some(value) = synth.param_mut(key) {
+                *value += (target - *value).max(0.0) * self.chaos.powf(exponent);
+            }
+        }
+    }
+
+    /// Zero the elapsed time, frame count and beat clock together, so loops
+    /// and LFOs restart from the top
+    pub fn reset_clock(&mut self) {
+        self.time = 0.0;
+        self.frame = 0;
+        self.loop_position = 0.0;
+        self.automation.reset_clocks();
+    }
+
+    /// Enter loop mode with a loop of `frames` feedback steps (None leaves
+    /// it), restarting the clock at the top of the loop. Loop exports set
+    /// this to their frame count.
+    pub fn set_loop(&mut self, frames: Option<u32>) {
+        self.loop_frames = frames.map(|frames| frames.max(1));
+        self.reset_clock();
+        if self.loop_frames.is_none() {
+            self.automation.set_loop_position(None, 0.0);
+        } else {
+            // Position LFOs at the top of the loop before the first update
+            let secs = self.loop_length() as f64;
+            self.automation.set_loop_position(Some(0.0), secs);
+        }
+    }
+
+    /// Loop mode length in seconds (0 = free running)
+    pub fn loop_length(&self) -> f32 {
+        self.loop_frames.map_or(0.0, |frames| frames as f32 / self.feedback_rate)
+    }
+
+    /// Advance exactly one feedback step on the next frame (while paused)
+    pub fn request_frame_step(&mut self) {
+        if self.paused {
+            self.step_frame = true;
+        }
+    }
+
+    /// Per-frame render values that are not part of the patch
+    pub fn frame_params(&self) -> FrameParams {
+        FrameParams {
+            time: self.time,
+            frame: self.frame,
+            master_fade: self.master_fade,
+            signal_only: self.signal_only,
+            input_solo: self.input_solo,
+            signal_range: self.signal_range,
+            signal_probe: false,
+            flash_gain: if self.flash_limiter.enabled { self.flash_limiter.gain } else { 1.0 },
+            feedback_damp: if self.jump_guard.enabled { self.jump_guard.damp } else { 1.0 },
+            loop_length: self.loop_length(),
+            effect_mix: Some(self.effect_mix),
+            aspect: self.output_size[0] as f32 / self.output_size[1] as f32,
+        }
+    }
+
+    /// Feedback steps to run this frame so feedback evolves at
+    /// `feedback_rate` whatever the display refresh (0 = just redisplay)
+    pub fn feedback_steps(&mut self, dt: f32) -> u32 {
+        // A held mixer backdrop is the feedback buffer, so don't overwrite it
+        if !self.synth.feedback.enabled && self.synth.mixer.hold_backdrop {
+            return 0;
+        }
+        self.feedback_step_accum += dt * self.feedback_rate;
+        let steps = self.feedback_step_accum.floor();
+        self.feedback_step_accum -= steps;
+        // After a stall, catch up a little rather than all at once
+        (steps as u32).min(MAX_FEEDBACK_STEPS)
+    }
+
+    /// Ease the measured signal range toward the min/max of a probe readback
+    /// at the patch's normalize rate
+    pub fn adapt_signal_range(&mut self, samples: &[[f32; 3]], dt: f32) {
+        let (min, max) = samples
+            .iter()
+            .flatten()
+            .fold((f32::MAX, f32::MIN), |(min, max), &v| (min.min(v), max.max(v)));
+        if min > max {
+            return;
+        }
+
+        let k = 1.0 - (-self.synth.amplitude.normalize_rate * dt).exp();
+        let (lo, hi) = &mut self.signal_range;
+        *lo += (min - *lo) * k;
+        *hi += (max - *hi) * k;
+    }
+
+    /// Fade the master output to `target` over `secs` seconds
+    pub fn fade_to(&mut self, target: f32, secs: f32) {
+        let target = target.clamp(0.0, 1.0);
+        if secs <= 0.0 {
+            self.master_fade = target;
+            self.fade = None;
+        } else {
+            self.fade = Some(MasterFade {
+                target,
+                rate: 1.0 / secs,
+            });
+        }
+    }
+
+    /// Whether the fade button would fade in: reverses a running fade,
+    /// otherwise heads away from the current level
+    pub fn next_fade_is_in(&self) -> bool {
+        match self.fade {
+            Some(fade) => fade.target < self.master_fade,
+            None => self.master_fade < 0.5,
+        }
+    }
+
+    /// Fade in or out over `fade_secs`, as the master panel's fade button
+    pub fn toggle_fade(&mut self) {
+        let target = if self.next_fade_is_in() { 1.0 } else { 0.0 };
+        self.fade_to(target, self.fade_secs);
+    }
+
+    /// Take on settings saved by an earlier run. The resolution is left
+    /// alone when none was saved, or when it isn't one Settings offers at the
+    /// saved aspect (a hand-edited size could exceed the GPU's texture limit),
+    /// so the caller's pick for the display stands.
+    #[cfg(feature = "shell")]
+    pub fn apply_config(&mut self, config: &AppConfig) {
+        self.bezel = config.bezel.clone();
+        self.automation.global_bpm = config.bpm.clamp(30.0, 240.0);
+        self.smoothing.secs = config.smoothing_secs.clamp(0.0, 1.0);
+        self.aspect = config.aspect;
+        if let Some(size) = config.output_size {
+            let offered = RESOLUTIONS.iter().any(|&[_, height]| config.aspect.size(height) == size);
+            if offered {
+                self.output_size = size;
+            } else {
+                log::warn!("Ignoring saved resolution {}x{}, not one of the offered sizes", size[0], size[1]);
+            }
+        }
+    }
+
+    /// Settings to save for the next run
+    #[cfg(feature = "shell")]
+    pub fn config(&self, window_size: Option<[u32; 2]>) -> AppConfig {
+        AppConfig {
+            bezel: self.bezel.clone(),
+            bpm: self.automation.global_bpm,
+            smoothing_secs: self.smoothing.secs,
+            output_size: Some(self.output_size),
+            aspect: self.aspect,
+            window_size,
+        }
     }
 
     /// Load a preset by index
     pub fn load_preset(&mut self, index: usize) {
         if let Some(preset) = self.presets.get(index) {
+            self.morph = None;
             self.synth = preset.state.clone();
+            self.synth.colorize.reload_palette();
+            if let Some(bpm) = preset.bpm {
+                self.automation.global_bpm = bpm.clamp(30.0, 240.0);
+            }
+            // A preset's recorded gestures replace the current ones
+            self.gestures.recording = preset.gestures.clone().unwrap_or_default();
+            self.gestures.play();
+            if let Some(lfos) = &preset.lfos {
+                self.automation.set_lfos(lfos.clone());
+            }
+            if let Some(steppers) = &preset.steppers {
+                self.automation.steppers = steppers.clone();
+            }
             self.current_preset = Some(index);
         }
     }
 
-    /// Mark state as modified (no longer matches a preset)
+    /// Load a preset JSON file, add it to the preset list and select it
+    pub fn load_preset_file(&mut self, path: &Path) -> Result<(), std::io::Error> {
+        let preset = Preset::load(path)?;
+        self.presets.push(preset);
+        self.load_preset(self.presets.len() - 1);
+        Ok(())
+    }
+
+    /// Save the current patch, tempo, LFOs, steppers and gestures as a
+    /// single preset file to share. It's named after the file.
+    pub fn export_preset(&mut self, path: &Path) {
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        let description = self
+            .current_preset
+            .and_then(|i| self.presets.get(i))
+            .map_or(String::new(), |p| p.description.clone());
+        let mut preset = Preset::new(&name, &description, self.synth.clone());
+        preset.bpm = Some(self.automation.global_bpm);
+        preset.gestures = (!self.gestures.recording.tracks.is_empty()).then(|| self.gestures.recording.clone());
+        preset.lfos = Some(self.automation.lfos());
+        preset.steppers = Some(self.automation.steppers.clone());
+
+        match preset.save(path) {
+            Ok(()) => self.show_toast(format!("Exported preset to {}", path.display())),
+            Err(e) => self.show_toast(format!("Can't export preset: {e}")),
+        }
+    }
+
+    /// Add a shared preset file to the preset list and select it
+    pub fn import_preset(&mut self, path: &Path) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        match self.load_preset_file(path) {
+            Ok(()) => {
+                let preset_name = self.presets.last().map_or("", |p| p.name.as_str());
+                self.show_toast(format!("Loaded preset \"{preset_name}\""));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                self.show_toast(format!("{name} isn't a preset this version can read ({e})"));
+            }
+            Err(e) => self.show_toast(format!("Can't load {name}: {e}")),
+        }
+    }
+
+    /// Save the whole working setup (patch, automation, tempo, bezel and
+    /// source paths) to reopen later with `load_session`
+    pub fn save_session(&mut self, path: &Path) {
+        let session = Session {
+            state: self.synth.clone(),
+            bpm: self.automation.global_bpm,
+            lfo_speeds: self.automation.lfo_speeds,
+            lfos: self.automation.lfos(),
+            steppers: self.automation.steppers.clone(),
+            audio_binds: self.automation.audio_binds(),
+            mod_routes: self.mod_routes.clone(),
+            gestures: self.gestures.recording.clone(),
+            bezel: self.bezel.clone(),
+            bezel_file: self.bezel_file.clone(),
+            input_image: self.input_image.clone(),
+            ..Session::default()
+        };
+        match session.save(path) {
+            Ok(()) => self.show_toast(format!("Saved session to {}", path.display())),
+            Err(e) => self.show_toast(format!("Can't save session: {e}")),
+        }
+    }
+
+    /// Restore a setup saved with `save_session`, replacing the patch and
+    /// all automation
+    pub fn load_session(&mut self, path: &Path) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let session = match Session::load(path) {
+            Ok(session) => session,
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                self.show_toast(format!("{name} isn't a session this version can read ({e})"));
+                return;
+            }
+            Err(e) => {
+                self.show_toast(format!("Can't load {name}: {e}"));
+                return;
+            }
+        };
+
+        self.morph = None;
+        self.synth = session.state;
+        self.synth.colorize.reload_palette();
+        self.automation.global_bpm = session.bpm.clamp(30.0, 240.0);
+        self.automation.lfo_speeds = session.lfo_speeds;
+        self.automation.sources.clear();
+        self.automation.set_lfos(session.lfos);
+        self.automation.set_audio_binds(session.audio_binds);
+        self.automation.steppers = session.steppers;
+        self.mod_routes = session.mod_routes;
+        self.mod_routes.truncate(MAX_MOD_ROUTES);
+        self.gestures.recording = session.gestures;
+        self.gestures.play();
+        self.bezel = session.bezel;
+        self.bezel_file = session.bezel_file;
+        self.input_image = session.input_image;
+        self.current_preset = None;
+        self.clear_feedback = true;
+        self.smoothing.snap();
+        self.show_toast(format!("Loaded session {name}"));
+    }
+
+    /// Use a .gpl/.hex palette for Threshold colorize
+    pub fn load_palette_file(&mut self, path: &Path) -> Result<usize, String> {
+        let colors = crate::palette::load_palette(path)?;
+        let count = colors.len();
+        self.synth.colorize.palette = colors;
+        self.synth.colorize.palette_path = Some(path.to_string_lossy().into_owned());
+        self.mark_modified();
+        Ok(count)
+    }
+
+    /// Use a PNG strip or .gpl/.hex palette for Palette colorize
+    pub fn load_lut_file(&mut self, path: &Path) -> Result<usize, String> {
+        let colors = crate::palette::load_lut(path)?;
+        let count = colors.len();
+        self.synth.colorize.lut = colors;
+        self.synth.colorize.lut_path = Some(path.to_string_lossy().into_owned());
+        self.mark_modified();
+        Ok(count)
+    }
+
+    /// Handle a file dropped onto the window
+    pub fn open_dropped_file(&mut self, path: &Path) {
+        let is_json = path.extension().is_some_and(|ext| {
+            ext.eq_ignore_ascii_case("json") || ext.eq_ignore_ascii_case(crate::presets::PRESET_EXTENSION)
+        });
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+        // In palette mode a dropped palette becomes the lookup table
+        if crate::palette::is_palette_file(path) && self.synth.colorize.mode == ColorMode::Palette {
+            match self.load_lut_file(path) {
+                Ok(count) => self.show_toast(format!("Loaded {count}-color lookup palette {name}")),
+                Err(e) => self.show_toast(format!("Can't load {name}: {e}")),
+            }
+            return;
+        }
+        if crate::palette::is_palette_file(path) {
+            match self.load_palette_file(path) {
+                Ok(count) => self.show_toast(format!("Loaded {count}-color palette {name}")),
+                Err(e) => self.show_toast(format!("Can't load {name}: {e}")),
+            }
+            return;
+        }
+        if !is_json {
+            self.show_toast(format!("Can't open {name}: only .json/.phxl presets and .gpl/.hex palettes can be dropped"));
+            return;
+        }
+        self.import_preset(path);
+    }
+
+    /// Show a short message over the preview for a few seconds
+    pub fn show_toast(&mut self, message: String) {
+        log::info!("{message}");
+        self.toast = Some(Toast {
+            message,
+            remaining: TOAST_SECS,
+        });
+    }
+
+    /// Write the current patch as a standalone WGSL shader in the working
+    /// directory, named after the preset ("feedback_spiral.wgsl")
+    pub fn export_shader(&mut self) {
+        let name = self
+            .current_preset
+            .and_then(|i| self.presets.get(i))
+            .map_or("patch".to_string(), |p| {
+                p.name.to_lowercase().replace(|c: char| !c.is_alphanumeric(), "_")
+            });
+        let path = format!("{name}.wgsl");
+
+        let params = FrameParams {
+            aspect: self.output_size[0] as f32 / self.output_size[1] as f32,
+            ..FrameParams::default()
+        };
+        let mut uniforms = SynthUniforms::from_state(&self.synth, &params);
+        uniforms.output_height = self.output_size[1] as f32;
+        let result = crate::shader_export::export_wgsl(&uniforms)
+            .and_then(|wgsl| std::fs::write(&path, wgsl).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => self.show_toast(format!("Exported shader to {path}")),
+            Err(e) => self.show_toast(format!("Shader export failed: {e}")),
+        }
+    }
+
+    /// Write a captured frame as a timestamped PNG next to the executable
+    /// (the working directory if that can't be found)
+    pub fn save_screenshot(&mut self, image: &image::RgbaImage) {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let dir = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf))
+            .unwrap_or_default();
+        let path = dir.join(format!("phosphlux_{secs}.png"));
+
+        match image.save(&path) {
+            Ok(()) => self.show_toast(format!("Saved screenshot {}", path.display())),
+            Err(e) => self.show_toast(format!("Screenshot failed: {e}")),
+        }
+    }
+
+    /// Start exporting `sequence_secs` of output at `sequence_fps` as numbered
+    /// PNGs in a new folder next to the executable. The export starts from
+    /// a zeroed clock and empty feedback, so the same patch always exports
+    /// the same frames.
+    pub fn start_sequence_export(&mut self) {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let dir = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf))
+            .unwrap_or_default()
+            .join(format!("phosphlux_{secs}"));
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            self.show_toast(format!("Export failed: {e}"));
+            return;
+        }
+
+        let fps = self.sequence_fps.max(1.0);
+        self.sequence_export = Some(SequenceExport {
+            dir,
+            frame: 0,
+            frames: (self.sequence_secs * fps).round().max(1.0) as u32,
+            fps,
+        });
+        self.paused = false;
+        self.reset_clock();
+        self.feedback_step_accum = 0.0;
+        self.clear_feedback = true;
+    }
+
+    /// Fixed time step while a sequence export runs (None = follow real time)
+    pub fn sequence_step(&self) -> Option<f32> {
+        self.sequence_export.as_ref().map(|export| 1.0 / export.fps)
+    }
+
+    /// Write the next frame of the running sequence export
+    pub fn save_sequence_frame(&mut self, image: &image::RgbaImage) {
+        let Some(export) = &mut self.sequence_export else {
+            return;
+        };
+        let path = export.dir.join(format!("frame_{:05}.png", export.frame));
+        if let Err(e) = image.save(&path) {
+            self.sequence_export = None;
+            self.show_toast(format!("Export failed: {e}"));
+            return;
+        }
+
+        export.frame += 1;
+        if export.frame >= export.frames {
+            let message = format!("Exported {} frames to {}", export.frames, export.dir.display());
+            self.sequence_export = None;
+            self.show_toast(message);
+        }
+    }
+
+    /// Start recording a GIF, or stop and save the one being recorded
+    pub fn toggle_gif_recording(&mut self) {
+        match self.gif_recording.take() {
+            Some(recording) => self.save_gif(recording),
+            None => {
+                self.gif_recording = Some(GifRecording {
+                    frames: Vec::new(),
+                    bytes: 0,
+                    fps: self.gif_fps.clamp(1.0, 50.0),
+                    elapsed: 0.0,
+                    next_capture: 0.0,
+                });
+            }
+        }
+    }
+
+    /// Advance the GIF recording by `dt` seconds; true when a frame should
+    /// be captured now
+    pub fn gif_capture_due(&mut self, dt: f32) -> bool {
+        let Some(recording) = &mut self.gif_recording else {
+            return false;
+        };
+        recording.elapsed += dt;
+        recording.next_capture -= dt;
+        if recording.next_capture > 0.0 {
+            return false;
+        }
+        recording.next_capture += 1.0 / recording.fps;
+        true
+    }
+
+    /// Add a captured frame, scaled down past `GIF_MAX_WIDTH`. Reaching
+    /// `GIF_MAX_SECS` or `GIF_MAX_BYTES` stops and saves; a frame of a
+    /// different size than the first (the resolution changed) is dropped.
+    pub fn add_gif_frame(&mut self, image: image::RgbaImage) {
+        let Some(recording) = &mut self.gif_recording else {
+            return;
+        };
+        let image = if image.width() > GIF_MAX_WIDTH {
+            let height = (image.height() * GIF_MAX_WIDTH / image.width()).max(1);
+            image::imageops::resize(&image, GIF_MAX_WIDTH, height, image::imageops::FilterType::Triangle)
+        } else {
+            image
+        };
+        if let Some(first) = recording.frames.first() {
+            if first.dimensions() != image.dimensions() {
+                log::warn!("Dropping a {}x{} GIF frame from a {}x{} recording", image.width(), image.height(), first.width(), first.height());
+                return;
+            }
+        }
+        recording.bytes += image.as_raw().len();
+        recording.frames.push(image);
+        if recording.frames.len() as f32 >= GIF_MAX_SECS * recording.fps || recording.bytes >= GIF_MAX_BYTES {
+            self.toggle_gif_recording();
+        }
+    }
+
+    /// Encode a recording as a looping GIF next to the executable, on a
+    /// background thread (quantizing to 256 colors takes a while)
+    fn save_gif(&mut self, recording: GifRecording) {
+        if recording.frames.is_empty() {
+            return;
+        }
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let path = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf))
+            .unwrap_or_default()
+            .join(format!("phosphlux_{secs}.gif"));
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.gif_encoding = Some(receiver);
+        self.show_toast(format!("Encoding {} frames...", recording.frames.len()));
+        std::thread::spawn(move || {
+            let result = encode_gif(&path, recording.frames, recording.fps).map(|()| path);
+            let _ = sender.send(result.map_err(|e| e.to_string()));
+        });
+    }
+
+    /// Load a preset from a JSON file, or a built-in by name
+    /// ("feedback_spiral" matches "Feedback Spiral")
+    pub fn load_preset_arg(&mut self, arg: &str) -> Result<(), std::io::Error> {
+        let path = Path::new(arg);
+        if path.is_file() {
+            return self.load_preset_file(path);
+        }
+
+        let wanted = arg.trim_end_matches(".json").replace(['_', '-'], " ");
+        match self
+            .presets
+            .iter()
+            .position(|p| p.name.eq_ignore_ascii_case(&wanted))
+        {
+            Some(index) => {
+                self.load_preset(index);
+                Ok(())
+            }
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no preset file or built-in preset with that name",
+            )),
+        }
+    }
+
+    /// Read the scene slots saved by a previous session, if any
+    pub fn load_scenes(&mut self) {
+        let Some(path) = find_user_file(SCENES_FILE) else {
+            return;
+        };
+        match crate::presets::load_scenes(&path) {
+            Ok(scenes) => {
+                self.scenes = scenes;
+                for scene in self.scenes.iter_mut().flatten() {
+                    scene.colorize.reload_palette();
+                }
+            }
+            Err(e) => log::error!("Failed to load {}: {e}", path.display()),
+        }
+    }
+
+    fn save_scenes(&self) {
+        let path = user_file(SCENES_FILE);
+        let result = create_parent(&path).and_then(|()| crate::presets::save_scenes(&path, &self.scenes));
+        if let Err(e) = result {
+            log::error!("Failed to save {}: {e}", path.display());
+        }
+    }
+
+    /// Store the current patch in scene slot `index`
+    pub fn store_scene(&mut self, index: usize) {
+        if let Some(slot) = self.scenes.get_mut(index) {
+            *slot = Some(self.synth.clone());
+            self.save_scenes();
+            self.show_toast(format!("Stored scene {}", index + 1));
+        }
+    }
+
+    /// Recall scene slot `index` (if filled), starting from clean feedback
+    pub fn recall_scene(&mut self, index: usize) {
+        if let Some(Some(scene)) = self.scenes.get(index) {
+            self.synth = scene.clone();
+            self.clear_feedback = true;
+            self.smoothing.snap();
+            self.mark_modified();
+        }
+    }
+
+    /// Copy the selected stage panel's settings
+    pub fn copy_stage(&mut self) {
+        self.stage_clipboard = Some(StageClipboard::copy(&self.synth, self.selected_stage));
+    }
+
+    /// Paste copied settings into the selected stage, if they came from
+    /// the same kind of stage
+    pub fn paste_stage(&mut self) {
+        let Some(clipboard) = self.stage_clipboard.clone() else {
+            return;
+        };
+        if !clipboard.fits(self.selected_stage) {
+            return;
+        }
+        match clipboard {
+            StageClipboard::Input(input) => self.synth.input = input,
+            StageClipboard::Geometry(geometry) => self.synth.geometry = geometry,
+            StageClipboard::Amplitude(amplitude) => self.synth.amplitude = amplitude,
+            StageClipboard::Colorize(colorize) => self.synth.colorize = colorize,
+            StageClipboard::Feedback(mixer, feedback) => {
+                self.synth.mixer = mixer;
+                self.synth.feedback = feedback;
+            }
+            StageClipboard::Output(output) => self.synth.output = output,
+        }
+        self.mark_modified();
+    }
+
+    pub fn clear_scene(&mut self, index: usize) {
+        if let Some(slot) = self.scenes.get_mut(index) {
+            *slot = None;
+            self.save_scenes();
+        }
+    }
+
+    /// Read the MIDI mappings saved by a previous session, if any
+    pub fn load_midi_map(&mut self) {
+        let Some(path) = find_user_file(MIDI_FILE) else {
+            return;
+        };
+        match crate::presets::load_midi_map(&path) {
+            Ok(map) => self.midi_map = map,
+            Err(e) => log::error!("Failed to load {}: {e}", path.display()),
+        }
+    }
+
+    fn save_midi_map(&self) {
+        let path = user_file(MIDI_FILE);
+        let result = create_parent(&path).and_then(|()| crate::presets::save_midi_map(&path, &self.midi_map));
+        if let Err(e) = result {
+            log::error!("Failed to save {}: {e}", path.display());
+        }
+    }
+
+    /// Start (or cancel) MIDI learn. The focus is dropped so the mapping
+    /// goes to the next slider clicked, not the one touched last.
+    pub fn toggle_midi_learn(&mut self) {
+        self.midi_learn = !self.midi_learn;
+        if self.midi_learn {
+            self.focused_param = None;
+        }
+    }
+
+    /// Remove a parameter's CC mapping
+    pub fn unmap_midi(&mut self, key: &str) {
+        if self.midi_map.remove(key).is_some() {
+            self.save_midi_map();
+        }
+    }
+
+    /// Apply control changes received since the last frame: each mapped
+    /// parameter gets the CC value (0-127) scaled into its slider range.
+    /// While learning, a CC arriving with a slider clicked maps it instead.
+    fn apply_midi(&mut self) {
+        let Some(midi) = &self.midi else {
+            return;
+        };
+        let messages: Vec<(u8, u8)> = midi.poll().collect();
+
+        let mut modified = false;
+        for (cc, value) in messages {
+            if self.midi_learn {
+                if let Some(key) = self.focused_param.clone() {
+                    self.show_toast(format!("CC {cc} → {key}"));
+                    self.midi_map.insert(key, cc);
+                    self.save_midi_map();
+                    self.midi_learn = false;
+                }
+                continue;
+            }
+
+            let t = value as f32 / 127.0;
+            for (key, _) in self.midi_map.iter().filter(|&(_, &mapped)| mapped == cc) {
+                let (Some(spec), Some(param)) = (ParamSpec::find(key), self.synth.param_mut(key)) else {
+                    continue;
+                };
+                *param = spec.from_unit(t);
+                modified = true;
+            }
+        }
+
+        if modified {
+            self.mark_modified();
+        }
+    }
+
+    /// TAP button: set the global BPM from the tapped beat
+    pub fn tap_tempo(&mut self) {
+        if let Some(bpm) = self.tap_tempo.tap(instant::Instant::now()) {
+            self.automation.global_bpm = bpm;
+            self.show_toast(format!("Tap: {bpm:.1} BPM"));
+        }
+    }
+
+    /// Open, reopen or close the OSC listener to match the settings
+    fn sync_osc(&mut self) {
+        let open = self.osc.as_ref().map(|osc| osc.port);
+        if !self.osc_enabled {
+            self.osc = None;
+            self.osc_failed = None;
+        } else if open != Some(self.osc_port) && self.osc_failed != Some(self.osc_port) {
+            self.osc = None;
+            self.osc_sent.clear();
+            self.osc = OscServer::open(self.osc_port);
+            if self.osc.is_none() {
+                self.osc_failed = Some(self.osc_port);
+                self.show_toast(format!("Can't listen for OSC on port {}", self.osc_port));
+            }
+        }
+    }
+
+    /// Apply parameter changes from OSC, then send the controller any
+    /// values that changed (from LFOs, presets or the UI) since last time.
+    /// Floats are 0-1 across a slider's range, as with MIDI; ints pick a
+    /// switch or menu option.
+    fn apply_osc(&mut self) {
+        let Some(osc) = &mut self.osc else {
+            return;
+        };
+
+        // A new controller gets every value, not just later changes
+        let client = osc.client();
+        let messages = osc.poll();
+        if osc.client() != client {
+            self.osc_sent.clear();
+        }
+
+        let mut modified = false;
+        for (key, value) in messages {
+            modified |= match (ParamSpec::find(&key), value) {
+                (Some(spec), OscValue::Float(t)) => {
+                    let t = t.clamp(0.0, 1.0);
+                    self.osc_sent.insert(spec.key, t);
+                    self.automation.set_param(&mut self.synth, &key, spec.from_unit(t))
+                }
+                (None, OscValue::Int(index)) => {
+                    self.automation.set_step(&mut self.synth, &key, index.max(0) as u32)
+                }
+                (None, OscValue::Float(index)) => {
+                    self.automation.set_step(&mut self.synth, &key, index.round().max(0.0) as u32)
+                }
+                (Some(_), OscValue::Int(_)) => false,
+            };
+        }
+
+        if osc.client().is_some() {
+            for spec in crate::synth::PARAMS {
+                let Some(value) = self.synth.param(spec.key) else {
+                    continue;
+                };
+                let t = spec.to_unit(value);
+                if self.osc_sent.get(spec.key).is_none_or(|&sent| (sent - t).abs() > 1e-4) {
+                    osc.send(spec.key, t);
+                    self.osc_sent.insert(spec.key, t);
+                }
+            }
+        }
+
+        if modified {
+            self.mark_modified();
+        }
+    }
+
+    /// Open or close the webcam to match the patch: capturing from the
+    /// chosen device while a source uses it, closed otherwise
+    fn sync_camera(&mut self) {
+        let input = &self.synth.input;
+        let wanted = self.camera_count > 0
+            && (input.source_a == InputSource::Camera || input.source_b == InputSource::Camera);
+        let open = self.camera.as_ref().map(|camera| camera.index);
+
+        if !wanted {
+            self.camera = None;
+        } else if open != Some(self.camera_device) && self.camera_failed != Some(self.camera_device) {
+            // Release the old device before opening the new one
+            self.camera = None;
+            self.camera = Camera::open(self.camera_device);
+            if self.camera.is_none() {
+                self.camera_failed = Some(self.camera_device);
+                self.show_toast(format!("Can't open camera {}", self.camera_device));
+            }
+        }
+    }
+
+    /// Open or close the texture sharing sender to follow the settings
+    fn sync_share(&mut self) {
+        let open = self.share.as_ref().map(|share| share.name.clone());
+        if !self.share_enabled {
+            self.share = None;
+            self.share_failed = None;
+        } else if open.as_ref() != Some(&self.share_name) && self.share_failed.as_ref() != Some(&self.share_name) {
+            // Release the old name before claiming the new one
+            self.share = None;
+            match TextureSender::open(&self.share_name) {
+                Ok(sender) => self.share = Some(sender),
+                Err(e) => {
+                    self.share_failed = Some(self.share_name.clone());
+                    self.show_toast(format!("Can't share output: {e}"));
+                }
+            }
+        }
+    }
+
+    /// Panic button: default patch, no LFOs, mod routes, chaos or gesture
+    /// loop, empty feedback, no running fade or playlist
+    pub fn panic_reset(&mut self) {
+        self.synth = SynthState::default();
+        self.automation.clear();
+        self.mod_routes.clear();
+        self.chaos = 0.0;
+        self.gestures.stop();
+        self.fade = None;
+        self.playlist = None;
+        self.clear_feedback = true;
+        self.smoothing.snap();
+        self.mark_modified();
+    }
+
+    /// Freeze the patch as it is this instant: every LFO, stepper and audio bind is
+    /// evaluated once into the patch, then removed
+    pub fn bake_automation(&mut self) {
+        self.automation.apply(&mut self.synth);
+        self.automation.clear();
+        self.mark_modified();
+    }
+
+    /// Nudge the focused parameter by `steps`; a step is 1% of its range
+    /// (coarse: 10%). Like moving the slider, this stops any LFO on it.
+    pub fn nudge_focused_param(&mut self, steps: f32, coarse: bool) {
+        let Some(key) = &self.focused_param else {
+            return;
+        };
+        let (Some(spec), Some(value)) = (ParamSpec::find(key), self.synth.param_mut(key)) else {
+            return;
+        };
+
+        let step = (spec.max - spec.min) * if coarse { 0.1 } else { 0.01 };
+        *value = (*value + steps * step).clamp(spec.min, spec.max);
+        self.automation.remove_lfo(key);
+        self.mark_modified();
+    }
+
+    /// Mark state as modified (no longer matches a preset); touching the
+    /// patch cancels a morph where it is
     pub fn mark_modified(&mut self) {
         self.current_preset = None;
+        self.morph = None;
     }
 
-    /// Randomize all parameters
+    /// Glide every parameter from the current patch to preset `target` over
+    /// `duration_secs`; enums and switches flip halfway
+    pub fn morph_to(&mut self, target: usize, duration_secs: f32) {
+        let Some(preset) = self.presets.get(target) else {
+            return;
+        };
+        if duration_secs <= 0.0 {
+            self.load_preset(target);
+            return;
+        }
+
+        let mut to = preset.state.clone();
+        to.colorize.reload_palette();
+        self.morph = Some(Morph {
+            from: self.synth.clone(),
+            to,
+            target,
+            elapsed: 0.0,
+            duration: duration_secs,
+        });
+        self.current_preset = None;
+    }
+
+    /// Randomize all parameters from a fresh seed (kept short, so a look
+    /// worth keeping can be shared as just the number)
     pub fn randomize(&mut self) {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64 % 1_000_000)
+            .unwrap_or(12345);
+        self.randomize_with_seed(seed);
+    }
+
+    /// Randomize all parameters; the same seed always gives the same patch
+    pub fn randomize_with_seed(&mut self, seed: u64) {
         use crate::synth::*;
 
+        self.random_seed = seed;
+
+        // Simple LCG random - not cryptographic but fine for this
+        let state = std::cell::Cell::new(seed);
         let rand = || -> f32 {
-            // Simple LCG random - not cryptographic but fine for this
-            static mut SEED: u32 = 12345;
-            unsafe {
-                SEED = SEED.wrapping_mul(1103515245).wrapping_add(12345);
-                (SEED as f32) / (u32::MAX as f32)
-            }
+            let next = state
+                .get()
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            state.set(next);
+            // Top 24 bits are the best-mixed; gives 0 <= x < 1
+            (next >> 40) as f32 / (1u64 << 24) as f32
         };
 
+        // Start from defaults so fields the randomizer skips don't leak in
+        self.synth = SynthState::default();
+
         let rand_range = |min: f32, max: f32| min + rand() * (max - min);
         let rand_int = |max: u32| (rand() * max as f32) as u32;
 
@@ -172,6 +2064,7 @@ impl App {
         self.synth.input.frequency = rand_range(1.0, 12.0);
         self.synth.input.phase = rand();
         self.synth.input.rotation = rand();
+        self.synth.input.noise_seed = rand_int(10000);
 
         // Geometry - be conservative to avoid chaos
         self.synth.geometry.wobbulate_h = rand_range(0.0, 0.3);
@@ -182,6 +2075,8 @@ impl App {
         self.synth.geometry.lissajous_y = rand_range(0.0, 0.3);
         self.synth.geometry.rotation = rand_range(0.0, 0.1);
         self.synth.geometry.scale = rand_range(0.8, 1.2);
+        self.synth.geometry.rotate_center_x = rand_range(0.3, 0.7);
+        self.synth.geometry.rotate_center_y = rand_range(0.3, 0.7);
 
         // Amplitude
         self.synth.amplitude.fold_gain = rand_range(1.0, 4.0);
@@ -191,7 +2086,12 @@ impl App {
         self.synth.amplitude.soft_clip = rand_range(0.0, 0.5);
         self.synth.amplitude.solarize = rand_range(0.5, 1.0);
         self.synth.amplitude.gate_threshold = rand_range(0.0, 0.3);
-        self.synth.amplitude.invert = if rand() > 0.8 { 1.0 } else { 0.0 };
+        self.synth.amplitude.invert = if rand() > 0.8 {
+            [true; 3]
+        } else {
+            [rand() > 0.9, rand() > 0.9, rand() > 0.9]
+        };
+        self.synth.amplitude.solarize_channels = [rand() > 0.2, rand() > 0.2, rand() > 0.2];
 
         // Colorize
         self.synth.colorize.mode = match rand_int(4) {
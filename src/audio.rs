@@ -0,0 +1,271 @@
+//! Audio input for audio-reactive modulation
+//!
+//! Listens on the default input device and turns the most recent window of
+//! samples into an overall level (RMS) and low/mid/high band magnitudes,
+//! each 0-1. Without the `audio` feature there is never an input device.
+
+#[cfg(feature = "audio")]
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "audio")]
+use std::sync::{Arc, Mutex};
+
+/// Samples analyzed per update (a power of two for the FFT)
+#[cfg(feature = "audio")]
+const WINDOW: usize = 1024;
+
+/// Upper edges of the low and mid bands, in Hz
+#[cfg(feature = "audio")]
+const LOW_MAX_HZ: f32 = 250.0;
+#[cfg(feature = "audio")]
+const MID_MAX_HZ: f32 = 2000.0;
+
+/// How fast a band falls back after a hit (per second); rises are immediate
+#[cfg(feature = "audio")]
+const RELEASE_RATE: f32 = 8.0;
+
+/// How fast the auto-gain forgets a loud peak (fraction kept per second)
+#[cfg(feature = "audio")]
+const PEAK_DECAY: f32 = 0.5;
+
+/// Quietest peak the auto-gain scales up to full (about -50 dBFS), so
+/// silence and mic hiss stay near 0 instead of filling the range
+#[cfg(feature = "audio")]
+const NOISE_FLOOR: f32 = 3e-3;
+
+/// Measure a parameter can follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AudioBand {
+    /// Overall loudness (RMS)
+    Level,
+    /// Bass, below 250 Hz
+    #[default]
+    Low,
+    /// 250 Hz - 2 kHz
+    Mid,
+    /// Above 2 kHz
+    High,
+}
+
+impl AudioBand {
+    pub const ALL: [Self; 4] = [Self::Level, Self::Low, Self::Mid, Self::High];
+}
+
+/// Current level of each measure, 0-1
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AudioLevels {
+    pub level: f32,
+    pub low: f32,
+    pub mid: f32,
+    pub high: f32,
+}
+
+impl AudioLevels {
+    pub fn band(&self, band: AudioBand) -> f32 {
+        match band {
+            AudioBand::Level => self.level,
+            AudioBand::Low => self.low,
+            AudioBand::Mid => self.mid,
+            AudioBand::High => self.high,
+        }
+    }
+}
+
+/// Open input stream and its analysis state
+#[cfg(feature = "audio")]
+pub struct AudioInput {
+    _stream: cpal::Stream,
+    /// Most recent mono samples, written by the audio thread
+    samples: Arc<Mutex<Vec<f32>>>,
+    sample_rate: f32,
+    levels: AudioLevels,
+    /// Loudest recent raw value per measure, for auto-gain
+    peaks: [f32; 4],
+}
+
+#[cfg(feature = "audio")]
+impl AudioInput {
+    /// Open the default input device; None (logged) if there isn't a usable one
+    pub fn open() -> Option<Self> {
+        match Self::try_open() {
+            Ok(input) => Some(input),
+            Err(e) => {
+                log::warn!("No audio input ({e}); audio binds are disabled");
+                None
+            }
+        }
+    }
+
+    fn try_open() -> Result<Self, String> {
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or("no default input device")?;
+        let config = device.default_input_config().map_err(|e| e.to_string())?;
+        let channels = config.channels() as usize;
+        let sample_rate = config.sample_rate().0 as f32;
+        let format = config.sample_format();
+        let config: cpal::StreamConfig = config.into();
+
+        let samples = Arc::new(Mutex::new(Vec::with_capacity(WINDOW * 2)));
+        let writer = samples.clone();
+        let stream = match format {
+            cpal::SampleFormat::F32 => device.build_input_stream(
+                &config,
+                move |data: &[f32], _: &_| push_frames(&writer, data, channels, |s| s),
+                stream_error,
+                None,
+            ),
+            cpal::SampleFormat::I16 => device.build_input_stream(
+                &config,
+                move |data: &[i16], _: &_| push_frames(&writer, data, channels, |s| s as f32 / i16::MAX as f32),
+                stream_error,
+                None,
+            ),
+            cpal::SampleFormat::U16 => device.build_input_stream(
+                &config,
+                move |data: &[u16], _: &_| push_frames(&writer, data, channels, |s| (s as f32 - 32768.0) / 32768.0),
+                stream_error,
+                None,
+            ),
+            format => return Err(format!("unsupported sample format {format:?}")),
+        }
+        .map_err(|e| e.to_string())?;
+        stream.play().map_err(|e| e.to_string())?;
+        log::info!("Audio input: {}", device.name().unwrap_or_default());
+
+        Ok(Self {
+            _stream: stream,
+            samples,
+            sample_rate,
+            levels: AudioLevels::default(),
+            peaks: [0.0; 4],
+        })
+    }
+
+    /// Analyze the latest samples; call once per frame
+    pub fn update(&mut self, dt: f32) -> AudioLevels {
+        let window = match self.samples.lock() {
+            Ok(samples) if samples.len() >= WINDOW => samples[samples.len() - WINDOW..].to_vec(),
+            _ => return self.levels,
+        };
+
+        let rms = (window.iter().map(|s| s * s).sum::<f32>() / WINDOW as f32).sqrt();
+
+        // Hann-windowed spectrum, averaged per band
+        let mut re: Vec<f32> = window
+            .iter()
+            .enumerate()
+            .map(|(i, s)| s * 0.5 * (1.0 - (std::f32::consts::TAU * i as f32 / WINDOW as f32).cos()))
+            .collect();
+        let mut im = vec![0.0; WINDOW];
+        fft(&mut re, &mut im);
+
+        let bin_hz = self.sample_rate / WINDOW as f32;
+        // A full-scale sine reads about 1 in its bin, like its RMS scale
+        let bin_scale = 4.0 / WINDOW as f32;
+        let mut sums = [0.0f32; 3];
+        let mut counts = [0u32; 3];
+        for bin in 1..WINDOW / 2 {
+            let hz = bin as f32 * bin_hz;
+            let band = if hz < LOW_MAX_HZ { 0 } else if hz < MID_MAX_HZ { 1 } else { 2 };
+            sums[band] += (re[bin] * re[bin] + im[bin] * im[bin]).sqrt() * bin_scale;
+            counts[band] += 1;
+        }
+        let band = |i: usize| sums[i] / counts[i].max(1) as f32;
+        let raw = [rms, band(0), band(1), band(2)];
+
+        // Auto-gain against each measure's recent peak, then fast attack and
+        // smooth release
+        let keep = PEAK_DECAY.powf(dt);
+        let release = 1.0 - (-RELEASE_RATE * dt).exp();
+        let levels = [
+            &mut self.levels.level,
+            &mut self.levels.low,
+            &mut self.levels.mid,
+            &mut self.levels.high,
+        ];
+        for ((level, peak), value) in levels.into_iter().zip(&mut self.peaks).zip(raw) {
+            *peak = (*peak * keep).max(value).max(NOISE_FLOOR);
+            let target = (value / *peak).clamp(0.0, 1.0);
+            *level = if target > *level { target } else { *level + (target - *level) * release };
+        }
+
+        self.levels
+    }
+}
+
+#[cfg(feature = "audio")]
+fn stream_error(e: cpal::StreamError) {
+    log::warn!("Audio input error: {e}");
+}
+
+/// Downmix interleaved frames to mono and append them, keeping the last
+/// `WINDOW` samples
+#[cfg(feature = "audio")]
+fn push_frames<T: Copy>(samples: &Mutex<Vec<f32>>, data: &[T], channels: usize, to_f32: impl Fn(T) -> f32) {
+    let Ok(mut samples) = samples.lock() else {
+        return;
+    };
+    let channels = channels.max(1);
+    samples.extend(
+        data.chunks(channels)
+            .map(|frame| frame.iter().map(|&s| to_f32(s)).sum::<f32>() / channels as f32),
+    );
+    let excess = samples.len().saturating_sub(WINDOW);
+    samples.drain(..excess);
+}
+
+/// In-place radix-2 FFT; the length must be a power of two
+#[cfg(feature = "audio")]
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -std::f32::consts::TAU / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * cos - im[b] * sin;
+                let t_im = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Built without the `audio` feature: no input to open
+#[cfg(not(feature = "audio"))]
+pub struct AudioInput;
+
+#[cfg(not(feature = "audio"))]
+impl AudioInput {
+    pub fn open() -> Option<Self> {
+        log::warn!("Built without the audio feature; audio binds are disabled");
+        None
+    }
+
+    pub fn update(&mut self, _dt: f32) -> AudioLevels {
+        AudioLevels::default()
+    }
+}
@@ -2,24 +2,89 @@
 //!
 //! Ported from main Phosphlux, simplified for Lite's fixed pipeline.
 
-use crate::synth::SynthState;
-use instant::Instant;
+use crate::audio::{AudioBand, AudioLevels};
+use crate::synth::{BlendMode, ColorMode, FeedbackWrap, GradientSource, InputSource, OscWaveform, ParamSpec, SynthState, PARAMS};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// LFO waveform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LfoShape {
+    #[default]
+    Sine,
+    Triangle,
+    /// Holds `hi` for the first half of the cycle, then `lo`
+    Square,
+    /// Ramps from `lo` to `hi` over the cycle
+    Saw,
+    /// Ramps from `hi` to `lo` over the cycle
+    RampDown,
+    /// Sample and hold: a new random value each cycle
+    Random,
+}
+
+impl LfoShape {
+    pub const ALL: [Self; 6] = [
+        Self::Sine,
+        Self::Triangle,
+        Self::Square,
+        Self::Saw,
+        Self::RampDown,
+        Self::Random,
+    ];
+
+    /// Waveform at `phase` (0-1) of cycle number `cycle`, -1 to 1
+    fn value(self, phase: f32, cycle: i64, seed: u64) -> f32 {
+        match self {
+            Self::Sine => (phase * std::f32::consts::TAU).sin(),
+            // Peaks a quarter cycle in, like the sine
+            Self::Triangle => 1.0 - 4.0 * ((phase + 0.25).fract() - 0.5).abs(),
+            Self::Square => if phase < 0.5 { 1.0 } else { -1.0 },
+            Self::Saw => 2.0 * phase - 1.0,
+            Self::RampDown => 1.0 - 2.0 * phase,
+            Self::Random => sample_hold(cycle, seed),
+        }
+    }
+}
+
+/// Random value (-1 to 1) held through cycle `cycle`: splitmix64 of the
+/// cycle index and seed, so the same cycle always holds the same value
+fn sample_hold(cycle: i64, seed: u64) -> f32 {
+    let mut x = (cycle as u64)
+        .wrapping_add(0x9e3779b97f4a7c15)
+        .wrapping_add(seed.wrapping_mul(0xd1b54a32d192ed69));
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^= x >> 31;
+    (x >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0
+}
+
 /// LFO state for a single parameter
 #[derive(Clone, Serialize, Deserialize)]
 pub struct LfoState {
     /// Speed multiplier (0.1 = slow, 0.25 = medium, 0.5 = fast)
     pub speed: f32,
-    /// Lower bound of oscillation
+    /// Bound the oscillation starts away from (may exceed `hi`)
     pub lo: f32,
-    /// Upper bound of oscillation
+    /// Bound the oscillation rises toward first
     pub hi: f32,
     /// Phase offset (0.0-1.0)
     pub offset: f32,
     /// Tempo subdivision (0.25, 0.5, 1.0, 2.0, 4.0)
     pub subdivide: f32,
+    #[serde(default)]
+    pub shape: LfoShape,
+    /// Free-running: `speed` is cycles per second on its own clock,
+    /// ignoring the tempo and SYNC
+    #[serde(default)]
+    pub free: bool,
+    /// Random shape: picks the sequence of held values (0 = the original)
+    #[serde(default)]
+    pub seed: u64,
+    /// Random shape: fraction of each step spent gliding from the last
+    /// value (0 = hard jumps)
+    #[serde(default)]
+    pub slew: f32,
 }
 
 impl Default for LfoState {
@@ -30,71 +95,361 @@ impl Default for LfoState {
             hi: 1.0,
             offset: 0.0,
             subdivide: 1.0,
+            shape: LfoShape::Sine,
+            free: false,
+            seed: 0,
+            slew: 0.0,
         }
     }
 }
 
 impl LfoState {
-    /// Create slow LFO (0.1x BPM)
-    pub fn slow(min: f32, max: f32) -> Self {
+    /// LFO at `speed` (x BPM) sweeping the full `min`-`max` range
+    pub fn new(speed: f32, min: f32, max: f32) -> Self {
         Self {
-            speed: 0.1,
+            speed,
             lo: min,
             hi: max,
-            offset: 0.0,
-            subdivide: 1.0,
+            ..Self::default()
         }
     }
 
-    /// Create medium LFO (0.25x BPM)
-    pub fn medium(min: f32, max: f32) -> Self {
-        Self {
-            speed: 0.25,
-            lo: min,
-            hi: max,
-            offset: 0.0,
-            subdivide: 1.0,
+    /// Compute LFO value at a position on its clock: beats, or seconds for
+    /// a free LFO. Rises from center toward `hi` first; with `hi < lo` the
+    /// sweep is reversed.
+    pub fn compute(&self, clock: f64) -> f32 {
+        let cycles_per_tick = (self.speed * self.subdivide) as f64;
+        self.value_at_cycles(clock * cycles_per_tick)
+    }
+
+    /// LFO value at `position` (0-1) through a loop `loop_ticks` long on
+    /// its clock, its rate rounded to whole cycles per loop (at least one)
+    pub fn compute_looped(&self, position: f64, loop_ticks: f64) -> f32 {
+        let cycles = (self.speed * self.subdivide) as f64 * loop_ticks;
+        self.value_at_cycles(position * cycles.round().max(1.0))
+    }
+
+    /// LFO value at a point in its cycle (0-1, before the phase offset)
+    pub fn value_at(&self, phase: f32) -> f32 {
+        self.value_at_cycles(phase as f64)
+    }
+
+    /// LFO value after `cycles` whole and partial cycles (before the phase
+    /// offset); the whole part picks the sample-and-hold value
+    fn value_at_cycles(&self, cycles: f64) -> f32 {
+        let cycles = cycles + self.offset as f64;
+        let (phase, cycle) = (cycles.fract() as f32, cycles.floor() as i64);
+        let mut wave = self.shape.value(phase, cycle, self.seed);
+        if self.shape == LfoShape::Random && self.slew > 0.0 {
+            // Ease in from the previous step's value
+            let t = (phase / self.slew).min(1.0);
+            let from = sample_hold(cycle - 1, self.seed);
+            wave = from + (wave - from) * t * t * (3.0 - 2.0 * t);
+        }
+        let center = (self.lo + self.hi) / 2.0;
+        let range = (self.hi - self.lo) / 2.0;
+        let val = center + range * wave;
+        val.clamp(self.lo.min(self.hi), self.lo.max(self.hi))
+    }
+}
+
+/// The LFOs on one parameter: a primary sweep and an optional second layer
+/// summed on top of it, for beating and phasing a single LFO can't make
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ParamLfos {
+    pub primary: LfoState,
+    /// Adds its swing around its own center (lo to hi) to the primary
+    #[serde(default)]
+    pub secondary: Option<LfoState>,
+}
+
+impl ParamLfos {
+    /// Combined value, with `value` evaluating one LFO; clamped to the
+    /// parameter's range
+    fn combine(&self, key: &str, value: impl Fn(&LfoState) -> f32) -> f32 {
+        let Some(secondary) = &self.secondary else {
+            return value(&self.primary);
+        };
+        let swing = value(secondary) - (secondary.lo + secondary.hi) / 2.0;
+        let combined = value(&self.primary) + swing;
+        match ParamSpec::find(key) {
+            Some(spec) => combined.clamp(spec.min, spec.max),
+            None => combined,
         }
     }
+}
+
+/// Step sequencer for a discrete parameter (enum or bool)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StepperState {
+    /// Option indices to cycle through, in order (bools: 0 = off, 1 = on)
+    pub steps: Vec<u32>,
+    /// Beats per step (1 = every beat, 4 = every bar)
+    pub beats_per_step: f32,
+}
 
-    /// Create fast LFO (0.5x BPM)
-    pub fn fast(min: f32, max: f32) -> Self {
+impl StepperState {
+    /// Step through every option once per bar
+    pub fn new(option_count: u32) -> Self {
         Self {
-            speed: 0.5,
-            lo: min,
-            hi: max,
-            offset: 0.0,
-            subdivide: 1.0,
+            steps: (0..option_count).collect(),
+            beats_per_step: 4.0,
         }
     }
 
-    /// Compute LFO value at given time
-    pub fn compute(&self, time_secs: f32, bpm_hz: f32) -> f32 {
-        let center = (self.lo + self.hi) / 2.0;
-        let range = (self.hi - self.lo) / 2.0;
-        let effective_hz = bpm_hz * self.speed * self.subdivide;
-        let phase = time_secs * effective_hz + self.offset;
-        let val = center + range * (phase * std::f32::consts::TAU).sin();
-        val.clamp(self.lo, self.hi)
+    /// Option index at a position on the beat clock
+    pub fn compute(&self, beats: f64) -> Option<u32> {
+        if self.steps.is_empty() {
+            return None;
+        }
+        let step = (beats / self.beats_per_step as f64).floor() as i64;
+        Some(self.steps[step.rem_euclid(self.steps.len() as i64) as usize])
+    }
+
+    /// Option index at `position` (0-1) through a loop of `loop_beats`, the
+    /// step length rounded so the sequence plays a whole number of times
+    pub fn compute_looped(&self, position: f64, loop_beats: f64) -> Option<u32> {
+        if self.steps.is_empty() {
+            return None;
+        }
+        let len = self.steps.len() as f64;
+        let passes = (loop_beats / self.beats_per_step as f64 / len).round().max(1.0);
+        let step = (position * passes * len).floor() as usize;
+        Some(self.steps[step % self.steps.len()])
+    }
+}
+
+/// Audio bind: a parameter following one audio measure
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AudioBind {
+    pub band: AudioBand,
+    /// Value at silence
+    pub lo: f32,
+    /// Value at the loudest recent level
+    pub hi: f32,
+}
+
+impl AudioBind {
+    pub fn compute(&self, levels: &AudioLevels) -> f32 {
+        self.lo + (self.hi - self.lo) * levels.band(self.band)
+    }
+}
+
+/// What drives a continuous parameter: LFOs on the beat clock, or an
+/// audio measure. A parameter follows one source at a time.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum ModSource {
+    Lfo(ParamLfos),
+    Audio(AudioBind),
+}
+
+/// Mod matrix route: one parameter's current value modulating another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModRoute {
+    /// "stage.param" key read
+    pub source: String,
+    /// "stage.param" key modulated
+    pub dest: String,
+    /// -1 to 1: fraction of the destination's range added when the source
+    /// is at its maximum (nothing at its minimum)
+    pub amount: f32,
+}
+
+/// Most routes the mod matrix holds
+pub const MAX_MOD_ROUTES: usize = 8;
+
+/// Add each route's contribution to its destination, clamped to the
+/// destination's range. Sources are all read before any destination is
+/// written, so routes can't chain or feed back within a frame.
+pub fn apply_mod_routes(routes: &[ModRoute], synth: &mut SynthState) {
+    let offsets: Vec<(&str, f32)> = routes
+        .iter()
+        .filter_map(|route| {
+            let source = ParamSpec::find(&route.source)?;
+            let dest = ParamSpec::find(&route.dest)?;
+            let value = synth.param(source.key)?;
+            let level = (value - source.min) / (source.max - source.min);
+            Some((dest.key, level * route.amount * (dest.max - dest.min)))
+        })
+        .collect();
+
+    for (key, offset) in offsets {
+        if let (Some(spec), Some(value)) = (ParamSpec::find(key), synth.param_mut(key)) {
+            *value = (*value + offset).clamp(spec.min, spec.max);
+        }
+    }
+}
+
+/// A recorded value of one parameter
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Keyframe {
+    /// Seconds from the start of the loop
+    pub time: f32,
+    pub value: f32,
+}
+
+/// Manual slider moves captured for looped playback
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GestureRecording {
+    /// Keyframes per "stage.param" key, in time order
+    pub tracks: HashMap<String, Vec<Keyframe>>,
+    /// Loop length in seconds
+    pub length: f32,
+}
+
+impl GestureRecording {
+    /// Set every recorded parameter to its value `time` seconds into the loop
+    /// (each keyframe holds until the next, as it was dragged)
+    pub fn apply(&self, synth: &mut SynthState, time: f32) {
+        for (key, track) in &self.tracks {
+            let current = track.iter().take_while(|k| k.time <= time).last();
+            if let (Some(keyframe), Some(value)) = (current.or(track.last()), synth.param_mut(key)) {
+                *value = keyframe.value;
+            }
+        }
+    }
+}
+
+/// What the gesture recorder is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GestureMode {
+    #[default]
+    Idle,
+    Recording,
+    Playing,
+}
+
+/// Records parameter changes made by hand (sliders, nudges) and loops them
+/// back. Changes are found by comparing the patch with the last frame, so
+/// every control is captured without hooks in the UI; parameters under an
+/// LFO are skipped.
+#[derive(Default)]
+pub struct GestureRecorder {
+    pub recording: GestureRecording,
+    pub mode: GestureMode,
+    /// Seconds into the recording or loop
+    pub clock: f32,
+    /// Every `PARAMS` value last frame, while recording
+    last: Vec<f32>,
+}
+
+impl GestureRecorder {
+    /// Start a new recording, discarding the old one
+    pub fn record(&mut self, synth: &SynthState) {
+        self.recording = GestureRecording::default();
+        self.mode = GestureMode::Recording;
+        self.clock = 0.0;
+        self.last = PARAMS.iter().map(|spec| synth.param(spec.key).unwrap_or_default()).collect();
+    }
+
+    /// Finish recording and start looping it (nothing to loop = idle)
+    pub fn stop_recording(&mut self) {
+        self.recording.length = self.clock;
+        self.play();
+    }
+
+    pub fn play(&mut self) {
+        self.clock = 0.0;
+        self.mode = if self.recording.tracks.is_empty() || self.recording.length <= 0.0 {
+            GestureMode::Idle
+        } else {
+            GestureMode::Playing
+        };
+    }
+
+    pub fn stop(&mut self) {
+        self.mode = GestureMode::Idle;
+    }
+
+    /// Advance by `dt` seconds, capturing this frame's changes or applying
+    /// the loop (after LFOs, so playback wins)
+    pub fn update(&mut self, synth: &mut SynthState, automation: &AutomationState, dt: f32) {
+        match self.mode {
+            GestureMode::Idle => {}
+            GestureMode::Recording => {
+                self.clock += dt;
+                for (spec, last) in PARAMS.iter().zip(&mut self.last) {
+                    let Some(value) = synth.param(spec.key) else {
+                        continue;
+                    };
+                    if value == *last || automation.has_lfo(spec.key) {
+                        *last = value;
+                        continue;
+                    }
+                    let track = self.recording.tracks.entry(spec.key.to_string()).or_default();
+                    if track.is_empty() {
+                        // Start from the value before the first move
+                        track.push(Keyframe { time: 0.0, value: *last });
+                    }
+                    track.push(Keyframe { time: self.clock, value });
+                    *last = value;
+                }
+            }
+            GestureMode::Playing => {
+                self.clock = (self.clock + dt) % self.recording.length;
+                self.recording.apply(synth, self.clock);
+            }
+        }
     }
 }
 
 /// Global automation state
 pub struct AutomationState {
-    /// Active LFOs keyed by "stage.param" (e.g., "geometry.wobbulate_h")
-    pub lfos: HashMap<String, LfoState>,
+    /// LFO or audio bind driving each parameter, keyed by "stage.param"
+    /// (e.g., "geometry.wobbulate_h")
+    pub sources: HashMap<String, ModSource>,
+    /// Active steppers keyed by "stage.param" (e.g., "mixer.blend_mode")
+    pub steppers: HashMap<String, StepperState>,
+    /// Latest audio levels, set each frame by whoever owns the audio input
+    pub audio_levels: AudioLevels,
+    /// Whether an audio input is open (the LFO button offers binds only then)
+    pub audio_available: bool,
     /// Global tempo in BPM
     pub global_bpm: f32,
-    /// Start time for LFO phase calculation
-    start_time: Instant,
+    /// Slow / medium / fast speeds (x BPM) the LFO button steps through
+    pub lfo_speeds: [f32; 3],
+    /// Speeds typed into a parameter's LFO controls, offered first when
+    /// its LFO button next turns the LFO on
+    custom_speeds: HashMap<String, f32>,
+    /// Beat clock: beats elapsed at the current and past tempos.
+    /// Integrating beats (rather than time * tempo) keeps LFO phase continuous
+    /// and locked to the beat grid when the tempo changes.
+    beats: f64,
+    /// Seconds elapsed, the clock free LFOs run on (SYNC leaves it alone)
+    seconds: f64,
+    /// Snap the beat clock to the nearest beat once a tempo change settles
+    pub retrigger_on_bpm: bool,
+    /// Tempo seen on the last advance, and seconds since it last changed
+    last_bpm: f32,
+    bpm_settle: f32,
+    /// Loop mode: (position 0-1, loop length in beats, in seconds). LFOs
+    /// and steppers then complete whole cycles per loop instead of
+    /// following the clock.
+    looping: Option<(f64, f64, f64)>,
 }
 
+/// Factory slow / medium / fast LFO speeds
+pub const DEFAULT_LFO_SPEEDS: [f32; 3] = [0.1, 0.25, 0.5];
+
+/// Seconds the tempo must hold still before a retrigger (lets BPM drags finish)
+const BPM_SETTLE_SECS: f32 = 0.25;
+
 impl Default for AutomationState {
     fn default() -> Self {
         Self {
-            lfos: HashMap::new(),
+            sources: HashMap::new(),
+            steppers: HashMap::new(),
+            audio_levels: AudioLevels::default(),
+            audio_available: false,
             global_bpm: 120.0,
-            start_time: Instant::now(),
+            lfo_speeds: DEFAULT_LFO_SPEEDS,
+            custom_speeds: HashMap::new(),
+            beats: 0.0,
+            seconds: 0.0,
+            retrigger_on_bpm: false,
+            last_bpm: 120.0,
+            bpm_settle: BPM_SETTLE_SECS,
+            looping: None,
         }
     }
 }
@@ -104,153 +459,297 @@ impl AutomationState {
         Self::default()
     }
 
-    /// Get elapsed time since automation started
-    pub fn lfo_time(&self) -> f32 {
-        self.start_time.elapsed().as_secs_f32()
+    /// Advance the beat clock by `dt` seconds at the current tempo
+    pub fn advance(&mut self, dt: f32) {
+        self.beats += dt as f64 * self.global_bpm as f64 / 60.0;
+        self.seconds += dt as f64;
+
+        if self.global_bpm != self.last_bpm {
+            self.last_bpm = self.global_bpm;
+            self.bpm_settle = 0.0;
+        } else if self.bpm_settle < BPM_SETTLE_SECS {
+            self.bpm_settle += dt;
+            if self.bpm_settle >= BPM_SETTLE_SECS && self.retrigger_on_bpm {
+                self.beats = self.beats.round();
+            }
+        }
+    }
+
+    /// Restart the beat clock so every synced LFO is on its downbeat now
+    pub fn sync(&mut self) {
+        self.beats = 0.0;
     }
 
-    /// Apply all active LFOs to synth state
+    /// Restart both clocks, free LFOs included (for a clean start, as
+    /// opposed to a musical resync)
+    pub fn reset_clocks(&mut self) {
+        self.beats = 0.0;
+        self.seconds = 0.0;
+    }
+
+    /// Drive LFOs and steppers from a loop position (0-1) instead of the
+    /// beat clock, for a loop `loop_secs` long at the current tempo; None
+    /// returns to the clock
+    pub fn set_loop_position(&mut self, position: Option<f64>, loop_secs: f64) {
+        self.looping = position.map(|position| (position, loop_secs * self.global_bpm as f64 / 60.0, loop_secs));
+    }
+
+    /// Apply all active LFOs, steppers and audio binds to synth state
     /// Returns true if any parameters were modified
     pub fn apply(&self, synth: &mut SynthState) -> bool {
-        if self.lfos.is_empty() {
+        if self.sources.is_empty() && self.steppers.is_empty() {
             return false;
         }
 
-        let bpm_hz = self.global_bpm / 60.0;
-        let time = self.lfo_time();
         let mut modified = false;
 
-        for (key, lfo) in &self.lfos {
-            let val = lfo.compute(time, bpm_hz);
+        for (key, source) in &self.sources {
+            let val = match source {
+                ModSource::Lfo(lfos) => lfos.combine(key, |lfo| match (self.looping, lfo.free) {
+                    (Some((position, loop_beats, _)), false) => lfo.compute_looped(position, loop_beats),
+                    (Some((position, _, loop_secs)), true) => lfo.compute_looped(position, loop_secs),
+                    (None, false) => lfo.compute(self.beats),
+                    (None, true) => lfo.compute(self.seconds),
+                }),
+                ModSource::Audio(bind) => bind.compute(&self.audio_levels),
+            };
             if self.set_param(synth, key, val) {
                 modified = true;
             }
         }
 
+        for (key, stepper) in &self.steppers {
+            let index = match self.looping {
+                Some((position, loop_beats, _)) => stepper.compute_looped(position, loop_beats),
+                None => stepper.compute(self.beats),
+            };
+            if index.is_some_and(|index| self.set_step(synth, key, index)) {
+                modified = true;
+            }
+        }
+
         modified
     }
 
-    /// Set a parameter value by key
-    fn set_param(&self, synth: &mut SynthState, key: &str, val: f32) -> bool {
-        let parts: Vec<&str> = key.split('.').collect();
-        if parts.len() != 2 {
-            return false;
+    /// Set every LFO-driven parameter to its value at `phase` (0-1) of the
+    /// LFO's cycle, so a still captured from an animated patch is reproducible
+    pub fn apply_at_phase(&self, synth: &mut SynthState, phase: f32) {
+        for (key, source) in &self.sources {
+            if let ModSource::Lfo(lfos) = source {
+                self.set_param(synth, key, lfos.combine(key, |lfo| lfo.value_at(phase)));
+            }
+        }
+    }
+
+    /// Set a parameter value by key (shared with remote control)
+    pub fn set_param(&self, synth: &mut SynthState, key: &str, val: f32) -> bool {
+        match synth.param_mut(key) {
+            Some(param) => {
+                *param = val;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set a discrete parameter by key to the option at `index`
+    pub fn set_step(&self, synth: &mut SynthState, key: &str, index: u32) -> bool {
+        /// Assign `options[index]`, ignoring indices out of range
+        fn pick<T: Copy>(options: &[T], index: u32, target: &mut T) -> bool {
+            match options.get(index as usize) {
+                Some(&option) => {
+                    *target = option;
+                    true
+                }
+                None => false,
+            }
         }
 
-        let (stage, param) = (parts[0], parts[1]);
-
-        match stage {
-            "input" => match param {
-                "mix" => synth.input.mix = val,
-                "frequency" => synth.input.frequency = val,
-                "phase" => synth.input.phase = val,
-                "rotation" => synth.input.rotation = val,
-                _ => return false,
-            },
-            "geometry" => match param {
-                "wobbulate_h" => synth.geometry.wobbulate_h = val,
-                "wobbulate_v" => synth.geometry.wobbulate_v = val,
-                "wobble_freq" => synth.geometry.wobble_freq = val,
-                "z_displacement" => synth.geometry.z_displacement = val,
-                "lissajous_x" => synth.geometry.lissajous_x = val,
-                "lissajous_y" => synth.geometry.lissajous_y = val,
-                "rotation" => synth.geometry.rotation = val,
-                "scale" => synth.geometry.scale = val,
-                _ => return false,
-            },
-            "amplitude" => match param {
-                "fold_gain" => synth.amplitude.fold_gain = val,
-                "fold_mix" => synth.amplitude.fold_mix = val,
-                "quantize_levels" => synth.amplitude.quantize_levels = val,
-                "quantize_mix" => synth.amplitude.quantize_mix = val,
-                "soft_clip" => synth.amplitude.soft_clip = val,
-                "solarize" => synth.amplitude.solarize = val,
-                "gate_threshold" => synth.amplitude.gate_threshold = val,
-                _ => return false,
-            },
-            "colorize" => match param {
-                "hue_offset" => synth.colorize.hue_offset = val,
-                "saturation" => synth.colorize.saturation = val,
-                "levels" => synth.colorize.levels = val,
-                _ => return false,
-            },
-            "mixer" => match param {
-                "feedback_mix" => synth.mixer.feedback_mix = val,
-                "key_threshold" => synth.mixer.key_threshold = val,
-                "key_softness" => synth.mixer.key_softness = val,
-                "layer_opacity" => synth.mixer.layer_opacity = val,
-                _ => return false,
-            },
-            "feedback" => match param {
-                "zoom" => synth.feedback.zoom = val,
-                "rotation" => synth.feedback.rotation = val,
-                "hue_shift" => synth.feedback.hue_shift = val,
-                "decay" => synth.feedback.decay = val,
-                "offset_x" => synth.feedback.offset_x = val,
-                "offset_y" => synth.feedback.offset_y = val,
-                "saturation" => synth.feedback.saturation = val,
-                _ => return false,
-            },
-            "output" => match param {
-                "scanlines" => synth.output.scanlines = val,
-                "bloom" => synth.output.bloom = val,
-                "vignette" => synth.output.vignette = val,
-                "tracking" => synth.output.tracking = val,
-                "chroma_shift" => synth.output.chroma_shift = val,
-                "tape_wobble" => synth.output.tape_wobble = val,
-                "vhs_noise" => synth.output.vhs_noise = val,
-                "bandwidth" => synth.output.bandwidth = val,
-                "ghosting" => synth.output.ghosting = val,
-                "cable_noise" => synth.output.cable_noise = val,
-                _ => return false,
-            },
-            _ => return false,
+        match key {
+            "input.source_a" => pick(&InputSource::ALL, index, &mut synth.input.source_a),
+            "input.source_b" => pick(&InputSource::ALL, index, &mut synth.input.source_b),
+            "input.osc_waveform" => pick(&OscWaveform::ALL, index, &mut synth.input.osc_waveform),
+            "colorize.mode" => pick(&ColorMode::ALL, index, &mut synth.colorize.mode),
+            "colorize.gradient_source" => pick(&GradientSource::ALL, index, &mut synth.colorize.gradient_source),
+            "mixer.blend_mode" => pick(&BlendMode::ALL, index, &mut synth.mixer.blend_mode),
+            "mixer.key_invert" => pick(&[false, true], index, &mut synth.mixer.key_invert),
+            "feedback.wrap" => pick(&FeedbackWrap::ALL, index, &mut synth.feedback.wrap),
+            _ => false,
         }
-        true
     }
 
-    /// Cycle LFO state: Off -> Slow -> Medium -> Fast -> Off
+    /// Cycle LFO state: Off -> (Custom) -> Slow -> Medium -> Fast -> Audio
+    /// -> Off (Custom only after a speed was typed in, Audio only when an
+    /// audio input is open)
     pub fn cycle_lfo(&mut self, key: &str, min: f32, max: f32) {
-        if let Some(lfo) = self.lfos.get(key) {
-            let next_state = if lfo.speed < 0.15 {
-                // Currently slow -> medium
-                Some(LfoState::medium(min, max))
-            } else if lfo.speed < 0.4 {
-                // Currently medium -> fast
-                Some(LfoState::fast(min, max))
-            } else {
-                // Currently fast -> off
-                None
-            };
+        let next_speed = match (self.sources.get(key), self.lfo_bucket(key)) {
+            (Some(ModSource::Audio(_)), _) => {
+                self.sources.remove(key);
+                return;
+            }
+            (None, _) => Some(self.custom_speeds.get(key).copied().unwrap_or(self.lfo_speeds[0])),
+            (Some(ModSource::Lfo(_)), None) => Some(self.lfo_speeds[0]),
+            (Some(ModSource::Lfo(_)), Some(tier)) => self.lfo_speeds.get(tier + 1).copied(),
+        };
 
-            if let Some(new_lfo) = next_state {
-                self.lfos.insert(key.to_string(), new_lfo);
-            } else {
-                self.lfos.remove(key);
+        match (next_speed, self.get_lfo_mut(key)) {
+            // Stepping speed keeps the primary's settings and any layer
+            (Some(speed), Some(lfos)) => lfos.primary.speed = speed,
+            (Some(speed), None) => {
+                let lfos = ParamLfos {
+                    primary: LfoState::new(speed, min, max),
+                    secondary: None,
+                };
+                self.sources.insert(key.to_string(), ModSource::Lfo(lfos));
+            }
+            (None, _) => {
+                self.sources.remove(key);
+                if self.audio_available {
+                    let bind = AudioBind { band: AudioBand::default(), lo: min, hi: max };
+                    self.sources.insert(key.to_string(), ModSource::Audio(bind));
+                }
             }
+        }
+    }
+
+    /// Speed tier of a parameter's LFO if its speed is exactly one of
+    /// `lfo_speeds` (None for a typed-in speed)
+    pub fn lfo_bucket(&self, key: &str) -> Option<usize> {
+        let lfo = &self.get_lfo(key)?.primary;
+        self.lfo_speeds.iter().position(|&speed| (speed - lfo.speed).abs() < 1e-4)
+    }
+
+    /// Remember a speed typed into a parameter's LFO controls, so cycling
+    /// its LFO off and on comes back to it
+    pub fn set_custom_speed(&mut self, key: &str, speed: f32) {
+        if let Some(lfos) = self.get_lfo_mut(key) {
+            lfos.primary.speed = speed;
+        }
+        if self.lfo_speeds.iter().any(|&bucket| (bucket - speed).abs() < 1e-4) {
+            self.custom_speeds.remove(key);
         } else {
-            // Currently off -> slow
-            self.lfos.insert(key.to_string(), LfoState::slow(min, max));
+            self.custom_speeds.insert(key.to_string(), speed);
         }
     }
 
-    /// Remove LFO from a parameter
+    /// Speed tier (0 = slow, 1 = medium, 2 = fast) of a parameter's LFO:
+    /// whichever of `lfo_speeds` its speed is closest to
+    pub fn lfo_tier(&self, key: &str) -> Option<usize> {
+        let lfo = &self.get_lfo(key)?.primary;
+        (0..self.lfo_speeds.len()).min_by(|&a, &b| {
+            let distance = |tier: usize| (self.lfo_speeds[tier] - lfo.speed).abs();
+            distance(a).total_cmp(&distance(b))
+        })
+    }
+
+    /// Remove LFO (or audio bind) from a parameter
     pub fn remove_lfo(&mut self, key: &str) {
-        self.lfos.remove(key);
+        self.sources.remove(key);
     }
 
-    /// Check if LFO is active for a parameter
+    /// Check if an LFO or audio bind is driving a parameter
     pub fn has_lfo(&self, key: &str) -> bool {
-        self.lfos.contains_key(key)
+        self.sources.contains_key(key)
     }
 
-    /// Get LFO state for a parameter
-    pub fn get_lfo(&self, key: &str) -> Option<&LfoState> {
-        self.lfos.get(key)
+    /// Get LFO state (primary and any second layer) for a parameter
+    pub fn get_lfo(&self, key: &str) -> Option<&ParamLfos> {
+        match self.sources.get(key)? {
+            ModSource::Lfo(lfos) => Some(lfos),
+            ModSource::Audio(_) => None,
+        }
     }
 
     /// Get mutable LFO state for a parameter
-    pub fn get_lfo_mut(&mut self, key: &str) -> Option<&mut LfoState> {
-        self.lfos.get_mut(key)
+    pub fn get_lfo_mut(&mut self, key: &str) -> Option<&mut ParamLfos> {
+        match self.sources.get_mut(key)? {
+            ModSource::Lfo(lfos) => Some(lfos),
+            ModSource::Audio(_) => None,
+        }
+    }
+
+    /// Add a second LFO layer to a parameter's LFO: the fast speed, swinging
+    /// a quarter of the parameter's range
+    pub fn add_lfo_layer(&mut self, key: &str, min: f32, max: f32) {
+        let speed = self.lfo_speeds[self.lfo_speeds.len() - 1];
+        if let Some(lfos) = self.get_lfo_mut(key) {
+            let depth = (max - min) / 8.0;
+            lfos.secondary = Some(LfoState::new(speed, -depth, depth));
+        }
+    }
+
+    /// Get the audio bind on a parameter
+    pub fn get_audio_bind(&self, key: &str) -> Option<&AudioBind> {
+        match self.sources.get(key)? {
+            ModSource::Audio(bind) => Some(bind),
+            ModSource::Lfo(_) => None,
+        }
+    }
+
+    /// Get mutable audio bind for a parameter
+    pub fn get_audio_bind_mut(&mut self, key: &str) -> Option<&mut AudioBind> {
+        match self.sources.get_mut(key)? {
+            ModSource::Audio(bind) => Some(bind),
+            ModSource::Lfo(_) => None,
+        }
+    }
+
+    /// Every parameter's LFOs (as presets and sessions store them)
+    pub fn lfos(&self) -> HashMap<String, ParamLfos> {
+        self.sources
+            .iter()
+            .filter_map(|(key, source)| match source {
+                ModSource::Lfo(lfos) => Some((key.clone(), lfos.clone())),
+                ModSource::Audio(_) => None,
+            })
+            .collect()
+    }
+
+    /// Every parameter's audio bind (as sessions store them)
+    pub fn audio_binds(&self) -> HashMap<String, AudioBind> {
+        self.sources
+            .iter()
+            .filter_map(|(key, source)| match source {
+                ModSource::Audio(bind) => Some((key.clone(), bind.clone())),
+                ModSource::Lfo(_) => None,
+            })
+            .collect()
+    }
+
+    /// Replace all LFOs with `lfos`; audio binds on other parameters stay
+    pub fn set_lfos(&mut self, lfos: HashMap<String, ParamLfos>) {
+        self.sources.retain(|_, source| matches!(source, ModSource::Audio(_)));
+        self.sources
+            .extend(lfos.into_iter().map(|(key, lfos)| (key, ModSource::Lfo(lfos))));
+    }
+
+    /// Replace all audio binds with `binds`; LFOs on other parameters stay
+    pub fn set_audio_binds(&mut self, binds: HashMap<String, AudioBind>) {
+        self.sources.retain(|_, source| matches!(source, ModSource::Lfo(_)));
+        self.sources
+            .extend(binds.into_iter().map(|(key, bind)| (key, ModSource::Audio(bind))));
+    }
+
+    /// Drop every LFO, stepper and audio bind
+    pub fn clear(&mut self) {
+        self.sources.clear();
+        self.steppers.clear();
+    }
+
+    /// Turn a stepper on (stepping through all `option_count` options) or off
+    pub fn toggle_stepper(&mut self, key: &str, option_count: u32) {
+        if self.steppers.remove(key).is_none() {
+            self.steppers.insert(key.to_string(), StepperState::new(option_count));
+        }
+    }
+
+    /// Remove stepper from a parameter
+    pub fn remove_stepper(&mut self, key: &str) {
+        self.steppers.remove(key);
+    }
+
+    /// Get mutable stepper state for a parameter
+    pub fn get_stepper_mut(&mut self, key: &str) -> Option<&mut StepperState> {
+        self.steppers.get_mut(key)
     }
 }
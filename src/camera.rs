@@ -0,0 +1,158 @@
+//! Webcam input for the Camera source
+//!
+//! A background thread owns the nokhwa camera (it isn't Send on every
+//! platform) and keeps the newest frame in a shared slot for the renderer to
+//! upload. Without the `camera` feature no cameras are found.
+
+#[cfg(feature = "camera")]
+use nokhwa::pixel_format::RgbFormat;
+#[cfg(feature = "camera")]
+use nokhwa::utils::{ApiBackend, CameraIndex, RequestedFormat, RequestedFormatType};
+#[cfg(feature = "camera")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "camera")]
+use std::sync::mpsc;
+#[cfg(feature = "camera")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "camera")]
+use std::thread::JoinHandle;
+
+/// One captured frame, RGBA8
+pub struct CameraFrame {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// Number of cameras the system reports (0 if the query fails)
+#[cfg(feature = "camera")]
+pub fn count() -> usize {
+    match nokhwa::query(ApiBackend::Auto) {
+        Ok(cameras) => cameras.len(),
+        Err(e) => {
+            log::warn!("Camera query failed: {e}");
+            0
+        }
+    }
+}
+
+/// Running capture from one camera; dropping it stops the capture
+#[cfg(feature = "camera")]
+pub struct Camera {
+    /// Device index this capture is from
+    pub index: u32,
+    /// Newest frame not yet taken
+    frame: Arc<Mutex<Option<CameraFrame>>>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[cfg(feature = "camera")]
+impl Camera {
+    /// Start capturing from device `index`; None (logged) if it can't be opened
+    pub fn open(index: u32) -> Option<Self> {
+        let frame = Arc::new(Mutex::new(None));
+        let running = Arc::new(AtomicBool::new(true));
+        let (opened, result) = mpsc::channel();
+
+        let thread = {
+            let frame = frame.clone();
+            let running = running.clone();
+            std::thread::spawn(move || {
+                let format = RequestedFormat::new::<RgbFormat>(RequestedFormatType::AbsoluteHighestFrameRate);
+                let mut camera = match nokhwa::Camera::new(CameraIndex::Index(index), format)
+                    .and_then(|mut camera| camera.open_stream().map(|()| camera))
+                {
+                    Ok(camera) => {
+                        let _ = opened.send(Ok(()));
+                        camera
+                    }
+                    Err(e) => {
+                        let _ = opened.send(Err(e.to_string()));
+                        return;
+                    }
+                };
+
+                while running.load(Ordering::Relaxed) {
+                    let decoded = match camera.frame().and_then(|buffer| buffer.decode_image::<RgbFormat>()) {
+                        Ok(decoded) => decoded,
+                        Err(e) => {
+                            log::warn!("Camera frame failed: {e}");
+                            std::thread::sleep(std::time::Duration::from_millis(100));
+                            continue;
+                        }
+                    };
+                    let (width, height) = decoded.dimensions();
+                    let rgba = decoded
+                        .into_raw()
+                        .chunks_exact(3)
+                        .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                        .collect();
+                    if let Ok(mut slot) = frame.lock() {
+                        *slot = Some(CameraFrame { width, height, rgba });
+                    }
+                }
+                let _ = camera.stop_stream();
+            })
+        };
+
+        match result.recv() {
+            Ok(Ok(())) => {
+                log::info!("Camera {index} opened");
+                Some(Self {
+                    index,
+                    frame,
+                    running,
+                    thread: Some(thread),
+                })
+            }
+            Ok(Err(e)) => {
+                log::warn!("Can't open camera {index}: {e}");
+                None
+            }
+            Err(_) => {
+                log::warn!("Can't open camera {index}: capture thread exited");
+                None
+            }
+        }
+    }
+
+    /// The newest frame, if one arrived since the last call
+    pub fn take_frame(&self) -> Option<CameraFrame> {
+        self.frame.lock().ok()?.take()
+    }
+}
+
+#[cfg(feature = "camera")]
+impl Drop for Camera {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Built without the `camera` feature: there are no cameras
+#[cfg(not(feature = "camera"))]
+pub fn count() -> usize {
+    0
+}
+
+/// Built without the `camera` feature: never capturing
+#[cfg(not(feature = "camera"))]
+pub struct Camera {
+    pub index: u32,
+}
+
+#[cfg(not(feature = "camera"))]
+impl Camera {
+    pub fn open(index: u32) -> Option<Self> {
+        log::warn!("Built without the camera feature; can't open camera {index}");
+        None
+    }
+
+    pub fn take_frame(&self) -> Option<CameraFrame> {
+        None
+    }
+}
@@ -0,0 +1,109 @@
+//! App settings kept between runs
+//!
+//! Saved as `config.json` in the platform config directory (e.g.
+//! `~/.config/phosphlux-lite` on Linux) when the window closes, and read at
+//! startup. Each field is read on its own, so one that's missing or from an
+//! older version keeps its default without losing the rest.
+
+use crate::app::{BezelSettings, SynthAspect};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// File name inside the config directory
+pub const CONFIG_FILE: &str = "config.json";
+
+/// Settings restored at startup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub bezel: BezelSettings,
+    pub bpm: f32,
+    /// Parameter smoothing time (Settings)
+    pub smoothing_secs: f32,
+    /// Synth resolution (None = picked for the display)
+    pub output_size: Option<[u32; 2]>,
+    pub aspect: SynthAspect,
+    /// Window inner size in physical pixels (None = start maximized)
+    pub window_size: Option<[u32; 2]>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            bezel: BezelSettings::default(),
+            bpm: 120.0,
+            smoothing_secs: 0.0,
+            output_size: None,
+            aspect: SynthAspect::default(),
+            window_size: None,
+        }
+    }
+}
+
+/// The platform config directory for the app, if there is one
+pub fn config_dir() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "phosphlux-lite").map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+/// Full path of the config file, if the platform has a config directory
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(CONFIG_FILE))
+}
+
+/// Path of another file kept beside the config (scenes, MIDI mappings);
+/// the working directory if the platform has no config directory
+pub fn user_file(name: &str) -> PathBuf {
+    config_dir().map_or_else(|| PathBuf::from(name), |dir| dir.join(name))
+}
+
+/// Read the saved settings; defaults if there are none yet or the file
+/// can't be read (logged)
+pub fn load() -> AppConfig {
+    let mut config = AppConfig::default();
+    let Some(path) = config_path() else {
+        return config;
+    };
+    if !path.is_file() {
+        return config;
+    }
+    let value: Value = match std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string()))
+    {
+        Ok(value) => value,
+        Err(e) => {
+            log::error!("Failed to load {}: {e}", path.display());
+            return config;
+        }
+    };
+
+    read_field(&value, "bezel", &mut config.bezel);
+    read_field(&value, "bpm", &mut config.bpm);
+    read_field(&value, "smoothing_secs", &mut config.smoothing_secs);
+    read_field(&value, "output_size", &mut config.output_size);
+    read_field(&value, "aspect", &mut config.aspect);
+    read_field(&value, "window_size", &mut config.window_size);
+    config
+}
+
+/// Write the settings, creating the config directory if needed
+pub fn save(config: &AppConfig) -> Result<(), String> {
+    let path = config_path().ok_or("no config directory on this platform")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Overwrite `field` with `key` from the saved object, if present and valid
+fn read_field<T: DeserializeOwned>(value: &Value, key: &str, field: &mut T) {
+    let Some(saved) = value.get(key) else {
+        return;
+    };
+    match T::deserialize(saved) {
+        Ok(saved) => *field = saved,
+        Err(e) => log::warn!("Ignoring saved {key}: {e}"),
+    }
+}
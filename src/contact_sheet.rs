@@ -0,0 +1,97 @@
+//! Contact sheet: render a grid of random patches to a single PNG
+
+use crate::app::App;
+use crate::renderer::{create_headless_device, Renderer};
+use image::{Rgba, RgbaImage};
+use std::path::Path;
+
+const THUMB_WIDTH: u32 = 160;
+const THUMB_HEIGHT: u32 = 120;
+/// Space under each thumbnail for its seed
+const LABEL_HEIGHT: u32 = 16;
+const GAP: u32 = 4;
+
+/// Frames rendered per patch so feedback has time to build up
+const WARMUP_FRAMES: u32 = 90;
+const FRAME_DT: f32 = 1.0 / 30.0;
+
+/// 3x5 digit glyphs, one row per entry, high bit on the left
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111], // 0
+    [0b010, 0b110, 0b010, 0b010, 0b111], // 1
+    [0b111, 0b001, 0b111, 0b100, 0b111], // 2
+    [0b111, 0b001, 0b111, 0b001, 0b111], // 3
+    [0b101, 0b101, 0b111, 0b001, 0b001], // 4
+    [0b111, 0b100, 0b111, 0b001, 0b111], // 5
+    [0b111, 0b100, 0b111, 0b101, 0b111], // 6
+    [0b111, 0b001, 0b010, 0b010, 0b010], // 7
+    [0b111, 0b101, 0b111, 0b101, 0b111], // 8
+    [0b111, 0b101, 0b111, 0b001, 0b111], // 9
+];
+
+/// Draw a number with its top-left at (x, y), each font pixel `scale` wide
+fn draw_number(img: &mut RgbaImage, value: u64, x: u32, y: u32, scale: u32, color: Rgba<u8>) {
+    for (i, ch) in value.to_string().bytes().enumerate() {
+        let glyph = &DIGITS[(ch - b'0') as usize];
+        let glyph_x = x + i as u32 * 4 * scale;
+
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let px = glyph_x + col * scale + dx;
+                        let py = y + row as u32 * scale + dy;
+                        if px < img.width() && py < img.height() {
+                            img.put_pixel(px, py, color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Render `count` random patches (seeds `first_seed`, `first_seed + 1`, ...)
+/// headlessly and tile them into a PNG at `path`, each labelled with its seed
+pub fn render(count: u32, first_seed: u64, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let (device, queue) = create_headless_device().ok_or("no GPU adapter available")?;
+    let mut renderer = Renderer::new(device, queue, THUMB_WIDTH, THUMB_HEIGHT);
+    if let Some(e) = renderer.take_shader_error() {
+        return Err(format!("shader failed to compile: {e}").into());
+    }
+
+    let columns = ((count as f32).sqrt().ceil() as u32).max(1);
+    let rows = count.div_ceil(columns);
+    let cell_width = THUMB_WIDTH + GAP;
+    let cell_height = THUMB_HEIGHT + LABEL_HEIGHT + GAP;
+    let mut sheet = RgbaImage::from_pixel(
+        columns * cell_width + GAP,
+        rows * cell_height + GAP,
+        Rgba([20, 20, 20, 255]),
+    );
+
+    for i in 0..count {
+        let seed = first_seed + i as u64;
+        let mut app = App::new();
+        app.randomize_with_seed(seed);
+
+        renderer.clear_feedback();
+        for _ in 0..WARMUP_FRAMES {
+            app.update(FRAME_DT);
+            renderer.render(&app.synth, &app.frame_params());
+        }
+
+        let x = GAP + (i % columns) * cell_width;
+        let y = GAP + (i / columns) * cell_height;
+        image::imageops::replace(&mut sheet, &renderer.capture_frame(), x as i64, y as i64);
+        draw_number(&mut sheet, seed, x, y + THUMB_HEIGHT + 3, 2, Rgba([220, 220, 220, 255]));
+
+        log::info!("Rendered seed {} ({}/{})", seed, i + 1, count);
+    }
+
+    sheet.save(path)?;
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//! Hue drift check: seeds the feedback loop with a flat color, then lets
+//! pure feedback with a known `hue_shift` run and reads back each frame to
+//! confirm the hue turns by that much per frame
+
+use crate::app::App;
+use crate::renderer::{create_headless_device, Renderer};
+use crate::synth::{ColorMode, EffectMix};
+use image::RgbaImage;
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 48;
+const FRAME_DT: f32 = 1.0 / 30.0;
+
+/// Frames of the flat color before feedback takes over
+const SEED_FRAMES: u32 = 4;
+/// Feedback-only frames measured
+const CHECK_FRAMES: u32 = 24;
+/// Hue rotation per frame under test (7.2 degrees)
+const HUE_SHIFT: f32 = 0.02;
+/// Allowed error in the average drift, as a fraction of the expected drift
+const TOLERANCE: f32 = 0.2;
+
+/// Expected and measured hue drift, in degrees per frame
+pub struct HueDrift {
+    pub expected: f32,
+    pub measured: f32,
+}
+
+impl HueDrift {
+    pub fn passed(&self) -> bool {
+        (self.measured - self.expected).abs() <= self.expected * TOLERANCE
+    }
+}
+
+/// Render the check headlessly and measure the average per-frame drift
+pub fn run() -> Result<HueDrift, Box<dyn std::error::Error>> {
+    let (device, queue) = create_headless_device().ok_or("no GPU adapter available")?;
+    let mut renderer = Renderer::new(device, queue, WIDTH, HEIGHT);
+    if let Some(e) = renderer.take_shader_error() {
+        return Err(format!("shader failed to compile: {e}").into());
+    }
+    // 8-bit feedback rounds the color a little every frame
+    renderer.set_float_feedback(true);
+
+    // A flat orange, fed back untouched apart from the hue shift
+    let mut app = App::new();
+    let synth = &mut app.synth;
+    synth.colorize.mode = ColorMode::Gradient;
+    synth.colorize.gradient_start = [1.0, 0.4, 0.1];
+    synth.colorize.gradient_end = [1.0, 0.4, 0.1];
+    synth.feedback.zoom = 1.0;
+    synth.feedback.decay = 1.0;
+    synth.feedback.hue_shift = HUE_SHIFT;
+    synth.mixer.feedback_mix = 0.0;
+    synth.output.vhs_enabled = false;
+    synth.output.cable_enabled = false;
+    synth.output.crt_enabled = false;
+    // Skip the fade-out of the default patch's CRT, so no scanlines or
+    // bloom get into the loop
+    app.effect_mix = EffectMix::target(&app.synth);
+
+    renderer.clear_feedback();
+    for _ in 0..SEED_FRAMES {
+        app.update(FRAME_DT);
+        renderer.render(&app.synth, &app.frame_params());
+    }
+
+    // Full feedback through the normalized Mix crossfade shows only the feedback
+    app.synth.mixer.feedback_mix = 1.0;
+    let mut last = dominant_hue(&renderer.capture_frame()).ok_or("seed frame has no color")?;
+    let mut total = 0.0;
+    for frame in 0..CHECK_FRAMES {
+        app.update(FRAME_DT);
+        renderer.render(&app.synth, &app.frame_params());
+        let hue = dominant_hue(&renderer.capture_frame())
+            .ok_or_else(|| format!("frame {frame} lost its color"))?;
+        // Signed difference, wrapped to -180..180
+        total += (hue - last + 540.0).rem_euclid(360.0) - 180.0;
+        last = hue;
+    }
+
+    Ok(HueDrift {
+        expected: HUE_SHIFT * 360.0,
+        measured: total / CHECK_FRAMES as f32,
+    })
+}
+
+/// Chroma-weighted circular mean of the frame's hue, in degrees
+fn dominant_hue(frame: &RgbaImage) -> Option<f32> {
+    let (mut x, mut y) = (0.0f32, 0.0f32);
+    for px in frame.pixels() {
+        let [r, g, b, _] = px.0.map(|c| c as f32 / 255.0);
+        let max = r.max(g).max(b);
+        let chroma = max - r.min(g).min(b);
+        if chroma < 1e-3 {
+            continue;
+        }
+        let sector = if max == r {
+            ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / chroma + 2.0
+        } else {
+            (r - g) / chroma + 4.0
+        };
+        let angle = (sector * 60.0).to_radians();
+        x += chroma * angle.cos();
+        y += chroma * angle.sin();
+    }
+    (x.hypot(y) > 1e-3).then(|| y.atan2(x).to_degrees().rem_euclid(360.0))
+}
@@ -0,0 +1,208 @@
+//! Keyboard shortcuts
+//!
+//! Every shortcut is declared once in `BINDINGS`; the handler and the help
+//! overlay both read the table, so the overlay can't go stale.
+
+use egui::Key;
+use phosphlux_lite::app::{App, SelectedStage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Panic,
+    TogglePause,
+    StepFrame,
+    Sync,
+    ClearFeedback,
+    Randomize,
+    ToggleFade,
+    /// Select the stage at the pressed key's index in `STAGE_ORDER`
+    SelectStage,
+    NudgeUp,
+    NudgeDown,
+    NudgeUpCoarse,
+    NudgeDownCoarse,
+    /// Recall the scene at the pressed key's index (store it with Shift)
+    Scene,
+    ToggleHelp,
+    SaveSession,
+    OpenSession,
+}
+
+pub struct KeyBinding {
+    /// Any of these triggers the action
+    pub keys: &'static [Key],
+    pub action: Action,
+    /// Only with Ctrl (Cmd on macOS) held; the others only without it
+    pub command: bool,
+    pub help: &'static str,
+}
+
+/// Stage tabs in panel order, selected by the number keys
+const STAGE_ORDER: [SelectedStage; 6] = [
+    SelectedStage::Input,
+    SelectedStage::Geometry,
+    SelectedStage::Amplitude,
+    SelectedStage::Colorize,
+    SelectedStage::Feedback,
+    SelectedStage::Output,
+];
+
+pub const BINDINGS: &[KeyBinding] = &[
+    KeyBinding {
+        keys: &[Key::Escape, Key::Backspace],
+        action: Action::Panic,
+        command: false,
+        help: "Panic: reset the patch, clear LFOs, mod routes and feedback",
+    },
+    KeyBinding {
+        keys: &[Key::Space, Key::P],
+        action: Action::TogglePause,
+        command: false,
+        help: "Freeze / resume: hold the current frame",
+    },
+    KeyBinding {
+        keys: &[Key::Period],
+        action: Action::StepFrame,
+        command: false,
+        help: "Step one frame while paused",
+    },
+    KeyBinding {
+        keys: &[Key::S],
+        action: Action::Sync,
+        command: false,
+        help: "Sync: restart LFOs on the downbeat",
+    },
+    KeyBinding {
+        keys: &[Key::C],
+        action: Action::ClearFeedback,
+        command: false,
+        help: "Clear the feedback buffers",
+    },
+    KeyBinding {
+        keys: &[Key::R],
+        action: Action::Randomize,
+        command: false,
+        help: "Randomize the patch",
+    },
+    KeyBinding {
+        keys: &[Key::F],
+        action: Action::ToggleFade,
+        command: false,
+        help: "Fade the master out / in",
+    },
+    KeyBinding {
+        keys: &[Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6],
+        action: Action::SelectStage,
+        command: false,
+        help: "Select a stage tab (INPUT to OUT)",
+    },
+    KeyBinding {
+        keys: &[Key::ArrowUp],
+        action: Action::NudgeUp,
+        command: false,
+        help: "Nudge the selected slider up 1%",
+    },
+    KeyBinding {
+        keys: &[Key::ArrowDown],
+        action: Action::NudgeDown,
+        command: false,
+        help: "Nudge the selected slider down 1%",
+    },
+    KeyBinding {
+        keys: &[Key::PageUp],
+        action: Action::NudgeUpCoarse,
+        command: false,
+        help: "Nudge the selected slider up 10%",
+    },
+    KeyBinding {
+        keys: &[Key::PageDown],
+        action: Action::NudgeDownCoarse,
+        command: false,
+        help: "Nudge the selected slider down 10%",
+    },
+    KeyBinding {
+        keys: &[Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8],
+        action: Action::Scene,
+        command: false,
+        help: "Recall a scene slot (Shift: store the patch in it)",
+    },
+    KeyBinding {
+        keys: &[Key::Questionmark],
+        action: Action::ToggleHelp,
+        command: false,
+        help: "Show / hide this list",
+    },
+    KeyBinding {
+        keys: &[Key::S],
+        action: Action::SaveSession,
+        command: true,
+        help: "Save the session (patch, automation, bezel) to a file",
+    },
+    KeyBinding {
+        keys: &[Key::O],
+        action: Action::OpenSession,
+        command: true,
+        help: "Open a saved session",
+    },
+];
+
+/// egui temp-data slot the top bar sets while one of its menus is open
+/// (menus aren't tracked as popups)
+pub fn menu_open_id() -> egui::Id {
+    egui::Id::new("menu_open")
+}
+
+/// Run the action of every shortcut pressed this frame (not while typing
+/// into a text field or with a popup or menu open)
+pub fn handle_keys(ctx: &egui::Context, app: &mut App) {
+    // Escape closes an open combo box or menu; don't also panic
+    let menu_open = ctx.data_mut(|d| d.remove_temp::<bool>(menu_open_id())).unwrap_or(false);
+    if ctx.wants_keyboard_input() || ctx.memory(|m| m.any_popup_open()) || menu_open {
+        return;
+    }
+    let (shift, command) = ctx.input(|i| (i.modifiers.shift, i.modifiers.command));
+    for binding in BINDINGS.iter().filter(|binding| binding.command == command) {
+        for (index, &key) in binding.keys.iter().enumerate() {
+            if ctx.input(|i| i.key_pressed(key)) {
+                perform(binding.action, index, shift, app);
+            }
+        }
+    }
+}
+
+fn perform(action: Action, index: usize, shift: bool, app: &mut App) {
+    match action {
+        Action::Panic => app.panic_reset(),
+        Action::TogglePause => app.paused = !app.paused,
+        Action::StepFrame => app.request_frame_step(),
+        Action::Sync => app.automation.sync(),
+        Action::ClearFeedback => app.clear_feedback = true,
+        Action::Randomize => app.randomize(),
+        Action::ToggleFade => app.toggle_fade(),
+        Action::SelectStage => {
+            if let Some(&stage) = STAGE_ORDER.get(index) {
+                app.selected_stage = stage;
+            }
+        }
+        Action::NudgeUp => app.nudge_focused_param(1.0, false),
+        Action::NudgeDown => app.nudge_focused_param(-1.0, false),
+        Action::NudgeUpCoarse => app.nudge_focused_param(1.0, true),
+        Action::NudgeDownCoarse => app.nudge_focused_param(-1.0, true),
+        Action::Scene if shift => app.store_scene(index),
+        Action::Scene => app.recall_scene(index),
+        Action::ToggleHelp => app.show_key_help = !app.show_key_help,
+        Action::SaveSession => crate::ui::save_session_dialog(app),
+        Action::OpenSession => crate::ui::open_session_dialog(app),
+    }
+}
+
+/// Keys as shown in the help list: "Esc / Backspace", "1-6" for a run, or
+/// "Ctrl+S"
+pub fn keys_label(binding: &KeyBinding) -> String {
+    let keys = binding.keys;
+    let label = match keys {
+        [first, .., last] if keys.len() > 2 => format!("{}-{}", first.symbol_or_name(), last.symbol_or_name()),
+        _ => keys.iter().map(|key| key.symbol_or_name()).collect::<Vec<_>>().join(" / "),
+    };
+    if binding.command { format!("Ctrl+{label}") } else { label }
+}
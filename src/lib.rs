@@ -0,0 +1,50 @@
+//! Phosphlux Lite - Simple Fixed-Chain Video Synthesizer
+//!
+//! The synth engine as a library, for embedding in your own wgpu app. The
+//! `phosphlux-lite` binary is a winit/egui shell around these same types.
+//!
+//! - [`SynthState`]: a patch, the parameters of the 7-stage chain
+//! - [`Renderer`]: renders a patch into its own output texture with feedback,
+//!   on a device and queue you supply
+//! - [`AutomationState`]: LFOs and steppers driving a patch from a beat clock
+//! - [`App`]: everything the binary keeps between frames (patch, automation,
+//!   presets, master fade, ...), with `update` advancing it each frame
+//!
+//! A minimal loop:
+//!
+//! ```ignore
+//! let mut renderer = Renderer::new(device.clone(), queue.clone(), 640, 480);
+//! let mut app = App::new();
+//! loop {
+//!     app.update(dt);
+//!     renderer.render(&app.render_state(), &app.frame_params());
+//!     // sample renderer.output_view() in your own passes
+//! }
+//! ```
+//!
+//! Everything but the engine sits behind default cargo features: `shell`
+//! (the winit/egui binary and its saved settings), `audio`, `midi`, `osc` and
+//! `camera`. Build with `default-features = false` for just the engine; a
+//! device input whose feature is off never finds a device.
+
+pub mod app;
+pub mod audio;
+pub mod automation;
+pub mod camera;
+#[cfg(feature = "shell")]
+pub mod config;
+pub mod contact_sheet;
+pub mod hue_check;
+pub mod midi;
+pub mod osc;
+pub mod palette;
+pub mod presets;
+pub mod renderer;
+pub mod shader_export;
+pub mod synth;
+pub mod texture_share;
+
+pub use app::App;
+pub use automation::AutomationState;
+pub use renderer::Renderer;
+pub use synth::{FrameParams, SynthState};
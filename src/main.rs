@@ -2,23 +2,26 @@
 //!
 //! A unified instrument capturing the aesthetic of vintage analog video synthesis.
 //! Inspired by Paik/Abe, Sandin IP, Rutt/Etra, Jones Colorizer, and more.
+//!
+//! The window, egui panels and event loop; the engine is the library crate.
 
-mod app;
-mod automation;
-mod presets;
-mod renderer;
-mod synth;
+mod keys;
+#[cfg(debug_assertions)]
+mod shader_watch;
 mod ui;
 
-use app::App;
-use renderer::Renderer;
+use phosphlux_lite::{app, camera, config, contact_sheet, hue_check, renderer};
+use phosphlux_lite::audio::AudioInput;
+use phosphlux_lite::midi::MidiInput;
+use phosphlux_lite::{App, Renderer};
 use std::sync::Arc;
 use winit::{
     application::ApplicationHandler,
-    dpi::LogicalSize,
+    dpi::{LogicalSize, PhysicalSize},
     event::WindowEvent,
     event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
-    window::{Window, WindowId},
+    keyboard::{Key, NamedKey},
+    window::{Fullscreen, Window, WindowId},
 };
 
 const WINDOW_WIDTH: u32 = 1280;
@@ -26,17 +29,159 @@ const WINDOW_HEIGHT: u32 = 800;
 const SYNTH_WIDTH: u32 = 640;
 const SYNTH_HEIGHT: u32 = 480;
 
+/// Synth resolution for the window's display: 640x480, scaled up on
+/// 1440p and 4K monitors so the maximized preview isn't a blurry upscale
+fn synth_size_for_display(window: &Window) -> (u32, u32) {
+    let display_height = window.current_monitor().map_or(0, |m| m.size().height);
+    let scale = match display_height {
+        h if h >= 2160 => 2.0,
+        h if h >= 1440 => 1.5,
+        _ => 1.0,
+    };
+    (
+        (SYNTH_WIDTH as f32 * scale) as u32,
+        (SYNTH_HEIGHT as f32 * scale) as u32,
+    )
+}
+
+/// Largest rect of `aspect` (width / height) centered in `rect`
+fn fit_aspect(rect: egui::Rect, aspect: f32) -> egui::Rect {
+    let size = if rect.aspect_ratio() > aspect {
+        egui::vec2(rect.height() * aspect, rect.height())
+    } else {
+        egui::vec2(rect.width(), rect.width() / aspect)
+    };
+    egui::Rect::from_center_size(rect.center(), size)
+}
+
+/// Place the bezel and its screen region in the preview panel.
+/// Zoom and vertical offset are clamped so the screen region always stays
+/// fully inside the panel, whatever the window's aspect ratio or DPI.
+/// Returns (bezel rect, screen rect).
+fn bezel_layout(panel: egui::Rect, bezel_aspect: f32, bezel: &app::BezelSettings) -> (egui::Rect, egui::Rect) {
+    let available = panel.size().max(egui::vec2(1.0, 1.0));
+
+    // Fit the bezel to the panel, maintaining aspect ratio
+    let base = if available.x / available.y > bezel_aspect {
+        egui::vec2(available.y * bezel_aspect, available.y)
+    } else {
+        egui::vec2(available.x, available.x / bezel_aspect)
+    };
+
+    // Screen region as fractions of the bezel (kept ordered and non-empty)
+    let screen_min = egui::vec2(bezel.left.clamp(0.0, 0.99), bezel.top.clamp(0.0, 0.99));
+    let screen_max = egui::vec2(
+        bezel.right.min(1.0).max(screen_min.x + 0.01),
+        bezel.bottom.min(1.0).max(screen_min.y + 0.01),
+    );
+    let screen_frac = screen_max - screen_min;
+
+    // Largest zoom at which the screen region still fits the panel
+    let fit_zoom = (available.x / (base.x * screen_frac.x)).min(available.y / (base.y * screen_frac.y));
+    let size = base * bezel.zoom.min(fit_zoom);
+
+    // Center the bezel, with vertical offset
+    let bezel_min = panel.min + (available - size) / 2.0 + egui::vec2(0.0, bezel.offset_y * available.y);
+    let bezel_rect = egui::Rect::from_min_size(bezel_min, size);
+    let screen_rect = egui::Rect::from_min_max(bezel_min + size * screen_min, bezel_min + size * screen_max);
+
+    // Nudge back so the screen region is inside the panel
+    let shift = egui::vec2(
+        (panel.min.x - screen_rect.min.x).max(0.0) + (panel.max.x - screen_rect.max.x).min(0.0),
+        (panel.min.y - screen_rect.min.y).max(0.0) + (panel.max.y - screen_rect.max.y).min(0.0),
+    );
+    (bezel_rect.translate(shift), screen_rect.translate(shift))
+}
+
+const USAGE: &str = "Usage: phosphlux-lite [--preset <file.json | builtin_name>] [--seed <n>] [--kiosk] [--playlist <secs>]
+       phosphlux-lite --contact-sheet <count> [--seed <first>] [--out <file.png>]
+       phosphlux-lite --check-hue-drift
+
+  --preset           Load a preset JSON file, or a built-in preset by name (e.g. feedback_spiral)
+  --seed             Start from the random patch with this seed (overrides --preset)
+  --kiosk            Fullscreen output with no UI; press Escape to quit
+  --playlist         Hold each preset for <secs>, then morph to the next, looping
+  --contact-sheet    Render <count> random patches into a labelled PNG grid and exit
+  --out              Contact sheet path (default contact_sheet.png)
+  --check-hue-drift  Check headlessly that feedback hue shift turns the hue at the set rate, and exit";
+
+/// Command-line options
+#[derive(Default)]
+struct CliArgs {
+    /// Preset file path or built-in preset name
+    preset: Option<String>,
+    /// Fullscreen output only, Escape quits
+    kiosk: bool,
+    /// Start the auto-morph playlist, holding each preset this long
+    playlist: Option<f32>,
+    /// Randomizer seed to start from (first seed for the contact sheet)
+    seed: Option<u64>,
+    /// Render this many random patches to a contact sheet instead of running
+    contact_sheet: Option<u32>,
+    /// Contact sheet output path
+    out: Option<String>,
+    /// Run the headless feedback hue drift check instead of the app
+    check_hue_drift: bool,
+}
+
+impl CliArgs {
+    fn parse() -> Self {
+        let mut args = Self::default();
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--preset" => args.preset = iter.next(),
+                "--kiosk" => args.kiosk = true,
+                "--playlist" => args.playlist = iter.next().and_then(|v| v.parse().ok()),
+                "--seed" => args.seed = iter.next().and_then(|v| v.parse().ok()),
+                "--contact-sheet" => args.contact_sheet = iter.next().and_then(|v| v.parse().ok()),
+                "--out" => args.out = iter.next(),
+                "--check-hue-drift" => args.check_hue_drift = true,
+                "-h" | "--help" => {
+                    println!("{USAGE}");
+                    std::process::exit(0);
+                }
+                other => log::warn!("Ignoring unknown argument: {other}"),
+            }
+        }
+        args
+    }
+}
+
 /// Load the bezel PNG and create an egui ColorImage
 fn load_bezel_image() -> egui::ColorImage {
     let bezel_bytes = include_bytes!("../assets/cutout/Sony PVM-14_front_C_cutout_no logo.png");
-    let img = image::load_from_memory(bezel_bytes)
-        .expect("Failed to load bezel image")
-        .to_rgba8();
+    let img = image::load_from_memory(bezel_bytes).expect("Failed to load bezel image");
+    bezel_color_image(img)
+}
+
+/// Load a user's bezel image (a PNG with a transparent screen cutout)
+fn load_bezel_file(path: &std::path::Path) -> Result<egui::ColorImage, image::ImageError> {
+    Ok(bezel_color_image(image::open(path)?))
+}
+
+fn bezel_color_image(img: image::DynamicImage) -> egui::ColorImage {
+    let img = img.to_rgba8();
     let size = [img.width() as usize, img.height() as usize];
     let pixels = img.into_raw();
     egui::ColorImage::from_rgba_unmultiplied(size, &pixels)
 }
 
+/// Switch between borderless fullscreen with only the bezel and output
+/// showing, and the windowed UI at its previous size
+fn set_fullscreen(state: &mut AppState, fullscreen: bool) {
+    if fullscreen {
+        state.windowed_size = Some(state.window.inner_size());
+        state.window.set_fullscreen(Some(Fullscreen::Borderless(None)));
+    } else {
+        state.window.set_fullscreen(None);
+        if let Some(size) = state.windowed_size.take() {
+            let _ = state.window.request_inner_size(size);
+        }
+    }
+    state.app.show_ui = !fullscreen;
+}
+
 struct AppState {
     window: Arc<Window>,
     device: Arc<wgpu::Device>,
@@ -51,15 +196,24 @@ struct AppState {
     egui_texture_id: egui::TextureId,
     bezel_texture: egui::TextureHandle,  // Keep the handle alive
     bezel_size: [usize; 2],
+    /// Bezel file the texture currently holds (None = built-in)
+    bezel_file: Option<std::path::PathBuf>,
+    /// Image the renderer's Image source currently holds (None = grey)
+    input_image: Option<std::path::PathBuf>,
+    /// Window size to restore when leaving F11 fullscreen
+    windowed_size: Option<PhysicalSize<u32>>,
+    #[cfg(debug_assertions)]
+    shader_watcher: shader_watch::ShaderWatcher,
 }
 
 struct PhosphluxLite {
     state: Option<AppState>,
+    args: CliArgs,
 }
 
 impl PhosphluxLite {
-    fn new() -> Self {
-        Self { state: None }
+    fn new(args: CliArgs) -> Self {
+        Self { state: None, args }
     }
 }
 
@@ -69,13 +223,23 @@ impl ApplicationHandler for PhosphluxLite {
             return;
         }
 
-        // Create window (start maximized)
+        let config = config::load();
+
+        // Create window (maximized unless it was closed at another size)
         let window_attrs = Window::default_attributes()
             .with_title("Phosphlux Lite")
-            .with_inner_size(LogicalSize::new(WINDOW_WIDTH, WINDOW_HEIGHT))
-            .with_maximized(true);
+            .with_fullscreen(self.args.kiosk.then_some(Fullscreen::Borderless(None)));
+        let window_attrs = match config.window_size {
+            Some([width, height]) => window_attrs.with_inner_size(PhysicalSize::new(width, height)),
+            None => window_attrs
+                .with_inner_size(LogicalSize::new(WINDOW_WIDTH, WINDOW_HEIGHT))
+                .with_maximized(true),
+        };
 
         let window = Arc::new(event_loop.create_window(window_attrs).unwrap());
+        if self.args.kiosk {
+            window.set_cursor_visible(false);
+        }
 
         // Initialize wgpu
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
@@ -95,7 +259,8 @@ impl ApplicationHandler for PhosphluxLite {
         let (device, queue) = pollster::block_on(adapter.request_device(
             &wgpu::DeviceDescriptor {
                 label: Some("Device"),
-                required_features: wgpu::Features::empty(),
+                // Timestamps only feed the optional GPU time readout
+                required_features: adapter.features() & wgpu::Features::TIMESTAMP_QUERY,
                 required_limits: wgpu::Limits::default(),
                 memory_hints: Default::default(),
             },
@@ -142,8 +307,10 @@ impl ApplicationHandler for PhosphluxLite {
         let mut egui_renderer = egui_wgpu::Renderer::new(&device, surface_format, None, 1, false);
 
         // Create synth renderer
-        let synth_renderer =
-            Renderer::new(device.clone(), queue.clone(), SYNTH_WIDTH, SYNTH_HEIGHT);
+        let (synth_width, synth_height) = synth_size_for_display(&window);
+        log::info!("Synth resolution {synth_width}x{synth_height}");
+        let mut synth_renderer =
+            Renderer::new(device.clone(), queue.clone(), synth_width, synth_height);
 
         // Register synth output texture with egui
         let egui_texture_id = egui_renderer.register_native_texture(
@@ -161,6 +328,32 @@ impl ApplicationHandler for PhosphluxLite {
             egui::TextureOptions::LINEAR,
         );
 
+        let mut app = App::new();
+        app.output_size = [synth_width, synth_height];
+        app.apply_config(&config);
+        app.scope.row = app.output_size[1] / 2;
+        app.kiosk = self.args.kiosk;
+        app.load_scenes();
+        app.audio = AudioInput::open();
+        app.automation.audio_available = app.audio.is_some();
+        app.load_midi_map();
+        app.midi = MidiInput::open();
+        app.osc_enabled = true;
+        app.camera_count = camera::count();
+        app.perf.gpu_supported = synth_renderer.supports_gpu_timing();
+        if let Some(preset) = &self.args.preset {
+            if let Err(e) = app.load_preset_arg(preset) {
+                log::error!("Failed to load preset {preset}: {e}");
+            }
+        }
+        if let Some(seed) = self.args.seed {
+            app.randomize_with_seed(seed);
+        }
+        if let Some(hold_secs) = self.args.playlist {
+            app.playlist = Some(app::Playlist { hold_secs: hold_secs.max(0.0), elapsed: 0.0 });
+        }
+        app.shader_error = synth_renderer.take_shader_error();
+
         self.state = Some(AppState {
             window,
             device,
@@ -170,11 +363,16 @@ impl ApplicationHandler for PhosphluxLite {
             egui_state,
             egui_renderer,
             synth_renderer,
-            app: App::new(),
+            app,
             last_frame_time: instant::Instant::now(),
             egui_texture_id,
             bezel_texture,
             bezel_size,
+            bezel_file: None,
+            input_image: None,
+            windowed_size: None,
+            #[cfg(debug_assertions)]
+            shader_watcher: shader_watch::ShaderWatcher::new(),
         });
     }
 
@@ -189,13 +387,53 @@ impl ApplicationHandler for PhosphluxLite {
             None => return,
         };
 
+        // F11 toggles fullscreen and Escape leaves it; egui never sees these
+        // presses, so Escape doesn't also fire the panic shortcut
+        if let WindowEvent::KeyboardInput { event: key, .. } = &event {
+            if key.state.is_pressed() && !key.repeat && !state.app.kiosk {
+                let fullscreen = state.window.fullscreen().is_some();
+                match key.logical_key {
+                    Key::Named(NamedKey::F11) => {
+                        set_fullscreen(state, !fullscreen);
+                        return;
+                    }
+                    Key::Named(NamedKey::Escape) if fullscreen => {
+                        set_fullscreen(state, false);
+                        return;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
         // Pass events to egui
         let _ = state.egui_state.on_window_event(&state.window, &event);
 
         match event {
             WindowEvent::CloseRequested => {
+                // Kiosk runs leave the saved settings alone
+                if !state.app.kiosk {
+                    let window = &state.window;
+                    let windowed = !window.is_maximized() && window.fullscreen().is_none();
+                    let size = window.inner_size();
+                    let config = state.app.config(windowed.then_some([size.width, size.height]));
+                    if let Err(e) = config::save(&config) {
+                        log::error!("Failed to save settings: {e}");
+                    }
+                }
                 event_loop.exit();
             }
+            WindowEvent::KeyboardInput { event, .. }
+                if state.app.kiosk
+                    && event.state.is_pressed()
+                    && event.logical_key == Key::Named(NamedKey::Escape) =>
+            {
+                // Only way out of kiosk mode
+                event_loop.exit();
+            }
+            WindowEvent::DroppedFile(path) => {
+                state.app.open_dropped_file(&path);
+            }
             WindowEvent::Resized(new_size) => {
                 if new_size.width > 0 && new_size.height > 0 {
                     state.surface_config.width = new_size.width;
@@ -204,26 +442,182 @@ impl ApplicationHandler for PhosphluxLite {
                 }
             }
             WindowEvent::RedrawRequested => {
-                // Calculate delta time
+                // Calculate delta time (a sequence export steps at its own
+                // fixed rate, one exported frame per redraw)
                 let now = instant::Instant::now();
-                let dt = now.duration_since(state.last_frame_time).as_secs_f32();
+                let elapsed = now.duration_since(state.last_frame_time).as_secs_f32();
+                let dt = state.app.sequence_step().unwrap_or(elapsed);
                 state.last_frame_time = now;
+                state.app.perf.update(elapsed);
+
+                // Update app (patch time stands still while paused)
+                let synth_dt = state.app.update(dt);
+
+                // Debug builds pick up edits to the shader file
+                #[cfg(debug_assertions)]
+                if let Some(source) = state.shader_watcher.poll() {
+                    match state.synth_renderer.reload_shader(&source) {
+                        Ok(()) => {
+                            log::info!("Shader reloaded");
+                            state.app.shader_error = None;
+                        }
+                        Err(e) => {
+                            log::error!("Shader reload failed: {e}");
+                            state.app.shader_error = Some(e);
+                        }
+                    }
+                }
 
-                // Update app
-                state.app.update(dt);
+                // Follow the resolution setting; the preview texture is
+                // re-pointed at the new output view below every frame
+                let [width, height] = state.app.output_size;
+                if width != state.synth_renderer.width() || height != state.synth_renderer.height() {
+                    // A GIF can't change size partway, so save what's recorded
+                    if state.app.gif_recording.is_some() {
+                        state.app.toggle_gif_recording();
+                    }
+                    state.synth_renderer.resize(width, height);
+                    state.app.scope.row = state.app.scope.row.min(height - 1);
+                    log::info!("Synth resolution {width}x{height}");
+                }
+
+                // Follow the Image source setting; a file that won't decode
+                // leaves the source grey
+                if state.app.input_image != state.input_image {
+                    match state.app.input_image.clone() {
+                        Some(path) => {
+                            if let Err(e) = state.synth_renderer.load_input_image(&path) {
+                                let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                                state.app.show_toast(format!("Can't load {name}: {e}"));
+                                state.app.input_image = None;
+                                state.synth_renderer.clear_input_image();
+                            }
+                        }
+                        None => state.synth_renderer.clear_input_image(),
+                    }
+                    state.input_image = state.app.input_image.clone();
+                }
+
+                // Upload the newest webcam frame; grey again once the camera closes
+                match &state.app.camera {
+                    Some(camera) => {
+                        if let Some(frame) = camera.take_frame() {
+                            state.synth_renderer.update_camera(frame.width, frame.height, &frame.rgba);
+                        }
+                    }
+                    None => state.synth_renderer.clear_camera(),
+                }
+
+                // Follow the bezel image setting; a file that won't decode
+                // falls back to the built-in bezel
+                if state.app.bezel_file != state.bezel_file {
+                    let image = match state.app.bezel_file.as_deref().map(load_bezel_file) {
+                        Some(Ok(image)) => image,
+                        Some(Err(e)) => {
+                            log::error!("Failed to load bezel: {e}");
+                            state.app.bezel_error = Some(format!("Can't load bezel: {e}"));
+                            state.app.bezel_file = None;
+                            load_bezel_image()
+                        }
+                        None => load_bezel_image(),
+                    };
+                    state.bezel_size = image.size;
+                    state.bezel_texture.set(image, egui::TextureOptions::LINEAR);
+                    state.bezel_file = state.app.bezel_file.clone();
+                }
 
                 // Render synth
-                state.synth_renderer.render(
-                    &state.app.synth,
-                    state.app.time,
-                    state.app.frame,
-                );
+                state.synth_renderer.set_feedback_scale(state.app.feedback_scale);
+                state.synth_renderer.set_trail_length(state.app.synth.feedback.trail_length);
+                state.synth_renderer.set_supersample(if state.app.supersample { 2 } else { 1 });
+                state.synth_renderer.set_float_feedback(state.app.float_feedback);
+                state.synth_renderer.set_gpu_timing(state.app.perf.enabled);
+                if std::mem::take(&mut state.app.clear_feedback) {
+                    state.synth_renderer.clear_feedback();
+                }
+                // A screenshot frame holds LFOs at the stills phase, if set
+                let screenshot = std::mem::take(&mut state.app.screenshot_requested);
+                let synth = if screenshot { state.app.still_state() } else { state.app.render_state() };
+
+                // Measure the amplitude signal for auto-normalize
+                if synth.amplitude.normalize {
+                    let samples = state.synth_renderer.probe_signal(&synth, &state.app.frame_params());
+                    state.app.adapt_signal_range(&samples, dt);
+                }
+
+                // Measure this frame's brightness before it's shown, for the flash limiter
+                if state.app.flash_limiter.enabled {
+                    let samples = state.synth_renderer.probe_output(&synth, &state.app.frame_params());
+                    let mean = samples.iter().map(|&rgb| renderer::luminance(rgb)).sum::<f32>()
+                        / samples.len().max(1) as f32;
+                    state.app.flash_limiter.update(mean, dt);
+                }
+
+                // Feedback advances at a fixed rate; frames in between only redisplay
+                let params = state.app.frame_params();
+                let steps = state.app.feedback_steps(synth_dt);
+                if state.app.sequence_export.is_some() {
+                    let image = state.synth_renderer.render_sequence_frame(&synth, &params, steps);
+                    state.app.save_sequence_frame(&image);
+                } else {
+                    match steps {
+                        0 => state.synth_renderer.render_preview(&synth, &params),
+                        steps => {
+                            for _ in 0..steps {
+                                state.synth_renderer.render(&synth, &params);
+                            }
+                        }
+                    }
+                }
+
+                state.app.perf.gpu_ms = state.synth_renderer.gpu_time_ms();
+
+                if let Some(sender) = &mut state.app.share {
+                    state.synth_renderer.share_output(sender);
+                }
+
+                if screenshot {
+                    let image = state.synth_renderer.capture_frame();
+                    state.app.save_screenshot(&image);
+                }
+                if state.app.gif_capture_due(dt) {
+                    let image = state.synth_renderer.capture_frame();
+                    state.app.add_gif_frame(image);
+                }
+
+                // Read back rows for the scope and histogram in one go
+                let mut rows = Vec::new();
+                if state.app.scope.enabled {
+                    rows.push(state.app.scope.row);
+                }
+                if state.app.show_histogram {
+                    let height = state.synth_renderer.height();
+                    rows.extend((0..app::HISTOGRAM_ROWS).map(|i| (2 * i + 1) * height / (2 * app::HISTOGRAM_ROWS)));
+                }
+                if !rows.is_empty() {
+                    let readback = state.synth_renderer.read_output_rows(&rows);
+                    let mut readback = readback.iter();
+                    if state.app.scope.enabled {
+                        state.app.scope_samples = readback
+                            .next()
+                            .map(|row| row.iter().copied().map(renderer::luminance).collect())
+                            .unwrap_or_default();
+                    }
+                    if state.app.show_histogram {
+                        state.app.histogram = app::Histogram::from_rows(readback, 4);
+                    }
+                }
 
                 // Update egui texture
+                let preview_filter = if state.app.nearest_preview {
+                    wgpu::FilterMode::Nearest
+                } else {
+                    wgpu::FilterMode::Linear
+                };
                 state.egui_renderer.update_egui_texture_from_wgpu_texture(
                     &state.device,
                     state.synth_renderer.output_view(),
-                    wgpu::FilterMode::Linear,
+                    preview_filter,
                     state.egui_texture_id,
                 );
 
@@ -250,61 +644,72 @@ impl ApplicationHandler for PhosphluxLite {
                 let egui_ctx = state.egui_state.egui_ctx().clone();
 
                 let full_output = egui_ctx.run(raw_input, |ctx| {
-                    // Draw UI
-                    ui::draw_ui(ctx, &mut state.app);
+                    if state.app.kiosk {
+                        // Output only, letterboxed on black
+                        egui::CentralPanel::default()
+                            .frame(egui::Frame::none().fill(egui::Color32::BLACK))
+                            .show(ctx, |ui| {
+                                let aspect = state.synth_renderer.width() as f32
+                                    / state.synth_renderer.height() as f32;
+                                ui.painter().image(
+                                    state.egui_texture_id,
+                                    fit_aspect(ui.max_rect(), aspect),
+                                    egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                                    egui::Color32::WHITE,
+                                );
+                            });
+                        return;
+                    }
+
+                    // Draw UI (hidden in fullscreen, but the shortcuts stay live)
+                    if state.app.show_ui {
+                        ui::draw_ui(ctx, &mut state.app);
+                    } else {
+                        keys::handle_keys(ctx, &mut state.app);
+                    }
 
                     // Draw video preview with bezel overlay
+                    let [r, g, b] = state.app.background;
                     egui::CentralPanel::default()
-                        .frame(egui::Frame::none().fill(egui::Color32::from_rgb(25, 25, 25)))
+                        .frame(egui::Frame::none().fill(egui::Color32::from_rgb(r, g, b)))
                         .show(ctx, |ui| {
-                            let available = ui.available_size();
                             let bezel_aspect = state.bezel_size[0] as f32 / state.bezel_size[1] as f32;
-                            let zoom = state.app.bezel.zoom;
-
-                            // Calculate bezel size maintaining aspect ratio, with zoom applied
-                            let (base_w, base_h) = if available.x / available.y > bezel_aspect {
-                                (available.y * bezel_aspect, available.y)
-                            } else {
-                                (available.x, available.x / bezel_aspect)
-                            };
-                            let bezel_w = base_w * zoom;
-                            let bezel_h = base_h * zoom;
-
-                            // Center the bezel in available space, with vertical offset
-                            let offset_x = (available.x - bezel_w) / 2.0;
-                            let offset_y = (available.y - bezel_h) / 2.0 + (state.app.bezel.offset_y * available.y);
-                            let bezel_rect = egui::Rect::from_min_size(
-                                ui.min_rect().min + egui::vec2(offset_x, offset_y),
-                                egui::vec2(bezel_w, bezel_h),
-                            );
-
-                            // Calculate screen region within bezel (using app settings)
-                            let screen_rect = egui::Rect::from_min_max(
-                                egui::pos2(
-                                    bezel_rect.min.x + bezel_w * state.app.bezel.left,
-                                    bezel_rect.min.y + bezel_h * state.app.bezel.top,
-                                ),
-                                egui::pos2(
-                                    bezel_rect.min.x + bezel_w * state.app.bezel.right,
-                                    bezel_rect.min.y + bezel_h * state.app.bezel.bottom,
-                                ),
-                            );
-
-                            // Draw synth output in screen region
+                            let (bezel_rect, screen_rect) =
+                                bezel_layout(ui.available_rect_before_wrap(), bezel_aspect, &state.app.bezel);
+
+                            // Draw synth output in screen region, letterboxed on
+                            // the dark tube when its aspect differs from the bezel's
+                            let synth_aspect = state.synth_renderer.width() as f32
+                                / state.synth_renderer.height() as f32;
+                            let image_rect = fit_aspect(screen_rect, synth_aspect);
+                            if state.app.bezel.enabled && image_rect != screen_rect {
+                                ui.painter().rect_filled(screen_rect, 0.0, egui::Color32::BLACK);
+                            }
                             ui.painter().image(
                                 state.egui_texture_id,
-                                screen_rect,
+                                image_rect,
                                 egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
                                 egui::Color32::WHITE,
                             );
 
+                            // Mark the scope scanline
+                            if state.app.scope.enabled {
+                                let t = (state.app.scope.row as f32 + 0.5) / state.app.output_size[1] as f32;
+                                let y = egui::lerp(image_rect.y_range(), t);
+                                ui.painter().hline(
+                                    image_rect.x_range(),
+                                    y,
+                                    egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(0, 255, 120, 160)),
+                                );
+                            }
+
                             // Draw bezel overlay on top (if enabled)
                             if state.app.bezel.enabled {
                                 ui.put(
                                     bezel_rect,
                                     egui::Image::from_texture(egui::load::SizedTexture::new(
                                         state.bezel_texture.id(),
-                                        [bezel_w, bezel_h],
+                                        bezel_rect.size(),
                                     )),
                                 );
                             }
@@ -337,6 +742,16 @@ impl ApplicationHandler for PhosphluxLite {
                             label: Some("Egui Encoder"),
                         });
 
+                // Clear to the background color (linear, for the sRGB surface)
+                let [r, g, b] = state.app.background;
+                let background = egui::Rgba::from(egui::Color32::from_rgb(r, g, b));
+                let clear_color = wgpu::Color {
+                    r: background.r() as f64,
+                    g: background.g() as f64,
+                    b: background.b() as f64,
+                    a: 1.0,
+                };
+
                 state.egui_renderer.update_buffers(
                     &state.device,
                     &state.queue,
@@ -352,12 +767,7 @@ impl ApplicationHandler for PhosphluxLite {
                             view: &view,
                             resolve_target: None,
                             ops: wgpu::Operations {
-                                load: wgpu::LoadOp::Clear(wgpu::Color {
-                                    r: 0.1,
-                                    g: 0.1,
-                                    b: 0.1,
-                                    a: 1.0,
-                                }),
+                                load: wgpu::LoadOp::Clear(clear_color),
                                 store: wgpu::StoreOp::Store,
                             },
                         })],
@@ -391,9 +801,51 @@ impl ApplicationHandler for PhosphluxLite {
 fn main() {
     env_logger::init();
 
+    let args = CliArgs::parse();
+
+    if let Some(count) = args.contact_sheet {
+        // Short seeds so the labels stay readable
+        let first_seed = args.seed.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() % 1_000_000)
+                .unwrap_or(0)
+        });
+        let out = args.out.as_deref().unwrap_or("contact_sheet.png");
+        match contact_sheet::render(count, first_seed, std::path::Path::new(out)) {
+            Ok(()) => println!("Wrote {out} (seeds {first_seed}..{})", first_seed + count as u64),
+            Err(e) => {
+                eprintln!("Contact sheet failed: {e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if args.check_hue_drift {
+        match hue_check::run() {
+            Ok(drift) => {
+                println!(
+                    "Hue drift: expected {:.2} deg/frame, measured {:.2}",
+                    drift.expected, drift.measured
+                );
+                if !drift.passed() {
+                    eprintln!("Hue drift check failed");
+                    std::process::exit(1);
+                }
+            }
+            Err(e) => {
+                eprintln!("Hue drift check failed: {e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // The contact sheet and hue check render headlessly; only the interactive app needs a display
     let event_loop = EventLoop::new().unwrap();
     event_loop.set_control_flow(ControlFlow::Poll);
 
-    let mut app = PhosphluxLite::new();
+    let mut app = PhosphluxLite::new(args);
     event_loop.run_app(&mut app).unwrap();
 }
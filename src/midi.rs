@@ -0,0 +1,87 @@
+//! MIDI input for hardware controllers
+//!
+//! Listens on the first available MIDI input port and hands control change
+//! messages to the app, which maps CC numbers onto "stage.param" keys.
+//! Without the `midi` feature there is never an input port.
+
+use std::collections::BTreeMap;
+#[cfg(feature = "midi")]
+use std::sync::mpsc::{self, Receiver};
+
+/// CC number each mapped parameter follows, keyed by "stage.param"
+pub type MidiMap = BTreeMap<String, u8>;
+
+/// Open MIDI input port
+#[cfg(feature = "midi")]
+pub struct MidiInput {
+    _connection: midir::MidiInputConnection<()>,
+    /// (CC number, value 0-127) pairs, sent from midir's thread
+    messages: Receiver<(u8, u8)>,
+    /// Name of the connected port
+    pub port_name: String,
+}
+
+#[cfg(feature = "midi")]
+impl MidiInput {
+    /// Connect to the first MIDI input port; None (logged) if there isn't one
+    pub fn open() -> Option<Self> {
+        match Self::try_open() {
+            Ok(input) => Some(input),
+            Err(e) => {
+                log::warn!("No MIDI input ({e}); MIDI mapping is disabled");
+                None
+            }
+        }
+    }
+
+    fn try_open() -> Result<Self, String> {
+        let input = midir::MidiInput::new("phosphlux-lite").map_err(|e| e.to_string())?;
+        let port = input.ports().into_iter().next().ok_or("no input ports")?;
+        let port_name = input.port_name(&port).map_err(|e| e.to_string())?;
+
+        let (sender, messages) = mpsc::channel();
+        let connection = input
+            .connect(
+                &port,
+                "phosphlux-lite-in",
+                move |_, message, _| {
+                    // Control change on any channel: 0xBn, controller, value
+                    if let [0xB0..=0xBF, cc, value] = *message {
+                        let _ = sender.send((cc, value));
+                    }
+                },
+                (),
+            )
+            .map_err(|e| e.to_string())?;
+        log::info!("MIDI input: {port_name}");
+
+        Ok(Self {
+            _connection: connection,
+            messages,
+            port_name,
+        })
+    }
+
+    /// Control changes received since the last call, oldest first
+    pub fn poll(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        self.messages.try_iter()
+    }
+}
+
+/// Built without the `midi` feature: no port to connect to
+#[cfg(not(feature = "midi"))]
+pub struct MidiInput {
+    pub port_name: String,
+}
+
+#[cfg(not(feature = "midi"))]
+impl MidiInput {
+    pub fn open() -> Option<Self> {
+        log::warn!("Built without the midi feature; MIDI mapping is disabled");
+        None
+    }
+
+    pub fn poll(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        std::iter::empty()
+    }
+}
@@ -0,0 +1,195 @@
+//! OSC remote control, e.g. from TouchOSC
+//!
+//! A background thread listens for UDP messages addressed
+//! `/phosphlux/<stage>/<param>` and hands them to the app as "stage.param"
+//! keys. Values go back out to whoever last sent one, so a controller's
+//! faders follow LFOs, presets and the mouse. Without the `osc` feature no
+//! port is ever opened.
+
+#[cfg(feature = "osc")]
+use rosc::{OscMessage, OscPacket, OscType};
+use std::net::SocketAddr;
+#[cfg(feature = "osc")]
+use std::net::UdpSocket;
+#[cfg(feature = "osc")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "osc")]
+use std::sync::mpsc::{self, Receiver, Sender};
+#[cfg(feature = "osc")]
+use std::sync::Arc;
+#[cfg(feature = "osc")]
+use std::thread::JoinHandle;
+#[cfg(feature = "osc")]
+use std::time::Duration;
+
+/// Port listened on unless changed in Settings
+pub const DEFAULT_PORT: u16 = 9000;
+
+/// Every address starts with this, followed by "stage/param"
+#[cfg(feature = "osc")]
+const ADDRESS_PREFIX: &str = "/phosphlux/";
+
+/// First argument of a received message
+#[derive(Debug, Clone, Copy)]
+pub enum OscValue {
+    /// 0-1 across a slider's range
+    Float(f32),
+    /// Option index for a switch or menu
+    Int(i32),
+}
+
+/// Listening socket and its receive thread; dropping it closes the port
+#[cfg(feature = "osc")]
+pub struct OscServer {
+    /// Port this server is bound to
+    pub port: u16,
+    socket: UdpSocket,
+    /// ("stage.param" key, value, sender) from the receive thread
+    messages: Receiver<(String, OscValue, SocketAddr)>,
+    /// Where values are sent: the last address a message came from
+    client: Option<SocketAddr>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[cfg(feature = "osc")]
+impl OscServer {
+    /// Listen on `port` on all interfaces; None (logged) if it can't be bound
+    pub fn open(port: u16) -> Option<Self> {
+        match Self::try_open(port) {
+            Ok(server) => Some(server),
+            Err(e) => {
+                log::warn!("Can't listen for OSC on port {port}: {e}");
+                None
+            }
+        }
+    }
+
+    fn try_open(port: u16) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))?;
+        // Wake up now and then to notice when the server is dropped
+        socket.set_read_timeout(Some(Duration::from_millis(100)))?;
+        let receiver = socket.try_clone()?;
+
+        let (sender, messages) = mpsc::channel();
+        let running = Arc::new(AtomicBool::new(true));
+        let thread = {
+            let running = running.clone();
+            std::thread::spawn(move || {
+                let mut buffer = [0u8; rosc::decoder::MTU];
+                while running.load(Ordering::Relaxed) {
+                    let Ok((len, from)) = receiver.recv_from(&mut buffer) else {
+                        continue;
+                    };
+                    match rosc::decoder::decode_udp(&buffer[..len]) {
+                        Ok((_, packet)) => forward(packet, from, &sender),
+                        Err(e) => log::warn!("Bad OSC packet from {from}: {e:?}"),
+                    }
+                }
+            })
+        };
+        log::info!("Listening for OSC on port {port}");
+
+        Ok(Self {
+            port,
+            socket,
+            messages,
+            client: None,
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    /// Messages received since the last call, oldest first
+    pub fn poll(&mut self) -> Vec<(String, OscValue)> {
+        self.messages
+            .try_iter()
+            .map(|(key, value, from)| {
+                self.client = Some(from);
+                (key, value)
+            })
+            .collect()
+    }
+
+    /// Address values are sent to, once a message has arrived
+    pub fn client(&self) -> Option<SocketAddr> {
+        self.client
+    }
+
+    /// Send a parameter's value (0-1) to the controller, once one has
+    /// sent a message
+    pub fn send(&self, key: &str, value: f32) {
+        let Some(client) = self.client else {
+            return;
+        };
+        let packet = OscPacket::Message(OscMessage {
+            addr: format!("{ADDRESS_PREFIX}{}", key.replace('.', "/")),
+            args: vec![OscType::Float(value)],
+        });
+        match rosc::encoder::encode(&packet) {
+            Ok(bytes) => {
+                let _ = self.socket.send_to(&bytes, client);
+            }
+            Err(e) => log::warn!("Can't encode OSC for {key}: {e:?}"),
+        }
+    }
+}
+
+#[cfg(feature = "osc")]
+impl Drop for OscServer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Pass each message in a packet (bundles included) on as a key and value
+#[cfg(feature = "osc")]
+fn forward(packet: OscPacket, from: SocketAddr, sender: &Sender<(String, OscValue, SocketAddr)>) {
+    match packet {
+        OscPacket::Message(message) => {
+            let Some(key) = message.addr.strip_prefix(ADDRESS_PREFIX).map(|path| path.replace('/', ".")) else {
+                return;
+            };
+            let value = match message.args.first() {
+                Some(&OscType::Float(value)) => OscValue::Float(value),
+                Some(&OscType::Double(value)) => OscValue::Float(value as f32),
+                Some(&OscType::Int(value)) => OscValue::Int(value),
+                Some(&OscType::Bool(value)) => OscValue::Int(value as i32),
+                _ => return,
+            };
+            let _ = sender.send((key, value, from));
+        }
+        OscPacket::Bundle(bundle) => {
+            for packet in bundle.content {
+                forward(packet, from, sender);
+            }
+        }
+    }
+}
+
+/// Built without the `osc` feature: never listening
+#[cfg(not(feature = "osc"))]
+pub struct OscServer {
+    pub port: u16,
+}
+
+#[cfg(not(feature = "osc"))]
+impl OscServer {
+    pub fn open(port: u16) -> Option<Self> {
+        log::warn!("Built without the osc feature; can't listen on port {port}");
+        None
+    }
+
+    pub fn poll(&mut self) -> Vec<(String, OscValue)> {
+        Vec::new()
+    }
+
+    pub fn client(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    pub fn send(&self, _key: &str, _value: f32) {}
+}
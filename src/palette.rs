@@ -0,0 +1,173 @@
+//! Indexed palettes for Threshold colorize and lookup tables for Palette
+//! colorize
+//!
+//! Reads GIMP palettes (.gpl) and plain hex lists (.hex, one `rrggbb` or
+//! `#rrggbb` per line, as exported by Lospec and most pixel art tools).
+//! Lookup tables can also come from a PNG strip.
+
+use std::path::Path;
+
+/// Colors the shader can hold; longer palettes are truncated
+pub const MAX_PALETTE_COLORS: usize = 16;
+
+/// Entries in the Palette colorize lookup table
+pub const LUT_SIZE: usize = 256;
+
+/// Built-in lookup palettes for Palette colorize, darkest first
+pub const BUILTIN_LUTS: [(&str, &[[u8; 3]]); 5] = [
+    (
+        "Jones",
+        &[
+            [0, 0, 0],
+            [40, 0, 120],
+            [160, 0, 160],
+            [230, 20, 60],
+            [255, 130, 0],
+            [250, 230, 0],
+            [40, 200, 60],
+            [0, 200, 230],
+            [255, 255, 255],
+        ],
+    ),
+    (
+        "Thermal",
+        &[
+            [0, 0, 0],
+            [60, 0, 110],
+            [170, 0, 90],
+            [235, 60, 0],
+            [255, 170, 0],
+            [255, 240, 120],
+            [255, 255, 255],
+        ],
+    ),
+    ("Game Boy", &[[15, 56, 15], [48, 98, 48], [139, 172, 15], [155, 188, 15]]),
+    ("CGA", &[[0, 0, 0], [85, 255, 255], [255, 85, 255], [255, 255, 255]]),
+    ("Sepia", &[[20, 12, 6], [70, 45, 25], [130, 95, 60], [190, 155, 110], [240, 220, 180]]),
+];
+
+/// Colors of the built-in lookup palette `name` (the first if unknown)
+pub fn builtin_lut(name: &str) -> Vec<[f32; 3]> {
+    let (_, colors) = BUILTIN_LUTS.iter().find(|(n, _)| *n == name).unwrap_or(&BUILTIN_LUTS[0]);
+    colors
+        .iter()
+        .map(|&[r, g, b]| [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0])
+        .collect()
+}
+
+/// Load a palette file as 0-1 RGB colors
+pub fn load_palette(path: &Path) -> Result<Vec<[f32; 3]>, String> {
+    let mut colors = read_palette(path)?;
+    if colors.len() > MAX_PALETTE_COLORS {
+        log::warn!(
+            "{} has {} colors, using the first {MAX_PALETTE_COLORS}",
+            path.display(),
+            colors.len()
+        );
+        colors.truncate(MAX_PALETTE_COLORS);
+    }
+    Ok(colors)
+}
+
+/// Load a lookup palette: a PNG strip, read left to right along its middle
+/// row, or a .gpl/.hex palette. Longer ones are thinned to LUT_SIZE colors.
+pub fn load_lut(path: &Path) -> Result<Vec<[f32; 3]>, String> {
+    let colors = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png")) {
+        let image = image::open(path).map_err(|e| e.to_string())?.to_rgb8();
+        let row = image.height() / 2;
+        (0..image.width())
+            .map(|x| image.get_pixel(x, row).0.map(|c| c as f32 / 255.0))
+            .collect()
+    } else {
+        read_palette(path)?
+    };
+
+    if colors.is_empty() {
+        return Err("no colors in palette".to_string());
+    }
+    if colors.len() > LUT_SIZE {
+        return Ok((0..LUT_SIZE).map(|i| colors[i * colors.len() / LUT_SIZE]).collect());
+    }
+    Ok(colors)
+}
+
+/// Spread colors over the LUT_SIZE-entry lookup table as RGBA8, each taking
+/// an equal band
+pub fn lut_texels(colors: &[[f32; 3]]) -> Vec<u8> {
+    (0..LUT_SIZE)
+        .flat_map(|i| {
+            let [r, g, b] = colors.get(i * colors.len() / LUT_SIZE).copied().unwrap_or_default();
+            [r, g, b, 1.0].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+        })
+        .collect()
+}
+
+/// Read every color in a .gpl or .hex file
+fn read_palette(path: &Path) -> Result<Vec<[f32; 3]>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let colors = if text.trim_start().starts_with("GIMP Palette") {
+        parse_gpl(&text)?
+    } else {
+        parse_hex(&text)?
+    };
+
+    if colors.is_empty() {
+        return Err("no colors in palette".to_string());
+    }
+    Ok(colors)
+}
+
+/// Whether a dropped file looks like a palette
+pub fn is_palette_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gpl") || ext.eq_ignore_ascii_case("hex"))
+}
+
+/// GIMP palette: a header, optional Name/Columns lines, `#` comments, then
+/// one "R G B [name]" line per color (0-255)
+fn parse_gpl(text: &str) -> Result<Vec<[f32; 3]>, String> {
+    let mut colors = Vec::new();
+    for (number, line) in text.lines().enumerate().skip(1) {
+        let line = line.trim_start();
+        // Skips blank lines, comments and Name:/Columns: headers
+        if !line.starts_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+
+        let channels: Vec<f32> = line
+            .split_whitespace()
+            .take(3)
+            .map(|c| c.parse::<u8>().map(|v| v as f32 / 255.0))
+            .collect::<Result<_, _>>()
+            .map_err(|_| format!("line {}: expected \"R G B\"", number + 1))?;
+        match channels[..] {
+            [r, g, b] => colors.push([r, g, b]),
+            _ => return Err(format!("line {}: expected \"R G B\"", number + 1)),
+        }
+    }
+    Ok(colors)
+}
+
+/// Hex list: `rrggbb` or `#rrggbb` per line; `;` and `//` start comments
+fn parse_hex(text: &str) -> Result<Vec<[f32; 3]>, String> {
+    let mut colors = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split(';').next().unwrap_or("");
+        let line = line.split("//").next().unwrap_or("").trim();
+        let hex = line.trim_start_matches('#');
+        if hex.is_empty() {
+            continue;
+        }
+
+        let value = (hex.len() == 6)
+            .then(|| u32::from_str_radix(hex, 16).ok())
+            .flatten()
+            .ok_or_else(|| format!("line {}: expected a hex color like ff8800", number + 1))?;
+        colors.push([
+            ((value >> 16) & 0xff) as f32 / 255.0,
+            ((value >> 8) & 0xff) as f32 / 255.0,
+            (value & 0xff) as f32 / 255.0,
+        ]);
+    }
+    Ok(colors)
+}
@@ -1,38 +1,229 @@
 //! Preset system for saving/loading synthesizer state
 
+use crate::app::BezelSettings;
+use crate::automation::{AudioBind, GestureRecording, ModRoute, ParamLfos, StepperState, DEFAULT_LFO_SPEEDS};
+use crate::midi::MidiMap;
 use crate::synth::*;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Extension of a single preset exported to share (JSON, like presets/)
+pub const PRESET_EXTENSION: &str = "phxl";
+
+/// Preset format written by this build. Files without a version are v1.
+///
+/// - v1: unversioned; `amplitude.invert` may be a single 0/1 number, and
+///   `mixer.normalize` may be missing (the mixer always crossfaded)
+/// - v2: `version` field; `amplitude.invert` is per-channel flags
+pub const PRESET_VERSION: u32 = 2;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Preset {
+    /// Format version the file was written in (see `PRESET_VERSION`)
+    #[serde(default = "legacy_version")]
+    pub version: u32,
     pub name: String,
     pub description: String,
     pub state: SynthState,
+    /// Tempo the preset was designed at; sets the global BPM on load
+    /// (None keeps the current tempo)
+    #[serde(default)]
+    pub bpm: Option<f32>,
+    /// Recorded slider moves, looped from the moment the preset loads
+    #[serde(default)]
+    pub gestures: Option<GestureRecording>,
+    /// LFOs and steppers, replacing the running ones on load (None leaves
+    /// them running)
+    #[serde(default)]
+    pub lfos: Option<HashMap<String, ParamLfos>>,
+    #[serde(default)]
+    pub steppers: Option<HashMap<String, StepperState>>,
 }
 
 impl Preset {
     pub fn new(name: &str, description: &str, state: SynthState) -> Self {
         Self {
+            version: PRESET_VERSION,
             name: name.to_string(),
             description: description.to_string(),
             state,
+            bpm: None,
+            gestures: None,
+            lfos: None,
+            steppers: None,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), std::io::Error> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, json)
+    }
+
+    /// Load a preset file, upgrading older formats to `PRESET_VERSION`
+    pub fn load(path: &Path) -> Result<Self, std::io::Error> {
+        let json = std::fs::read_to_string(path)?;
+        let invalid = |e| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+        let mut value: Value = serde_json::from_str(&json).map_err(invalid)?;
+
+        let version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .map_or(legacy_version(), |v| v as u32);
+        if version > PRESET_VERSION {
+            // Unknown fields are ignored, so newer files mostly still load
+            log::warn!(
+                "{} is preset format v{version}, newer than this build (v{PRESET_VERSION}); loading what it can",
+                path.display()
+            );
+        }
+        migrate(&mut value, version);
+
+        let mut preset: Self = serde_json::from_value(value).map_err(invalid)?;
+        preset.version = PRESET_VERSION;
+        Ok(preset)
+    }
+}
+
+fn legacy_version() -> u32 {
+    1
+}
+
+/// Upgrade preset JSON from `version` to `PRESET_VERSION`, one step at a time
+fn migrate(value: &mut Value, version: u32) {
+    if version < 2 {
+        migrate_v1(value);
+    }
+}
+
+/// v1 -> v2: a single `amplitude.invert` number (0 or 1) becomes a flag per
+/// channel, and a mixer from before `normalize` keeps its crossfade
+fn migrate_v1(value: &mut Value) {
+    let invert = value.pointer("/state/amplitude/invert").and_then(Value::as_f64);
+    if let Some(amount) = invert {
+        value["state"]["amplitude"]["invert"] = Value::from(vec![amount > 0.5; 3]);
+    }
+    if let Some(mixer) = value.pointer_mut("/state/mixer").and_then(Value::as_object_mut) {
+        mixer.entry("normalize").or_insert(Value::Bool(true));
+    }
+}
+
+/// Extension of a saved session (JSON)
+pub const SESSION_EXTENSION: &str = "phxs";
+
+/// Session format written by this build. Files without a version are v1.
+///
+/// - v1: patch, automation, tempo, bezel and source image paths
+pub const SESSION_VERSION: u32 = 1;
+
+/// A whole working setup, saved to reopen exactly where it was left:
+/// the patch (palette paths included), all automation, the tempo, the bezel
+/// and the Image source. Fields missing from an older file take their
+/// defaults.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Format version the file was written in (see `SESSION_VERSION`)
+    pub schema_version: u32,
+    pub state: SynthState,
+    pub bpm: f32,
+    /// Slow/Medium/Fast LFO speeds
+    pub lfo_speeds: [f32; 3],
+    pub lfos: HashMap<String, ParamLfos>,
+    pub steppers: HashMap<String, StepperState>,
+    pub audio_binds: HashMap<String, AudioBind>,
+    pub mod_routes: Vec<ModRoute>,
+    pub gestures: GestureRecording,
+    pub bezel: BezelSettings,
+    /// Bezel image (None = built-in)
+    pub bezel_file: Option<PathBuf>,
+    /// Image input source (None = grey)
+    pub input_image: Option<PathBuf>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            schema_version: SESSION_VERSION,
+            state: SynthState::default(),
+            bpm: 120.0,
+            lfo_speeds: DEFAULT_LFO_SPEEDS,
+            lfos: HashMap::new(),
+            steppers: HashMap::new(),
+            audio_binds: HashMap::new(),
+            mod_routes: Vec::new(),
+            gestures: GestureRecording::default(),
+            bezel: BezelSettings::default(),
+            bezel_file: None,
+            input_image: None,
         }
     }
+}
 
+impl Session {
     pub fn save(&self, path: &Path) -> Result<(), std::io::Error> {
         let json = serde_json::to_string_pretty(self)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
         std::fs::write(path, json)
     }
 
+    /// Load a session file; a newer format loads what this build understands
     pub fn load(path: &Path) -> Result<Self, std::io::Error> {
         let json = std::fs::read_to_string(path)?;
-        serde_json::from_str(&json)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+        let invalid = |e| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+        let value: Value = serde_json::from_str(&json).map_err(invalid)?;
+
+        let version = value.get("schema_version").and_then(Value::as_u64).map_or(1, |v| v as u32);
+        if version > SESSION_VERSION {
+            log::warn!(
+                "{} is session format v{version}, newer than this build (v{SESSION_VERSION}); loading what it can",
+                path.display()
+            );
+        }
+
+        let mut session: Self = serde_json::from_value(value).map_err(invalid)?;
+        session.schema_version = SESSION_VERSION;
+        Ok(session)
     }
 }
 
+/// Scene slots, recalled with F1-F8
+pub const SCENE_SLOTS: usize = 8;
+
+/// Where scene slots are kept between sessions (working directory)
+pub const SCENES_FILE: &str = "scenes.json";
+
+pub type Scenes = [Option<SynthState>; SCENE_SLOTS];
+
+pub fn save_scenes(path: &Path, scenes: &Scenes) -> Result<(), std::io::Error> {
+    let json = serde_json::to_string_pretty(scenes)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    std::fs::write(path, json)
+}
+
+pub fn load_scenes(path: &Path) -> Result<Scenes, std::io::Error> {
+    let json = std::fs::read_to_string(path)?;
+    serde_json::from_str(&json)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Where MIDI CC mappings are kept between sessions (working directory)
+pub const MIDI_FILE: &str = "midi.json";
+
+pub fn save_midi_map(path: &Path, map: &MidiMap) -> Result<(), std::io::Error> {
+    let json = serde_json::to_string_pretty(map)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    std::fs::write(path, json)
+}
+
+pub fn load_midi_map(path: &Path) -> Result<MidiMap, std::io::Error> {
+    let json = std::fs::read_to_string(path)?;
+    serde_json::from_str(&json)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
 /// Built-in presets
 pub fn builtin_presets() -> Vec<Preset> {
     vec![
@@ -1,7 +1,10 @@
 //! Simplified GPU renderer for fixed pipeline
 
-use crate::synth::{SynthState, SynthUniforms};
+use crate::palette::{lut_texels, LUT_SIZE};
+use crate::synth::{ColorizeStage, FrameParams, SynthState, SynthUniforms, MAX_TRAIL_LENGTH};
+use crate::texture_share::TextureSender;
 use bytemuck::{Pod, Zeroable};
+use std::collections::VecDeque;
 use std::sync::Arc;
 use wgpu::util::DeviceExt;
 
@@ -36,30 +39,505 @@ const QUAD_VERTICES: &[Vertex] = &[
     Vertex { position: [-1.0, 1.0], uv: [0.0, 0.0] },
 ];
 
+/// Decode an 8-bit sRGB channel to linear 0-1
+fn srgb_to_linear(value: u8) -> f32 {
+    let c = value as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Rec. 709 luma of a linear RGB color
+pub fn luminance(rgb: [f32; 3]) -> f32 {
+    0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2]
+}
+
+/// Size of the downsampled signal probe used by auto-normalize
+const PROBE_WIDTH: u32 = 64;
+const PROBE_HEIGHT: u32 = 48;
+
+/// Feedback buffer size for an output size and scale factor
+fn scaled_size(width: u32, height: u32, scale: f32) -> (u32, u32) {
+    (
+        ((width as f32 * scale).round() as u32).max(1),
+        ((height as f32 * scale).round() as u32).max(1),
+    )
+}
+
+/// Output and probe format (what egui displays and readbacks decode)
+const DISPLAY_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Feedback and work target format with high precision feedback on
+const FLOAT_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Create a render target the synth can render into and sample
+fn create_target_texture(
+    device: &wgpu::Device,
+    label: &str,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_SRC
+            | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    })
+}
+
+/// Picture sampled by an input source: the Image source's still (group 2)
+/// or the Camera source's latest frame (group 3)
+struct SourceTexture {
+    texture: wgpu::Texture,
+    _view: wgpu::TextureView,
+    bind_group: wgpu::BindGroup,
+}
+
+impl SourceTexture {
+    /// Upload RGBA8 pixels as-is: their encoded values are the signal, like
+    /// a camera's video voltage
+    fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+    ) -> Self {
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Source Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = create_texture_bind_group(device, layout, &view, sampler, "Source Bind Group");
+        let source = Self { texture, _view: view, bind_group };
+        source.write(queue, rgba);
+        source
+    }
+
+    /// Neutral grey, so a source is safe to pick before it has a picture
+    fn placeholder(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+    ) -> Self {
+        Self::new(device, queue, layout, sampler, 1, 1, &[128, 128, 128, 255])
+    }
+
+    /// Replace the pixels (same size as the texture)
+    fn write(&self, queue: &wgpu::Queue, rgba: &[u8]) {
+        let size = self.texture.size();
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            rgba,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * size.width),
+                rows_per_image: Some(size.height),
+            },
+            size,
+        );
+    }
+}
+
+/// Bind a texture view and sampler for use as group 1 (or 2, 3)
+fn create_texture_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+    label: &str,
+) -> wgpu::BindGroup {
+    create_echo_bind_group(device, layout, view, view, sampler, label)
+}
+
+/// Bind a texture view and sampler, plus a second view at binding 2 that
+/// the feedback echo tap reads (only feedback bind groups differ)
+fn create_echo_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    view: &wgpu::TextureView,
+    echo_view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+    label: &str,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some(label),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::TextureView(echo_view),
+            },
+        ],
+    })
+}
+
+/// Internal render target, used when supersampling (at a multiple of the
+/// output size) or with float feedback (so the frame fed back isn't
+/// quantized to 8 bits). The synth renders here; the output texture and the
+/// feedback buffers are resampled from it.
+struct WorkTarget {
+    _texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    bind_group: wgpu::BindGroup,
+}
+
+impl WorkTarget {
+    fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> Self {
+        let texture = create_target_texture(device, "Work Texture", width, height, format);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = create_texture_bind_group(device, layout, &view, sampler, "Work Bind Group");
+        Self {
+            _texture: texture,
+            view,
+            bind_group,
+        }
+    }
+}
+
+/// Ring of feedback buffers (2 = plain ping-pong). Each frame is stored
+/// over the oldest one, so reading buffer `i` as the previous frame,
+/// buffer `i + 1` (wrapping) is the oldest: the echo tap's frame.
+struct FeedbackTargets {
+    textures: Vec<wgpu::Texture>,
+    views: Vec<wgpu::TextureView>,
+    /// Bind group reading each buffer, with the oldest as its echo
+    bind_groups: Vec<wgpu::BindGroup>,
+    width: u32,
+    height: u32,
+}
+
+impl FeedbackTargets {
+    fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+        count: usize,
+    ) -> Self {
+        let textures: Vec<_> = (0..count)
+            .map(|i| create_target_texture(device, &format!("Feedback Texture {i}"), width, height, format))
+            .collect();
+        let views: Vec<_> = textures
+            .iter()
+            .map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()))
+            .collect();
+        let bind_groups = (0..count)
+            .map(|i| {
+                let echo = &views[(i + 1) % count];
+                create_echo_bind_group(device, layout, &views[i], echo, sampler, &format!("Feedback Bind Group {i}"))
+            })
+            .collect();
+
+        Self {
+            textures,
+            views,
+            bind_groups,
+            width,
+            height,
+        }
+    }
+}
+
+/// Output frames copied for texture sharing and not yet sent. With all of
+/// them in flight a frame isn't shared rather than waiting on the GPU.
+const SHARE_RING_SIZE: usize = 3;
+
+/// Staging buffer the output texture is copied into for texture sharing,
+/// read once its mapping completes a frame or two later
+struct ShareSlot {
+    buffer: wgpu::Buffer,
+    width: u32,
+    height: u32,
+    /// Row pitch in the buffer, padded to `COPY_BYTES_PER_ROW_ALIGNMENT`
+    bytes_per_row: u32,
+    /// Mapping of the buffer, while a copy is in flight
+    pending: Option<std::sync::mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>>,
+}
+
+impl ShareSlot {
+    fn new(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let bytes_per_row = (width * 4).div_ceil(align) * align;
+        Self {
+            buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Share Staging Buffer"),
+                size: bytes_per_row as u64 * height as u64,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }),
+            width,
+            height,
+            bytes_per_row,
+            pending: None,
+        }
+    }
+}
+
+/// Timestamp queries around the synth render pass. One measurement is in
+/// flight at a time; frames rendered meanwhile go untimed.
+struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    /// Nanoseconds per timestamp tick
+    period: f32,
+    /// Mapping of the readback buffer, while a measurement is in flight
+    pending: Option<std::sync::mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>>,
+    /// Most recent measurement, in milliseconds
+    last_ms: Option<f32>,
+}
+
+impl GpuTimer {
+    fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let size = 2 * wgpu::QUERY_SIZE as u64;
+        Self {
+            query_set: device.create_query_set(&wgpu::QuerySetDescriptor {
+                label: Some("Render Pass Timestamps"),
+                ty: wgpu::QueryType::Timestamp,
+                count: 2,
+            }),
+            resolve_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Timestamp Resolve Buffer"),
+                size,
+                usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            }),
+            readback_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Timestamp Readback Buffer"),
+                size,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }),
+            period: queue.get_timestamp_period(),
+            pending: None,
+            last_ms: None,
+        }
+    }
+}
+
+/// Create a device with no window surface, for offline rendering
+pub fn create_headless_device() -> Option<(Arc<wgpu::Device>, Arc<wgpu::Queue>)> {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::all(),
+        ..Default::default()
+    });
+
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        compatible_surface: None,
+        force_fallback_adapter: false,
+    }))?;
+
+    let (device, queue) = pollster::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: Some("Headless Device"),
+            required_features: wgpu::Features::empty(),
+            required_limits: wgpu::Limits::default(),
+            memory_hints: Default::default(),
+        },
+        None,
+    ))
+    .ok()?;
+
+    Some((Arc::new(device), Arc::new(queue)))
+}
+
+/// Main and blit pipelines built from the synth shader, for each target format
+struct Pipelines {
+    main: wgpu::RenderPipeline,
+    /// Resamples a texture into a target of a different size or format
+    blit: wgpu::RenderPipeline,
+    main_float: wgpu::RenderPipeline,
+    blit_float: wgpu::RenderPipeline,
+}
+
+impl Pipelines {
+    fn main(&self, float: bool) -> &wgpu::RenderPipeline {
+        if float { &self.main_float } else { &self.main }
+    }
+
+    fn blit(&self, float: bool) -> &wgpu::RenderPipeline {
+        if float { &self.blit_float } else { &self.blit }
+    }
+}
+
+fn create_pipelines(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+) -> Pipelines {
+    let create_pipeline = |label: &str, entry_point: &str, format: wgpu::TextureFormat| {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some(entry_point),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        })
+    };
+
+    Pipelines {
+        main: create_pipeline("Render Pipeline", "fs_main", DISPLAY_FORMAT),
+        blit: create_pipeline("Blit Pipeline", "fs_blit", DISPLAY_FORMAT),
+        main_float: create_pipeline("Float Render Pipeline", "fs_main", FLOAT_FORMAT),
+        blit_float: create_pipeline("Float Blit Pipeline", "fs_blit", FLOAT_FORMAT),
+    }
+}
+
+/// Compile WGSL and build its pipelines, returning the validation error
+/// message instead of panicking if the shader is broken
+fn build_pipelines(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    source: &str,
+) -> Result<Pipelines, String> {
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Lite Shader"),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+    let pipelines = create_pipelines(device, layout, &shader);
+    match pollster::block_on(device.pop_error_scope()) {
+        Some(error) => Err(error.to_string()),
+        None => Ok(pipelines),
+    }
+}
+
 /// Renderer for Phosphlux Lite
 pub struct Renderer {
     device: Arc<wgpu::Device>,
     queue: Arc<wgpu::Queue>,
-    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    pipelines: Pipelines,
     vertex_buffer: wgpu::Buffer,
     uniform_buffer: wgpu::Buffer,
     bind_group_layout: wgpu::BindGroupLayout,
     texture_bind_group_layout: wgpu::BindGroupLayout,
     bind_group: wgpu::BindGroup,
 
-    // Feedback textures (ping-pong)
-    feedback_textures: [wgpu::Texture; 2],
-    feedback_views: [wgpu::TextureView; 2],
-    feedback_bind_groups: [wgpu::BindGroup; 2],
+    // Feedback textures (a ring of trail_length), sized by feedback_scale,
+    // 16-bit float with float_feedback
+    feedback: FeedbackTargets,
+    float_feedback: bool,
+    feedback_scale: f32,
+    trail_length: u32,
     current_feedback: usize,
 
-    // Output texture for egui
+    // Output texture for egui (main pass target)
     output_texture: wgpu::Texture,
     output_view: wgpu::TextureView,
+    output_bind_group: wgpu::BindGroup,
+
+    // Synth rendered at `supersample`x and/or in float, then resampled
+    // (None at 1x without float feedback)
+    supersample: u32,
+    work_target: Option<WorkTarget>,
+
+    // Small target for measuring the amplitude signal (auto-normalize)
+    probe_texture: wgpu::Texture,
+    probe_view: wgpu::TextureView,
 
     sampler: wgpu::Sampler,
     width: u32,
     height: u32,
+
+    // Image input source, sampled with wrapping so it tiles
+    input_image: SourceTexture,
+    image_sampler: wgpu::Sampler,
+
+    // Camera input source, clamped at its edges
+    camera: SourceTexture,
+
+    // Palette colorize lookup table (LUT_SIZE x 1) and the colors in it
+    lut_texture: wgpu::Texture,
+    lut_colors: Vec<[f32; 3]>,
+
+    /// Startup compile error, until taken for display
+    shader_error: Option<String>,
+
+    /// GPU timing of the synth pass (None while off or unsupported)
+    gpu_timer: Option<GpuTimer>,
+
+    /// Texture sharing readback ring, and the slots in flight, oldest first
+    share_slots: Vec<ShareSlot>,
+    share_queue: VecDeque<usize>,
 }
 
 impl Renderer {
@@ -69,13 +547,6 @@ impl Renderer {
         width: u32,
         height: u32,
     ) -> Self {
-        // Load shader
-        let shader_source = include_str!("../shaders/lite.wgsl");
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Lite Shader"),
-            source: wgpu::ShaderSource::Wgsl(shader_source.into()),
-        });
-
         // Create uniform buffer
         let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Synth Uniforms"),
@@ -87,16 +558,29 @@ impl Renderer {
         // Bind group layout for uniforms
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Uniform Bind Group Layout"),
-            entries: &[wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::FRAGMENT,
-                ty: wgpu::BindingType::Buffer {
-                    ty: wgpu::BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Palette colorize lookup table, read with textureLoad
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
                 },
-                count: None,
-            }],
+            ],
         });
 
         // Bind group layout for textures
@@ -120,59 +604,44 @@ impl Renderer {
                         ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                         count: None,
                     },
+                    // Feedback echo tap (a repeat of binding 0 elsewhere)
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        },
+                        count: None,
+                    },
                 ],
             });
 
-        // Create bind group
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Uniform Bind Group"),
-            layout: &bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: uniform_buffer.as_entire_binding(),
-            }],
-        });
-
         // Create pipeline
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Render Pipeline Layout"),
-            bind_group_layouts: &[&bind_group_layout, &texture_bind_group_layout],
+            bind_group_layouts: &[
+                &bind_group_layout,
+                &texture_bind_group_layout,
+                &texture_bind_group_layout,
+                &texture_bind_group_layout,
+            ],
             push_constant_ranges: &[],
         });
 
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[Vertex::desc()],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: wgpu::TextureFormat::Rgba8UnormSrgb,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
+        // Load shader, falling back to a plain passthrough if it doesn't compile
+        let mut shader_error = None;
+        let pipelines =
+            match build_pipelines(&device, &pipeline_layout, include_str!("../shaders/lite.wgsl")) {
+                Ok(pipelines) => pipelines,
+                Err(e) => {
+                    log::error!("Shader failed to compile: {e}");
+                    shader_error = Some(e);
+                    build_pipelines(&device, &pipeline_layout, include_str!("../shaders/passthrough.wgsl"))
+                        .expect("passthrough shader must compile")
+                }
+            };
 
         // Create vertex buffer
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -192,114 +661,210 @@ impl Renderer {
             ..Default::default()
         });
 
-        // Create feedback textures (ping-pong for temporal effects)
-        let create_feedback_texture = || {
-            device.create_texture(&wgpu::TextureDescriptor {
-                label: Some("Feedback Texture"),
-                size: wgpu::Extent3d {
-                    width,
-                    height,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8UnormSrgb,
-                usage: wgpu::TextureUsages::TEXTURE_BINDING
-                    | wgpu::TextureUsages::RENDER_ATTACHMENT
-                    | wgpu::TextureUsages::COPY_SRC,
-                view_formats: &[],
-            })
-        };
-
-        let feedback_textures = [create_feedback_texture(), create_feedback_texture()];
-        let feedback_views = [
-            feedback_textures[0].create_view(&wgpu::TextureViewDescriptor::default()),
-            feedback_textures[1].create_view(&wgpu::TextureViewDescriptor::default()),
-        ];
-
-        let feedback_bind_groups = [
-            device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Feedback Bind Group 0"),
-                layout: &texture_bind_group_layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: wgpu::BindingResource::TextureView(&feedback_views[0]),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: wgpu::BindingResource::Sampler(&sampler),
-                    },
-                ],
-            }),
-            device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Feedback Bind Group 1"),
-                layout: &texture_bind_group_layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: wgpu::BindingResource::TextureView(&feedback_views[1]),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: wgpu::BindingResource::Sampler(&sampler),
-                    },
-                ],
-            }),
-        ];
-
-        // Create output texture
-        let output_texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Output Texture"),
+        let image_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let lut_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Palette LUT"),
             size: wgpu::Extent3d {
-                width,
-                height,
+                width: LUT_SIZE as u32,
+                height: 1,
                 depth_or_array_layers: 1,
             },
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING
-                | wgpu::TextureUsages::RENDER_ATTACHMENT
-                | wgpu::TextureUsages::COPY_SRC
-                | wgpu::TextureUsages::COPY_DST,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             view_formats: &[],
         });
+        let lut_view = lut_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Create bind group
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Uniform Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&lut_view),
+                },
+            ],
+        });
+
+        let input_image = SourceTexture::placeholder(&device, &queue, &texture_bind_group_layout, &image_sampler);
+        let camera = SourceTexture::placeholder(&device, &queue, &texture_bind_group_layout, &sampler);
+
+        // Create feedback textures (ping-pong for temporal effects)
+        let feedback_scale = 1.0;
+        let trail_length = 2;
+        let (feedback_width, feedback_height) = scaled_size(width, height, feedback_scale);
+        let feedback = FeedbackTargets::new(
+            &device,
+            &texture_bind_group_layout,
+            &sampler,
+            feedback_width,
+            feedback_height,
+            DISPLAY_FORMAT,
+            trail_length as usize,
+        );
+
+        // Create output texture
+        let output_texture = create_target_texture(&device, "Output Texture", width, height, DISPLAY_FORMAT);
         let output_view = output_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let output_bind_group = create_texture_bind_group(
+            &device,
+            &texture_bind_group_layout,
+            &output_view,
+            &sampler,
+            "Output Bind Group",
+        );
+
+        let probe_texture =
+            create_target_texture(&device, "Probe Texture", PROBE_WIDTH, PROBE_HEIGHT, DISPLAY_FORMAT);
+        let probe_view = probe_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
         Self {
             device,
             queue,
-            pipeline,
+            pipeline_layout,
+            pipelines,
             vertex_buffer,
             uniform_buffer,
             bind_group_layout,
             texture_bind_group_layout,
             bind_group,
-            feedback_textures,
-            feedback_views,
-            feedback_bind_groups,
+            feedback,
+            float_feedback: false,
+            feedback_scale,
+            trail_length,
             current_feedback: 0,
             output_texture,
             output_view,
+            output_bind_group,
+            supersample: 1,
+            work_target: None,
+            probe_texture,
+            probe_view,
             sampler,
             width,
             height,
+            input_image,
+            image_sampler,
+            camera,
+            lut_texture,
+            lut_colors: Vec::new(),
+            shader_error,
+            gpu_timer: None,
+            share_slots: Vec::new(),
+            share_queue: VecDeque::new(),
+        }
+    }
+
+    /// Rebuild the pipelines from new WGSL source. On a compile or
+    /// validation error the current pipelines are kept and the message returned.
+    pub fn reload_shader(&mut self, source: &str) -> Result<(), String> {
+        self.pipelines = build_pipelines(&self.device, &self.pipeline_layout, source)?;
+        Ok(())
+    }
+
+    /// Compile error from startup, if the passthrough shader is running instead
+    pub fn take_shader_error(&mut self) -> Option<String> {
+        self.shader_error.take()
+    }
+
+    /// Render a frame and store it as the next feedback frame
+    pub fn render(&mut self, state: &SynthState, params: &FrameParams) {
+        self.render_frame(state, params, true);
+    }
+
+    /// Render a frame for display only, leaving the feedback buffers as they
+    /// are (used between fixed-rate feedback steps)
+    pub fn render_preview(&mut self, state: &SynthState, params: &FrameParams) {
+        self.render_frame(state, params, false);
+    }
+
+    /// Render the raw amplitude signal at low resolution and read it back
+    /// (linear RGB, one entry per probe pixel) for auto-normalize
+    pub fn probe_signal(&mut self, state: &SynthState, params: &FrameParams) -> Vec<[f32; 3]> {
+        self.probe(state, &FrameParams { signal_probe: true, ..*params })
+    }
+
+    /// Render this frame's output at low resolution and read it back,
+    /// without the flash limiter (which the result is used to set)
+    pub fn probe_output(&mut self, state: &SynthState, params: &FrameParams) -> Vec<[f32; 3]> {
+        self.probe(state, &FrameParams { flash_gain: 1.0, ..*params })
+    }
+
+    /// Render into the probe texture, sampling the current feedback frame
+    fn probe(&mut self, state: &SynthState, params: &FrameParams) -> Vec<[f32; 3]> {
+        self.sync_lut(&state.colorize);
+        let mut uniforms = SynthUniforms::from_state(state, params);
+        uniforms.output_height = self.height as f32;
+        self.queue
+            .write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Probe Encoder"),
+            });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Probe Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &self.probe_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            render_pass.set_pipeline(self.pipelines.main(false));
+            render_pass.set_bind_group(0, &self.bind_group, &[]);
+            render_pass.set_bind_group(1, &self.feedback.bind_groups[self.current_feedback], &[]);
+            render_pass.set_bind_group(2, &self.input_image.bind_group, &[]);
+            render_pass.set_bind_group(3, &self.camera.bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.draw(0..6, 0..1);
         }
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let rows: Vec<u32> = (0..PROBE_HEIGHT).collect();
+        self.read_texture_rows(&self.probe_texture, &rows)
+            .iter()
+            .flat_map(|row| row.chunks_exact(4))
+            .map(|px| [srgb_to_linear(px[0]), srgb_to_linear(px[1]), srgb_to_linear(px[2])])
+            .collect()
     }
 
-    /// Render a frame
-    pub fn render(&mut self, state: &SynthState, time: f32, frame: u32) {
+    fn render_frame(&mut self, state: &SynthState, params: &FrameParams, step_feedback: bool) {
+        self.sync_lut(&state.colorize);
+
         // Update uniforms
-        let uniforms = SynthUniforms::from_state(state, time, frame);
+        let mut uniforms = SynthUniforms::from_state(state, params);
+        uniforms.pixel_scale = self.supersample as f32;
+        uniforms.output_height = self.height as f32;
         self.queue
             .write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
 
-        // Determine which feedback texture to read from and write to
+        // Determine which feedback texture to read from and write to (the
+        // oldest, which the echo tap reads until then)
         let read_index = self.current_feedback;
-        let write_index = 1 - self.current_feedback;
+        let write_index = (self.current_feedback + 1) % self.feedback.views.len();
 
         // Create command encoder
         let mut encoder = self
@@ -308,12 +873,18 @@ impl Renderer {
                 label: Some("Render Encoder"),
             });
 
-        // Render to feedback texture (write_index)
+        // Render to output texture (or the work target), sampling the
+        // previous feedback frame
+        let target_view = match &self.work_target {
+            Some(target) => &target.view,
+            None => &self.output_view,
+        };
+        let timer = self.gpu_timer.as_ref().filter(|timer| timer.pending.is_none());
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &self.feedback_views[write_index],
+                    view: target_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
@@ -322,42 +893,141 @@ impl Renderer {
                 })],
                 depth_stencil_attachment: None,
                 occlusion_query_set: None,
-                timestamp_writes: None,
+                timestamp_writes: timer.map(|timer| wgpu::RenderPassTimestampWrites {
+                    query_set: &timer.query_set,
+                    beginning_of_pass_write_index: Some(0),
+                    end_of_pass_write_index: Some(1),
+                }),
             });
 
-            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_pipeline(self.pipelines.main(self.float_feedback));
             render_pass.set_bind_group(0, &self.bind_group, &[]);
-            render_pass.set_bind_group(1, &self.feedback_bind_groups[read_index], &[]);
+            render_pass.set_bind_group(1, &self.feedback.bind_groups[read_index], &[]);
+            render_pass.set_bind_group(2, &self.input_image.bind_group, &[]);
+            render_pass.set_bind_group(3, &self.camera.bind_group, &[]);
             render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
             render_pass.draw(0..6, 0..1);
         }
+        let timed = timer.is_some();
+        if let Some(timer) = timer {
+            encoder.resolve_query_set(&timer.query_set, 0..2, &timer.resolve_buffer, 0);
+            let size = timer.readback_buffer.size();
+            encoder.copy_buffer_to_buffer(&timer.resolve_buffer, 0, &timer.readback_buffer, 0, size);
+        }
 
-        // Copy to output texture
-        encoder.copy_texture_to_texture(
-            wgpu::ImageCopyTexture {
-                texture: &self.feedback_textures[write_index],
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
+        // Resolve into the output: at 2x, each bilinear tap lands between
+        // four samples (a box downsample); at 1x it's an exact copy
+        if let Some(target) = &self.work_target {
+            self.blit(&mut encoder, &target.bind_group, &self.output_view, false, "Resolve Pass");
+        }
+
+        // Store the frame and advance the feedback ring
+        if step_feedback {
+            self.store_feedback(&mut encoder, write_index);
+            self.current_feedback = write_index;
+        }
+
+        // Submit
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        // Read the timestamps back without waiting; gpu_time_ms picks them up
+        if let Some(timer) = self.gpu_timer.as_mut().filter(|_| timed) {
+            let (tx, rx) = std::sync::mpsc::channel();
+            timer.readback_buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+                let _ = tx.send(result);
+            });
+            timer.pending = Some(rx);
+        }
+    }
+
+    /// Upload the palette mode's lookup table when its colors change
+    fn sync_lut(&mut self, colorize: &ColorizeStage) {
+        let colors = colorize.lut_colors();
+        if colors == self.lut_colors {
+            return;
+        }
+        self.queue.write_texture(
             wgpu::ImageCopyTexture {
-                texture: &self.output_texture,
+                texture: &self.lut_texture,
                 mip_level: 0,
                 origin: wgpu::Origin3d::ZERO,
                 aspect: wgpu::TextureAspect::All,
             },
-            wgpu::Extent3d {
-                width: self.width,
-                height: self.height,
-                depth_or_array_layers: 1,
+            &lut_texels(&colors),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * LUT_SIZE as u32),
+                rows_per_image: Some(1),
             },
+            self.lut_texture.size(),
         );
+        self.lut_colors = colors;
+    }
 
-        // Submit
-        self.queue.submit(std::iter::once(encoder.finish()));
+    /// Store the frame into feedback buffer `write_index`: a straight copy
+    /// of the output at full size, otherwise a filtered resample (from the
+    /// work target when there is one, keeping float precision)
+    fn store_feedback(&self, encoder: &mut wgpu::CommandEncoder, write_index: usize) {
+        let target = &self.feedback.views[write_index];
+        if let Some(work) = &self.work_target {
+            self.blit(encoder, &work.bind_group, target, self.float_feedback, "Feedback Blit Pass");
+        } else if self.feedback.width == self.width && self.feedback.height == self.height {
+            encoder.copy_texture_to_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &self.output_texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::ImageCopyTexture {
+                    texture: &self.feedback.textures[write_index],
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::Extent3d {
+                    width: self.width,
+                    height: self.height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        } else {
+            self.blit(encoder, &self.output_bind_group, target, false, "Feedback Blit Pass");
+        }
+    }
 
-        // Swap feedback buffers
-        self.current_feedback = write_index;
+    /// Resample a texture (bound as `source`) into `target` with filtering;
+    /// `float` picks the pipeline for a float target
+    fn blit(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &wgpu::BindGroup,
+        target: &wgpu::TextureView,
+        float: bool,
+        label: &str,
+    ) {
+        let mut blit_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some(label),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        blit_pass.set_pipeline(self.pipelines.blit(float));
+        blit_pass.set_bind_group(0, &self.bind_group, &[]);
+        blit_pass.set_bind_group(1, source, &[]);
+        blit_pass.set_bind_group(2, &self.input_image.bind_group, &[]);
+        blit_pass.set_bind_group(3, &self.camera.bind_group, &[]);
+        blit_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        blit_pass.draw(0..6, 0..1);
     }
 
     /// Get output texture view for egui
@@ -370,6 +1040,189 @@ impl Renderer {
         &self.output_texture
     }
 
+    /// Read back rows of the output texture as linear RGB (0-1).
+    /// Blocks until the GPU has finished the frame, so keep `rows` short.
+    pub fn read_output_rows(&self, rows: &[u32]) -> Vec<Vec<[f32; 3]>> {
+        // Texture is sRGB; decode back to the values the shader wrote
+        self.read_texture_rows(&self.output_texture, rows)
+            .iter()
+            .map(|row| {
+                row.chunks_exact(4)
+                    .map(|px| [srgb_to_linear(px[0]), srgb_to_linear(px[1]), srgb_to_linear(px[2])])
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Render one frame of an offline sequence: `steps` feedback steps (0
+    /// redisplays without advancing feedback, as between live steps), then
+    /// read the frame back. Frames must be rendered in order, since each
+    /// builds on the feedback left by the last.
+    pub fn render_sequence_frame(&mut self, state: &SynthState, params: &FrameParams, steps: u32) -> image::RgbaImage {
+        match steps {
+            0 => self.render_preview(state, params),
+            steps => {
+                for _ in 0..steps {
+                    self.render(state, params);
+                }
+            }
+        }
+        self.capture_frame()
+    }
+
+    /// Read back the whole output frame as an sRGB image
+    pub fn capture_frame(&self) -> image::RgbaImage {
+        let rows: Vec<u32> = (0..self.height).collect();
+        let pixels = self.read_texture_rows(&self.output_texture, &rows).concat();
+        image::RgbaImage::from_raw(self.width, self.height, pixels)
+            .unwrap_or_else(|| image::RgbaImage::new(self.width, self.height))
+    }
+
+    /// Publish the output frame to other apps without waiting on the GPU:
+    /// this frame is copied into a staging buffer, and copies whose mapping
+    /// has completed (a frame or two back) are sent, oldest first
+    pub fn share_output(&mut self, sender: &mut TextureSender) {
+        self.device.poll(wgpu::Maintain::Poll);
+        while let Some(&index) = self.share_queue.front() {
+            let slot = &mut self.share_slots[index];
+            let result = match slot.pending.as_ref().map(|rx| rx.try_recv()) {
+                Some(Ok(result)) => result,
+                Some(Err(std::sync::mpsc::TryRecvError::Empty)) => break,
+                _ => Err(wgpu::BufferAsyncError),
+            };
+            self.share_queue.pop_front();
+            slot.pending = None;
+            if result.is_ok() {
+                let data = slot.buffer.slice(..).get_mapped_range();
+                sender.send(slot.width, slot.height, slot.bytes_per_row, &data);
+                drop(data);
+                slot.buffer.unmap();
+            }
+        }
+
+        // A free slot for this frame, reallocated if the size changed
+        let (width, height) = (self.width, self.height);
+        let index = match self.share_slots.iter().position(|slot| slot.pending.is_none()) {
+            Some(index) => index,
+            None if self.share_slots.len() < SHARE_RING_SIZE => {
+                self.share_slots.push(ShareSlot::new(&self.device, width, height));
+                self.share_slots.len() - 1
+            }
+            None => return,
+        };
+        let slot = &mut self.share_slots[index];
+        if (slot.width, slot.height) != (width, height) {
+            *slot = ShareSlot::new(&self.device, width, height);
+        }
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Share Encoder"),
+            });
+        encoder.copy_texture_to_buffer(
+            self.output_texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &slot.buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(slot.bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        slot.buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        slot.pending = Some(rx);
+        self.share_queue.push_back(index);
+    }
+
+    /// Read back rows of a render target as raw RGBA8 (sRGB) bytes
+    fn read_texture_rows(&self, texture: &wgpu::Texture, rows: &[u32]) -> Vec<Vec<u8>> {
+        if rows.is_empty() {
+            return Vec::new();
+        }
+
+        // Each row copy must start on a 256-byte boundary
+        let (width, height) = (texture.width(), texture.height());
+        let unpadded = width * 4;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let bytes_per_row = unpadded.div_ceil(align) * align;
+
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: bytes_per_row as u64 * rows.len() as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Readback Encoder"),
+            });
+
+        for (i, &row) in rows.iter().enumerate() {
+            encoder.copy_texture_to_buffer(
+                wgpu::ImageCopyTexture {
+                    texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: 0,
+                        y: row.min(height - 1),
+                        z: 0,
+                    },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::ImageCopyBuffer {
+                    buffer: &buffer,
+                    layout: wgpu::ImageDataLayout {
+                        offset: i as u64 * bytes_per_row as u64,
+                        bytes_per_row: Some(bytes_per_row),
+                        rows_per_image: None,
+                    },
+                },
+                wgpu::Extent3d {
+                    width,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        if !matches!(rx.recv(), Ok(Ok(()))) {
+            log::warn!("Texture readback failed");
+            return Vec::new();
+        }
+
+        let data = slice.get_mapped_range();
+        let result = data
+            .chunks(bytes_per_row as usize)
+            .map(|row| row[..unpadded as usize].to_vec())
+            .collect();
+        drop(data);
+        buffer.unmap();
+
+        result
+    }
+
     pub fn width(&self) -> u32 {
         self.width
     }
@@ -378,6 +1231,203 @@ impl Renderer {
         self.height
     }
 
+    /// Wipe all feedback buffers to black
+    pub fn clear_feedback(&mut self) {
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Clear Feedback Encoder"),
+            });
+
+        for view in &self.feedback.views {
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Clear Feedback Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+        }
+
+        self.queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Feedback buffer size relative to the output (1 = same size)
+    pub fn feedback_scale(&self) -> f32 {
+        self.feedback_scale
+    }
+
+    /// Change the feedback buffer resolution; clears the feedback trail
+    pub fn set_feedback_scale(&mut self, scale: f32) {
+        let scale = scale.clamp(0.125, 1.0);
+        if scale == self.feedback_scale {
+            return;
+        }
+
+        self.feedback_scale = scale;
+        self.recreate_feedback();
+    }
+
+    /// Number of feedback buffers kept (2 to `MAX_TRAIL_LENGTH`); the echo
+    /// tap reads the oldest. Changing it clears the feedback trail.
+    pub fn set_trail_length(&mut self, length: u32) {
+        let length = length.clamp(2, MAX_TRAIL_LENGTH);
+        if length == self.trail_length {
+            return;
+        }
+
+        self.trail_length = length;
+        self.recreate_feedback();
+    }
+
+    /// Render the synth at `factor`x the output size (1 = off, max 2)
+    pub fn set_supersample(&mut self, factor: u32) {
+        let factor = factor.clamp(1, 2);
+        if factor == self.supersample {
+            return;
+        }
+
+        self.supersample = factor;
+        self.recreate_work_target();
+    }
+
+    /// Keep feedback in 16-bit float instead of 8-bit, so slow drifts and
+    /// long decays don't stall on rounding; clears the feedback trail
+    pub fn set_float_feedback(&mut self, enabled: bool) {
+        if enabled == self.float_feedback {
+            return;
+        }
+
+        self.float_feedback = enabled;
+        self.recreate_feedback();
+        self.recreate_work_target();
+    }
+
+    /// Whether the device can time render passes (TIMESTAMP_QUERY)
+    pub fn supports_gpu_timing(&self) -> bool {
+        self.device.features().contains(wgpu::Features::TIMESTAMP_QUERY)
+    }
+
+    /// Time the synth render pass on the GPU; ignored if unsupported
+    pub fn set_gpu_timing(&mut self, enabled: bool) {
+        if !enabled {
+            self.gpu_timer = None;
+        } else if self.gpu_timer.is_none() && self.supports_gpu_timing() {
+            self.gpu_timer = Some(GpuTimer::new(&self.device, &self.queue));
+        }
+    }
+
+    /// Milliseconds the synth render pass took on the GPU, from the latest
+    /// measurement that has finished
+    pub fn gpu_time_ms(&mut self) -> Option<f32> {
+        let timer = self.gpu_timer.as_mut()?;
+        self.device.poll(wgpu::Maintain::Poll);
+        match timer.pending.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            Some(Ok(())) => {
+                let data = timer.readback_buffer.slice(..).get_mapped_range();
+                let mut ticks = data.chunks_exact(8).map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()));
+                if let (Some(begin), Some(end)) = (ticks.next(), ticks.next()) {
+                    timer.last_ms = Some(end.saturating_sub(begin) as f32 * timer.period / 1_000_000.0);
+                }
+                drop(data);
+                timer.readback_buffer.unmap();
+                timer.pending = None;
+            }
+            Some(Err(_)) => timer.pending = None,
+            None => {}
+        }
+        timer.last_ms
+    }
+
+    fn recreate_work_target(&mut self) {
+        let format = if self.float_feedback { FLOAT_FORMAT } else { DISPLAY_FORMAT };
+        self.work_target = (self.supersample > 1 || self.float_feedback).then(|| {
+            WorkTarget::new(
+                &self.device,
+                &self.texture_bind_group_layout,
+                &self.sampler,
+                self.width * self.supersample,
+                self.height * self.supersample,
+                format,
+            )
+        });
+    }
+
+    fn recreate_feedback(&mut self) {
+        let (width, height) = scaled_size(self.width, self.height, self.feedback_scale);
+        let format = if self.float_feedback { FLOAT_FORMAT } else { DISPLAY_FORMAT };
+        self.feedback = FeedbackTargets::new(
+            &self.device,
+            &self.texture_bind_group_layout,
+            &self.sampler,
+            width,
+            height,
+            format,
+            self.trail_length as usize,
+        );
+        self.current_feedback = 0;
+    }
+
+    /// Load the still the Image input source samples
+    pub fn load_input_image(&mut self, path: &std::path::Path) -> Result<(), image::ImageError> {
+        let image = image::open(path)?.to_rgba8();
+        self.input_image = SourceTexture::new(
+            &self.device,
+            &self.queue,
+            &self.texture_bind_group_layout,
+            &self.image_sampler,
+            image.width(),
+            image.height(),
+            image.as_raw(),
+        );
+        Ok(())
+    }
+
+    /// Back to the neutral grey placeholder
+    pub fn clear_input_image(&mut self) {
+        self.input_image = SourceTexture::placeholder(
+            &self.device,
+            &self.queue,
+            &self.texture_bind_group_layout,
+            &self.image_sampler,
+        );
+    }
+
+    /// Upload a camera frame for the Camera source, reallocating only when
+    /// the camera's resolution changes
+    pub fn update_camera(&mut self, width: u32, height: u32, rgba: &[u8]) {
+        let size = self.camera.texture.size();
+        if size.width == width && size.height == height {
+            self.camera.write(&self.queue, rgba);
+        } else {
+            self.camera = SourceTexture::new(
+                &self.device,
+                &self.queue,
+                &self.texture_bind_group_layout,
+                &self.sampler,
+                width,
+                height,
+                rgba,
+            );
+        }
+    }
+
+    /// Back to grey (camera closed); cheap to call every frame
+    pub fn clear_camera(&mut self) {
+        let size = self.camera.texture.size();
+        if size.width == 1 && size.height == 1 {
+            return;
+        }
+        self.camera = SourceTexture::placeholder(&self.device, &self.queue, &self.texture_bind_group_layout, &self.sampler);
+    }
+
     /// Resize textures if needed
     pub fn resize(&mut self, new_width: u32, new_height: u32) {
         if new_width == self.width && new_height == self.height {
@@ -387,85 +1437,22 @@ impl Renderer {
         self.width = new_width;
         self.height = new_height;
 
-        // Recreate feedback textures
-        let create_feedback_texture = || {
-            self.device.create_texture(&wgpu::TextureDescriptor {
-                label: Some("Feedback Texture"),
-                size: wgpu::Extent3d {
-                    width: new_width,
-                    height: new_height,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8UnormSrgb,
-                usage: wgpu::TextureUsages::TEXTURE_BINDING
-                    | wgpu::TextureUsages::RENDER_ATTACHMENT
-                    | wgpu::TextureUsages::COPY_SRC,
-                view_formats: &[],
-            })
-        };
-
-        self.feedback_textures = [create_feedback_texture(), create_feedback_texture()];
-        self.feedback_views = [
-            self.feedback_textures[0].create_view(&wgpu::TextureViewDescriptor::default()),
-            self.feedback_textures[1].create_view(&wgpu::TextureViewDescriptor::default()),
-        ];
-
-        self.feedback_bind_groups = [
-            self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Feedback Bind Group 0"),
-                layout: &self.texture_bind_group_layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: wgpu::BindingResource::TextureView(&self.feedback_views[0]),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: wgpu::BindingResource::Sampler(&self.sampler),
-                    },
-                ],
-            }),
-            self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Feedback Bind Group 1"),
-                layout: &self.texture_bind_group_layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: wgpu::BindingResource::TextureView(&self.feedback_views[1]),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: wgpu::BindingResource::Sampler(&self.sampler),
-                    },
-                ],
-            }),
-        ];
-
         // Recreate output texture
-        self.output_texture = self.device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Output Texture"),
-            size: wgpu::Extent3d {
-                width: new_width,
-                height: new_height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING
-                | wgpu::TextureUsages::RENDER_ATTACHMENT
-                | wgpu::TextureUsages::COPY_SRC
-                | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
-        });
+        self.output_texture =
+            create_target_texture(&self.device, "Output Texture", new_width, new_height, DISPLAY_FORMAT);
         self.output_view = self
             .output_texture
             .create_view(&wgpu::TextureViewDescriptor::default());
+        self.output_bind_group = create_texture_bind_group(
+            &self.device,
+            &self.texture_bind_group_layout,
+            &self.output_view,
+            &self.sampler,
+            "Output Bind Group",
+        );
 
-        self.current_feedback = 0;
+        // Recreate feedback and work textures
+        self.recreate_feedback();
+        self.recreate_work_target();
     }
 }
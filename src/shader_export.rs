@@ -0,0 +1,104 @@
+//! Export the current patch as a standalone WGSL shader
+//!
+//! The exported file is lite.wgsl with the `synth` uniform replaced by the
+//! patch's values baked in as a constant. The only inputs left are the clock
+//! and the previous frame:
+//!
+//! - `@group(0) @binding(0)`: `Clock { time: f32, frame: u32 }` uniform
+//! - `@group(0) @binding(1)`: the Palette colorize mode's 256x1 lookup
+//!   table (RGBA8, unfiltered)
+//! - `@group(1) @binding(0/1)`: the previous output frame and its sampler.
+//!   Feedback reads this texture, so the host has to ping-pong two render
+//!   targets (render into one while sampling the other). Bind a black texture
+//!   for patches without feedback. `@group(1) @binding(2)` is the echo
+//!   tap's frame, `trail_length - 1` frames older; bind the same texture
+//!   as binding 0 for patches without an echo.
+//! - `@group(2) @binding(0/1)`: the Image input source's texture and a
+//!   repeating sampler. Bind any texture (a 1x1 grey one will do) for
+//!   patches that don't use it.
+//! - `@group(3) @binding(0/1)`: the Camera input source's texture and a
+//!   clamping sampler, likewise.
+//!
+//! Entry points are unchanged: `vs_main` expects a fullscreen quad with
+//! position and uv attributes, `fs_main` renders the patch.
+
+use crate::synth::SynthUniforms;
+
+const SHADER: &str = include_str!("../shaders/lite.wgsl");
+const UNIFORM_DECL: &str = "@group(0) @binding(0)\nvar<uniform> synth: Synth;";
+const FS_MAIN: &str = "fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {\n";
+
+/// WGSL source reproducing a patch without the synth uniform buffer
+pub fn export_wgsl(uniforms: &SynthUniforms) -> Result<String, String> {
+    let source = SHADER.replace("\r\n", "\n");
+    if !source.contains(UNIFORM_DECL) || !source.contains(FS_MAIN) {
+        return Err("shader layout changed; update shader_export".to_string());
+    }
+
+    let patch = patch_constant(&source, uniforms)?;
+    let clock = format!(
+        "{patch}\n\
+         // Host-supplied clock: the exported shader's only uniform\n\
+         struct Clock {{\n    time: f32,\n    frame: u32,\n}}\n\n\
+         @group(0) @binding(0)\n\
+         var<uniform> clock: Clock;\n\n\
+         var<private> synth: Synth = PATCH;"
+    );
+
+    let header = "// Exported from Phosphlux Lite: patch values are baked into PATCH below.\n\
+                  // Bind a Clock uniform at group 0 and the previous output frame at group 1\n\
+                  // (render targets ping-pong so feedback sees the last frame).\n\n";
+    Ok(header.to_string()
+        + &source.replacen(UNIFORM_DECL, &clock, 1).replacen(
+            FS_MAIN,
+            &format!("{FS_MAIN}    synth.time = clock.time;\n    synth.frame = clock.frame;\n\n"),
+            1,
+        ))
+}
+
+/// `const PATCH: Synth = Synth(...)`, reading each member of the WGSL `Synth`
+/// struct from the matching words of the uniform block
+fn patch_constant(source: &str, uniforms: &SynthUniforms) -> Result<String, String> {
+    let start = source.find("struct Synth {").ok_or("no Synth struct in shader")?;
+    let end = start + source[start..].find('}').ok_or("unterminated Synth struct")?;
+    let words: &[u32] = bytemuck::cast_slice(std::slice::from_ref(uniforms));
+
+    let float = |word: u32| format!("{:?}", f32::from_bits(word));
+    let mut members = String::new();
+    let mut index = 0;
+    for line in source[start..end].lines().skip(1) {
+        let line = line.split("//").next().unwrap_or("").trim();
+        let Some((name, ty)) = line.split_once(':') else {
+            continue;
+        };
+        let ty = ty.trim().trim_end_matches(',');
+        let (value, size) = match ty {
+            "f32" => (words.get(index).map(|&w| float(w)), 1),
+            "u32" => (words.get(index).map(|&w| format!("{w}u")), 1),
+            "vec3<f32>" => (
+                words.get(index..index + 3).map(|w| {
+                    format!("vec3<f32>({}, {}, {})", float(w[0]), float(w[1]), float(w[2]))
+                }),
+                3,
+            ),
+            "array<vec4<f32>, 16>" => (
+                words.get(index..index + 64).map(|w| {
+                    let items: Vec<String> = w
+                        .chunks(4)
+                        .map(|c| {
+                            format!("vec4<f32>({}, {}, {}, {})", float(c[0]), float(c[1]), float(c[2]), float(c[3]))
+                        })
+                        .collect();
+                    format!("array<vec4<f32>, 16>(\n        {},\n    )", items.join(",\n        "))
+                }),
+                64,
+            ),
+            _ => return Err(format!("unsupported Synth member type {ty}")),
+        };
+        let value = value.ok_or("Synth struct is larger than SynthUniforms")?;
+        members += &format!("    {value}, // {}\n", name.trim());
+        index += size;
+    }
+
+    Ok(format!("const PATCH: Synth = Synth(\n{members});\n"))
+}
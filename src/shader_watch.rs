@@ -0,0 +1,70 @@
+//! Shader hot reload for debug builds
+//!
+//! Watches shaders/lite.wgsl in the source tree with `notify` so shader edits
+//! show up without a rebuild. Release builds only use the embedded copy.
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+
+pub struct ShaderWatcher {
+    path: PathBuf,
+    /// Kept alive for as long as changes should be reported (None if watching failed)
+    _watcher: Option<RecommendedWatcher>,
+    events: Receiver<notify::Result<notify::Event>>,
+    /// The file differs from what the renderer was last given
+    changed: bool,
+}
+
+impl ShaderWatcher {
+    pub fn new() -> Self {
+        let path = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/shaders/lite.wgsl"));
+        let (tx, events) = mpsc::channel();
+
+        // Watch the directory: editors often save by replacing the file
+        let watcher = notify::recommended_watcher(tx).and_then(|mut watcher| {
+            let dir = path.parent().unwrap_or(&path);
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        });
+        let watcher = match watcher {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                log::warn!("Shader hot reload unavailable: {e}");
+                None
+            }
+        };
+
+        // Only pick up edits made since the build, not the file as embedded
+        let changed = std::fs::read_to_string(&path)
+            .is_ok_and(|source| source != include_str!("../shaders/lite.wgsl"));
+
+        Self {
+            path,
+            _watcher: watcher,
+            events,
+            changed,
+        }
+    }
+
+    /// Shader source, if the file changed since the last call
+    pub fn poll(&mut self) -> Option<String> {
+        // Coalesce the burst of events a single save produces
+        for event in self.events.try_iter().flatten() {
+            let relevant = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                && event.paths.iter().any(|p| p.file_name() == self.path.file_name());
+            self.changed |= relevant;
+        }
+        if !std::mem::take(&mut self.changed) {
+            return None;
+        }
+
+        match std::fs::read_to_string(&self.path) {
+            Ok(source) => Some(source),
+            Err(e) => {
+                log::warn!("Can't read {}: {e}", self.path.display());
+                None
+            }
+        }
+    }
+}
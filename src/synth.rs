@@ -3,8 +3,10 @@
 //! Defines the 7-stage fixed signal chain:
 //! INPUT -> GEOMETRY -> AMPLITUDE -> COLORIZE -> MIXER -> FEEDBACK -> OUTPUT
 
+use crate::palette::MAX_PALETTE_COLORS;
 use bytemuck::{Pod, Zeroable};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 /// Input source types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -21,6 +23,10 @@ pub enum InputSource {
     ShapeRect = 8,
     ShapeDiamond = 9,
     Checkerboard = 10,
+    /// A loaded still image (grey until one is loaded)
+    Image = 11,
+    /// Live webcam (grey without a camera)
+    Camera = 12,
 }
 
 impl Default for InputSource {
@@ -29,6 +35,52 @@ impl Default for InputSource {
     }
 }
 
+impl InputSource {
+    pub const ALL: [Self; 13] = [
+        Self::RampH,
+        Self::RampV,
+        Self::OscH,
+        Self::OscV,
+        Self::NoiseWhite,
+        Self::NoisePink,
+        Self::NoiseBrown,
+        Self::ShapeCircle,
+        Self::ShapeRect,
+        Self::ShapeDiamond,
+        Self::Checkerboard,
+        Self::Image,
+        Self::Camera,
+    ];
+}
+
+/// Waveform of the OscH/OscV sources
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u32)]
+pub enum OscWaveform {
+    Sine = 0,
+    Triangle = 1,
+    Saw = 2,
+    Square = 3,
+    /// Square with an adjustable duty cycle (`osc_duty`)
+    Pulse = 4,
+}
+
+impl Default for OscWaveform {
+    fn default() -> Self {
+        Self::Sine
+    }
+}
+
+impl OscWaveform {
+    pub const ALL: [Self; 5] = [
+        Self::Sine,
+        Self::Triangle,
+        Self::Saw,
+        Self::Square,
+        Self::Pulse,
+    ];
+}
+
 /// Colorize mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(u32)]
@@ -37,6 +89,8 @@ pub enum ColorMode {
     Threshold = 1,
     Gradient = 2,
     Monochrome = 3,
+    /// Luminance through a lookup palette, in hard bands
+    Palette = 4,
 }
 
 impl Default for ColorMode {
@@ -45,6 +99,42 @@ impl Default for ColorMode {
     }
 }
 
+impl ColorMode {
+    pub const ALL: [Self; 5] = [
+        Self::Spectrum,
+        Self::Threshold,
+        Self::Gradient,
+        Self::Monochrome,
+        Self::Palette,
+    ];
+}
+
+/// Lookup coordinate for gradient colorize mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u32)]
+pub enum GradientSource {
+    /// Signal level (classic)
+    Luminance = 0,
+    /// Distance from the center of the (geometry-warped) frame
+    Radial = 1,
+    /// Angle around the center, like a hue wheel
+    Angle = 2,
+}
+
+impl Default for GradientSource {
+    fn default() -> Self {
+        Self::Luminance
+    }
+}
+
+impl GradientSource {
+    pub const ALL: [Self; 3] = [
+        Self::Luminance,
+        Self::Radial,
+        Self::Angle,
+    ];
+}
+
 /// Blend mode for mixer
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(u32)]
@@ -65,17 +155,86 @@ impl Default for BlendMode {
     }
 }
 
+impl BlendMode {
+    pub const ALL: [Self; 8] = [
+        Self::Mix,
+        Self::Add,
+        Self::Multiply,
+        Self::Screen,
+        Self::Overlay,
+        Self::Difference,
+        Self::LumaKeyA,
+        Self::LumaKeyB,
+    ];
+}
+
+/// What feedback shows where the transformed frame leaves the buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u32)]
+pub enum FeedbackWrap {
+    /// Stretch the edge pixels (classic)
+    Clamp = 0,
+    /// Tile the previous frame
+    Repeat = 1,
+    /// Tile with every other copy flipped, so borders line up seamlessly
+    Mirror = 2,
+}
+
+impl Default for FeedbackWrap {
+    fn default() -> Self {
+        Self::Clamp
+    }
+}
+
+impl FeedbackWrap {
+    pub const ALL: [Self; 3] = [
+        Self::Clamp,
+        Self::Repeat,
+        Self::Mirror,
+    ];
+}
+
+/// Display emulation effect in the output chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u32)]
+pub enum OutputEffect {
+    Vhs = 0,
+    Cable = 1,
+    Crt = 2,
+}
+
+impl OutputEffect {
+    /// Also the default chain order
+    pub const ALL: [Self; 3] = [Self::Vhs, Self::Cable, Self::Crt];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Vhs => "VHS",
+            Self::Cable => "Cable",
+            Self::Crt => "CRT",
+        }
+    }
+}
+
 
 /// Stage 1: Input Matrix
 /// Mix and combine signal sources
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
 pub struct InputStage {
     pub source_a: InputSource,
     pub source_b: InputSource,
     pub mix: f32,           // 0-1 blend between A and B
-    pub frequency: f32,     // 0.5-20 cycles
+    pub frequency: f32,     // 0.5-100 cycles
     pub phase: f32,         // 0-1 phase offset
     pub rotation: f32,      // 0-1 (maps to 0-2pi)
+    pub phase_speed: f32,   // -2 to 2 phase cycles per second (auto-scroll)
+    pub spin_speed: f32,    // -1 to 1 turns per second added to rotation (auto-spin)
+    pub noise_seed: u32,    // picks this patch's noise field (0 = classic)
+    pub osc_waveform: OscWaveform, // shape of both oscillator sources
+    pub osc_duty: f32,      // 0.05-0.95 high fraction of a Pulse cycle
+    pub shape_size: f32,    // 0.1-2 scale of the circle/rect/diamond shapes
+    pub shape_softness: f32, // 0-1 edge blur of the shapes
 }
 
 impl Default for InputStage {
@@ -87,6 +246,13 @@ impl Default for InputStage {
             frequency: 4.0,
             phase: 0.0,
             rotation: 0.0,
+            phase_speed: 0.0,
+            spin_speed: 0.0,
+            noise_seed: 0,
+            osc_waveform: OscWaveform::Sine,
+            osc_duty: 0.25,
+            shape_size: 1.0,
+            shape_softness: 0.25,
         }
     }
 }
@@ -94,6 +260,7 @@ impl Default for InputStage {
 /// Stage 2: Geometry
 /// Spatial distortions - Wobbulate, Z-displacement, Lissajous
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GeometryStage {
     pub wobbulate_h: f32,   // 0-1 horizontal wobble amount
     pub wobbulate_v: f32,   // 0-1 vertical wobble amount
@@ -102,7 +269,10 @@ pub struct GeometryStage {
     pub lissajous_x: f32,   // 0-1 lissajous X modulation
     pub lissajous_y: f32,   // 0-1 lissajous Y modulation
     pub rotation: f32,      // 0-1 (maps to 0-2pi)
+    pub spin_speed: f32,    // -1 to 1 turns per second added to rotation (auto-spin)
     pub scale: f32,         // 0.5-2.0
+    pub rotate_center_x: f32, // 0-1 pivot for rotation/scale (0.5 = center)
+    pub rotate_center_y: f32, // 0-1
 }
 
 impl Default for GeometryStage {
@@ -115,14 +285,22 @@ impl Default for GeometryStage {
             lissajous_x: 0.0,
             lissajous_y: 0.0,
             rotation: 0.0,
+            spin_speed: 0.0,
             scale: 1.0,
+            rotate_center_x: 0.5,
+            rotate_center_y: 0.5,
         }
     }
 }
 
 /// Stage 3: Amplitude
 /// Waveform shaping - fold, quantize, clip, solarize
+///
+/// Solarize, gate and invert run per output channel: each of R/G/B gets its own
+/// copy of the signal and colorize picks the matching channel, so flipping a
+/// single channel gives false-color results.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AmplitudeStage {
     pub fold_gain: f32,     // 1-8 folding intensity
     pub fold_mix: f32,      // 0-1 dry/wet
@@ -131,7 +309,27 @@ pub struct AmplitudeStage {
     pub soft_clip: f32,     // 0-1 soft clipping amount
     pub solarize: f32,      // 0-1 solarize threshold
     pub gate_threshold: f32, // 0-1 hard gate
-    pub invert: f32,        // 0 or 1
+    pub invert: [bool; 3],  // per-channel (R, G, B) invert
+    pub solarize_channels: [bool; 3], // channels solarize applies to
+    pub dither: f32,        // 0-1 ordered dither before quantize (also threshold colorize)
+    pub normalize: bool,    // stretch the signal's measured range to 0-1 before colorize
+    pub normalize_rate: f32, // 0.1-5 how fast the measured range adapts
+}
+
+/// Pack an output chain order into 2 bits per slot, first effect lowest
+fn effect_order(order: [OutputEffect; 3]) -> u32 {
+    order
+        .iter()
+        .enumerate()
+        .fold(0, |packed, (i, &effect)| packed | (effect as u32) << (2 * i))
+}
+
+/// Pack R/G/B flags into bits 0/1/2
+fn channel_mask(flags: [bool; 3]) -> u32 {
+    flags
+        .iter()
+        .enumerate()
+        .fold(0, |mask, (i, &on)| if on { mask | (1 << i) } else { mask })
 }
 
 impl Default for AmplitudeStage {
@@ -144,14 +342,19 @@ impl Default for AmplitudeStage {
             soft_clip: 0.0,
             solarize: 1.0, // 1.0 = off (threshold above max)
             gate_threshold: 0.0,
-            invert: 0.0,
+            invert: [false; 3],
+            solarize_channels: [true; 3],
+            dither: 0.0,
+            normalize: false,
+            normalize_rate: 1.0,
         }
     }
 }
 
 /// Stage 4: Colorize
 /// Luminance to color mapping
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ColorizeStage {
     pub mode: ColorMode,
     pub hue_offset: f32,    // 0-1 hue rotation
@@ -159,6 +362,19 @@ pub struct ColorizeStage {
     pub levels: f32,        // 2-32 for threshold mode
     pub gradient_start: [f32; 3], // RGB start color
     pub gradient_end: [f32; 3],   // RGB end color
+    pub mono_tint: [f32; 3],      // RGB tint for monochrome mode
+    pub gradient_source: GradientSource, // gradient lookup coordinate
+    pub palette_path: Option<String>,    // .gpl/.hex palette for threshold mode
+    /// Colors read from `palette_path` (empty = procedural bands)
+    #[serde(skip)]
+    pub palette: Vec<[f32; 3]>,
+    /// Built-in lookup palette for palette mode, unless `lut_path` is set
+    pub lut_name: String,
+    /// PNG strip or .gpl/.hex file for palette mode
+    pub lut_path: Option<String>,
+    /// Colors read from `lut_path` (empty = the built-in `lut_name`)
+    #[serde(skip)]
+    pub lut: Vec<[f32; 3]>,
 }
 
 impl Default for ColorizeStage {
@@ -170,20 +386,77 @@ impl Default for ColorizeStage {
             levels: 8.0,
             gradient_start: [0.0, 0.0, 0.0],
             gradient_end: [1.0, 1.0, 1.0],
+            mono_tint: [1.0, 1.0, 1.0],
+            gradient_source: GradientSource::Luminance,
+            palette_path: None,
+            palette: Vec::new(),
+            lut_name: crate::palette::BUILTIN_LUTS[0].0.to_string(),
+            lut_path: None,
+            lut: Vec::new(),
         }
     }
 }
 
+impl ColorizeStage {
+    /// Re-read the palette files after a preset load. A missing or broken
+    /// file falls back to the procedural colors (or the built-in lookup
+    /// palette).
+    pub fn reload_palette(&mut self) {
+        self.palette = match &self.palette_path {
+            Some(path) => crate::palette::load_palette(Path::new(path)).unwrap_or_else(|e| {
+                log::warn!("Can't load palette {path}: {e}");
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        self.lut = match &self.lut_path {
+            Some(path) => crate::palette::load_lut(Path::new(path)).unwrap_or_else(|e| {
+                log::warn!("Can't load lookup palette {path}: {e}");
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+    }
+
+    /// Colors palette mode looks up: the loaded file's, else the built-in
+    pub fn lut_colors(&self) -> Vec<[f32; 3]> {
+        if self.lut.is_empty() { crate::palette::builtin_lut(&self.lut_name) } else { self.lut.clone() }
+    }
+}
+
 /// Stage 5: Mixer
 /// Blend with feedback, keying
+///
+/// The generated layer `color` and the feedback sample `fb` are combined as:
+///
+/// ```text
+/// blended = blend_mode(color, fb)      // what 100% feedback looks like
+/// w_fb    = feedback_mix^2             // how much feedback (power curve)
+/// w_layer = layer_opacity              // how much new signal
+///
+/// normalize = true:   out = ((1 - w_fb) * color + w_fb * blended) * w_layer
+/// normalize = false:  out = w_layer * color + w_fb * blended
+/// ```
+///
+/// With `normalize` on (the default) the feedback and new signal weights always
+/// sum to 1, a plain crossfade, and the opacity scales the result as an output
+/// gain. With it off the opacity is the new signal's own weight, independent of
+/// the feedback amount, so the result can brighten or darken.
+///
+/// With feedback disabled `fb` is black, or the last frame shown before
+/// feedback was turned off when `hold_backdrop` is set, so blend modes and
+/// keying still work standalone.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MixerStage {
     pub feedback_mix: f32,  // 0-1 feedback amount
     pub blend_mode: BlendMode,
     pub key_threshold: f32, // 0-1 for keying modes
     pub key_softness: f32,  // 0-0.5 key edge softness
     pub key_invert: bool,
-    pub layer_opacity: f32, // 0-1 overall layer opacity
+    pub layer_opacity: f32, // 0-1 new signal amount (output gain when normalized)
+    pub normalize: bool,    // crossfade layer and feedback, weights summing to 1
+    pub hold_backdrop: bool, // feedback off: blend against a held frame instead of black
 }
 
 impl Default for MixerStage {
@@ -195,22 +468,38 @@ impl Default for MixerStage {
             key_softness: 0.1,
             key_invert: false,
             layer_opacity: 1.0,
+            normalize: true,
+            hold_backdrop: false,
         }
     }
 }
 
+/// Most feedback frames kept for the echo tap
+pub const MAX_TRAIL_LENGTH: u32 = 8;
+
 /// Stage 6: Feedback
 /// Temporal effects - zoom, rotate, decay
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
 pub struct FeedbackStage {
     pub enabled: bool,
     pub zoom: f32,          // 0.9-1.1 feedback zoom
-    pub rotation: f32,      // 0-1 (maps to +/- pi/8)
+    pub rotation: f32,      // -1 to 1 per frame (1 = 45 degrees)
     pub hue_shift: f32,     // 0-1 hue rotation per frame
-    pub decay: f32,         // 0.8-1.0 brightness decay
+    pub decay: f32,         // 0.8-1.1 brightness decay (above 1 = brightening trails)
     pub offset_x: f32,      // -0.1 to 0.1 horizontal drift
     pub offset_y: f32,      // -0.1 to 0.1 vertical drift
     pub saturation: f32,    // 0-2 saturation adjustment
+    // Second tap: another transform of the previous frame, blended in
+    pub tap_mix: f32,       // 0-1 (0 = single tap)
+    pub tap2_zoom: f32,     // 0.9-1.1
+    pub tap2_rotation: f32, // -1 to 1 per frame (1 = 45 degrees)
+    pub tap2_offset_x: f32, // -0.1 to 0.1
+    pub tap2_offset_y: f32, // -0.1 to 0.1
+    pub wrap: FeedbackWrap, // sampling outside the buffer (both taps)
+    // Echo: the frame `trail_length - 1` steps before the last, blended in
+    pub trail_length: u32,  // 2 to MAX_TRAIL_LENGTH feedback buffers
+    pub echo_mix: f32,      // 0-1 (0 = no echo)
 }
 
 impl Default for FeedbackStage {
@@ -224,24 +513,36 @@ impl Default for FeedbackStage {
             offset_x: 0.0,
             offset_y: 0.0,
             saturation: 1.0,
+            tap_mix: 0.0,
+            tap2_zoom: 0.98,
+            tap2_rotation: 0.0,
+            tap2_offset_x: 0.0,
+            tap2_offset_y: 0.0,
+            wrap: FeedbackWrap::Clamp,
+            trail_length: 2,
+            echo_mix: 0.0,
         }
     }
 }
 
 /// Stage 7: Output
-/// Display emulation - stacked effects: VHS -> Cable -> CRT
-/// Each can be toggled independently, applied in fixed order
+/// Display emulation - stacked effects, VHS -> Cable -> CRT by default
+/// Each can be toggled independently and the chain reordered
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
 pub struct OutputStage {
-    // Effect toggles (applied in order: VHS -> Cable -> CRT)
+    // Effect toggles
     pub vhs_enabled: bool,
     pub cable_enabled: bool,
     pub crt_enabled: bool,
+    /// Chain order (e.g. CRT first = a CRT filmed onto tape)
+    pub order: [OutputEffect; 3],
 
     // CRT effects
     pub scanlines: f32,     // 0-1 scanline intensity
     pub bloom: f32,         // 0-1 phosphor bloom
     pub vignette: f32,      // 0-1 edge darkening
+    pub vignette_color: [f32; 3], // RGB the edges fade toward (black = plain darkening)
 
     // VHS effects
     pub tracking: f32,      // 0-1 tracking error amount
@@ -253,6 +554,13 @@ pub struct OutputStage {
     pub bandwidth: f32,     // 0.5-1.0 bandwidth limiting
     pub ghosting: f32,      // 0-0.3 RF ghosting
     pub cable_noise: f32,   // 0-0.2 signal noise
+    // Composite bandwidth, 1 = full: NTSC smears color (chroma) while
+    // keeping detail (luma)
+    pub luma_bandwidth: f32,   // 0-1
+    pub chroma_bandwidth: f32, // 0-1
+
+    // Shared by VHS and cable noise
+    pub noise_persistence: f32, // 0-1 (0 = fresh snow every frame, higher = slower, tape-like)
 }
 
 impl Default for OutputStage {
@@ -261,9 +569,11 @@ impl Default for OutputStage {
             vhs_enabled: false,
             cable_enabled: false,
             crt_enabled: true,
+            order: OutputEffect::ALL,
             scanlines: 0.15,
             bloom: 0.2,
             vignette: 0.3,
+            vignette_color: [0.0, 0.0, 0.0],
             tracking: 0.3,
             chroma_shift: 0.005,
             tape_wobble: 0.2,
@@ -271,6 +581,9 @@ impl Default for OutputStage {
             bandwidth: 0.9,
             ghosting: 0.1,
             cable_noise: 0.02,
+            luma_bandwidth: 1.0,
+            chroma_bandwidth: 1.0,
+            noise_persistence: 0.0,
         }
     }
 }
@@ -287,6 +600,187 @@ pub struct SynthState {
     pub output: OutputStage,
 }
 
+/// Static description of a continuous (f32) parameter
+#[derive(Debug, Clone, Copy)]
+pub struct ParamSpec {
+    /// "stage.param" key used by automation, nudging and remote control
+    pub key: &'static str,
+    /// Human-readable name
+    pub name: &'static str,
+    pub min: f32,
+    pub max: f32,
+    /// Slider uses a logarithmic scale (wide ranges that need fine low end)
+    pub logarithmic: bool,
+}
+
+impl ParamSpec {
+    /// Look up a parameter by key
+    pub fn find(key: &str) -> Option<&'static ParamSpec> {
+        PARAMS.iter().find(|spec| spec.key == key)
+    }
+
+    pub fn range(&self) -> std::ops::RangeInclusive<f32> {
+        self.min..=self.max
+    }
+
+    /// Value `t` (0-1) of the way along the slider, following its scale
+    pub fn from_unit(&self, t: f32) -> f32 {
+        if self.logarithmic && self.min > 0.0 {
+            self.min * (self.max / self.min).powf(t)
+        } else {
+            self.min + (self.max - self.min) * t
+        }
+    }
+
+    /// Slider position (0-1) of `value`; the inverse of `from_unit`
+    pub fn to_unit(&self, value: f32) -> f32 {
+        let t = if self.logarithmic && self.min > 0.0 {
+            (value / self.min).ln() / (self.max / self.min).ln()
+        } else {
+            (value - self.min) / (self.max - self.min)
+        };
+        t.clamp(0.0, 1.0)
+    }
+}
+
+/// A parameter's spec together with its default and current value
+#[derive(Debug, Clone, Copy)]
+pub struct ParamInfo {
+    pub spec: &'static ParamSpec,
+    pub default: f32,
+    pub value: f32,
+}
+
+/// Declares every continuous parameter once, generating `PARAMS` and the
+/// keyed accessors on `SynthState` so the two can't drift apart
+macro_rules! param_table {
+    (@logarithmic) => { false };
+    (@logarithmic log) => { true };
+    ($($stage:ident.$field:ident => $name:literal, $min:literal..=$max:literal $(, $scale:ident)?;)*) => {
+        /// Every continuous parameter, in panel order
+        pub const PARAMS: &[ParamSpec] = &[
+            $(ParamSpec {
+                key: concat!(stringify!($stage), ".", stringify!($field)),
+                name: $name,
+                min: $min,
+                max: $max,
+                logarithmic: param_table!(@logarithmic $($scale)?),
+            },)*
+        ];
+
+        impl SynthState {
+            /// Current value of a parameter by "stage.param" key
+            pub fn param(&self, key: &str) -> Option<f32> {
+                $(if key == concat!(stringify!($stage), ".", stringify!($field)) {
+                    return Some(self.$stage.$field);
+                })*
+                None
+            }
+
+            /// Parameter by "stage.param" key (e.g. "geometry.wobbulate_h")
+            pub fn param_mut(&mut self, key: &str) -> Option<&mut f32> {
+                $(if key == concat!(stringify!($stage), ".", stringify!($field)) {
+                    return Some(&mut self.$stage.$field);
+                })*
+                None
+            }
+        }
+    };
+}
+
+param_table! {
+    // Input
+    input.mix => "Input Mix A/B", 0.0..=1.0;
+    input.frequency => "Input Frequency", 0.5..=100.0, log;
+    input.phase => "Input Phase", 0.0..=1.0;
+    input.phase_speed => "Input Scroll Speed", -2.0..=2.0;
+    input.rotation => "Input Rotation", 0.0..=1.0;
+    input.spin_speed => "Input Spin Speed", -1.0..=1.0;
+    input.osc_duty => "Oscillator Duty", 0.05..=0.95;
+    input.shape_size => "Shape Size", 0.1..=2.0;
+    input.shape_softness => "Shape Softness", 0.0..=1.0;
+
+    // Geometry
+    geometry.wobbulate_h => "Wobble H", 0.0..=1.0;
+    geometry.wobbulate_v => "Wobble V", 0.0..=1.0;
+    geometry.wobble_freq => "Wobble Frequency", 1.0..=20.0;
+    geometry.z_displacement => "Z Displacement", 0.0..=0.5;
+    geometry.lissajous_x => "Lissajous X", 0.0..=1.0;
+    geometry.lissajous_y => "Lissajous Y", 0.0..=1.0;
+    geometry.rotation => "Geometry Rotation", 0.0..=1.0;
+    geometry.spin_speed => "Geometry Spin Speed", -1.0..=1.0;
+    geometry.scale => "Geometry Scale", 0.5..=2.0;
+    geometry.rotate_center_x => "Pivot X", 0.0..=1.0;
+    geometry.rotate_center_y => "Pivot Y", 0.0..=1.0;
+
+    // Amplitude
+    amplitude.fold_gain => "Fold Gain", 1.0..=8.0;
+    amplitude.fold_mix => "Fold Mix", 0.0..=1.0;
+    amplitude.quantize_levels => "Quantize Levels", 2.0..=32.0;
+    amplitude.quantize_mix => "Quantize Mix", 0.0..=1.0;
+    amplitude.dither => "Dither", 0.0..=1.0;
+    amplitude.soft_clip => "Soft Clip", 0.0..=1.0;
+    amplitude.solarize => "Solarize Threshold", 0.0..=1.0;
+    amplitude.gate_threshold => "Gate Threshold", 0.0..=1.0;
+    amplitude.normalize_rate => "Normalize Rate", 0.1..=5.0;
+
+    // Colorize
+    colorize.hue_offset => "Hue Offset", 0.0..=1.0;
+    colorize.saturation => "Color Saturation", 0.0..=2.0;
+    colorize.levels => "Color Levels", 2.0..=32.0;
+
+    // Mixer
+    mixer.feedback_mix => "Feedback Mix", 0.0..=1.0;
+    mixer.layer_opacity => "Layer Opacity", 0.0..=1.0;
+    mixer.key_threshold => "Key Threshold", 0.0..=1.0;
+    mixer.key_softness => "Key Softness", 0.0..=0.5;
+
+    // Feedback
+    feedback.zoom => "Feedback Zoom", 0.9..=1.1;
+    feedback.rotation => "Feedback Rotation", -1.0..=1.0;
+    feedback.offset_x => "Feedback Offset X", -0.1..=0.1;
+    feedback.offset_y => "Feedback Offset Y", -0.1..=0.1;
+    feedback.tap_mix => "Tap 2 Mix", 0.0..=1.0;
+    feedback.tap2_zoom => "Tap 2 Zoom", 0.9..=1.1;
+    feedback.tap2_rotation => "Tap 2 Rotation", -1.0..=1.0;
+    feedback.tap2_offset_x => "Tap 2 Offset X", -0.1..=0.1;
+    feedback.tap2_offset_y => "Tap 2 Offset Y", -0.1..=0.1;
+    feedback.echo_mix => "Echo Mix", 0.0..=1.0;
+    feedback.hue_shift => "Feedback Hue Shift", 0.0..=0.1;
+    feedback.decay => "Feedback Decay", 0.8..=1.1;
+    feedback.saturation => "Feedback Saturation", 0.0..=2.0;
+
+    // Output
+    output.tracking => "VHS Tracking", 0.0..=1.0;
+    output.chroma_shift => "VHS Chroma Shift", 0.0..=0.02;
+    output.tape_wobble => "VHS Tape Wobble", 0.0..=1.0;
+    output.vhs_noise => "VHS Noise", 0.0..=0.5;
+    output.bandwidth => "Cable Bandwidth", 0.5..=1.0;
+    output.ghosting => "Cable Ghosting", 0.0..=0.3;
+    output.luma_bandwidth => "Luma Bandwidth", 0.0..=1.0;
+    output.chroma_bandwidth => "Chroma Bandwidth", 0.0..=1.0;
+    output.cable_noise => "Cable Noise", 0.0..=0.2;
+    output.noise_persistence => "Noise Persistence", 0.0..=1.0;
+    output.scanlines => "CRT Scanlines", 0.0..=0.5;
+    output.bloom => "CRT Bloom", 0.0..=1.0;
+    output.vignette => "CRT Vignette", 0.0..=1.0;
+}
+
+impl SynthState {
+    /// Every continuous parameter with its default and current value
+    pub fn params(&self) -> Vec<ParamInfo> {
+        let defaults = SynthState::default();
+        PARAMS
+            .iter()
+            .map(|spec| ParamInfo {
+                spec,
+                default: defaults.param(spec.key).unwrap_or(spec.min),
+                value: self.param(spec.key).unwrap_or(spec.min),
+            })
+            .collect()
+    }
+}
+
 impl Default for SynthState {
     fn default() -> Self {
         Self {
@@ -301,7 +795,99 @@ impl Default for SynthState {
     }
 }
 
-/// GPU-friendly packed uniforms (256 bytes)
+/// Per-frame values that live outside the patch (never stored in presets)
+#[derive(Debug, Clone, Copy)]
+pub struct FrameParams {
+    pub time: f32,
+    pub frame: u32,
+    /// Master output level applied after every stage (1 = full, 0 = black)
+    pub master_fade: f32,
+    /// Preview the generated signal only: feedback and output emulation are
+    /// bypassed without touching the patch
+    pub signal_only: bool,
+    /// Momentarily hear one input source, overriding the A/B mix
+    pub input_solo: Option<InputSolo>,
+    /// Measured amplitude signal range (lo, hi), stretched to 0-1 when the
+    /// patch has auto-normalize on
+    pub signal_range: (f32, f32),
+    /// Output the raw amplitude signal (for measuring `signal_range`)
+    pub signal_probe: bool,
+    /// Flash limiter gain on the whole picture (1 = unlimited)
+    pub flash_gain: f32,
+    /// Extra feedback decay after a sudden jump of a feedback control (1 = none)
+    pub feedback_damp: f32,
+    /// Loop mode length in seconds (`time` wraps at it; 0 = free running)
+    pub loop_length: f32,
+    /// Ramped effect enables (None = straight from the patch's switches)
+    pub effect_mix: Option<EffectMix>,
+    /// Synth buffer width / height, so input patterns don't stretch
+    pub aspect: f32,
+}
+
+/// How far feedback and each output effect are faded in (0-1). Toggling a
+/// switch ramps these instead of popping the effect on or off.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EffectMix {
+    pub feedback: f32,
+    pub vhs: f32,
+    pub cable: f32,
+    pub crt: f32,
+}
+
+impl EffectMix {
+    /// Fully on or off, as the patch's switches are set
+    pub fn target(state: &SynthState) -> Self {
+        let amount = |on: bool| if on { 1.0 } else { 0.0 };
+        Self {
+            feedback: amount(state.feedback.enabled),
+            vhs: amount(state.output.vhs_enabled),
+            cable: amount(state.output.cable_enabled),
+            crt: amount(state.output.crt_enabled),
+        }
+    }
+
+    /// Move each amount toward `target` by at most `step`
+    pub fn ramp_toward(&mut self, target: Self, step: f32) {
+        let ramp = |value: &mut f32, target: f32| {
+            *value += (target - *value).clamp(-step, step);
+        };
+        ramp(&mut self.feedback, target.feedback);
+        ramp(&mut self.vhs, target.vhs);
+        ramp(&mut self.cable, target.cable);
+        ramp(&mut self.crt, target.crt);
+    }
+}
+
+/// Input source soloed by holding its Solo button
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSolo {
+    A,
+    B,
+}
+
+impl Default for FrameParams {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            frame: 0,
+            master_fade: 1.0,
+            signal_only: false,
+            input_solo: None,
+            signal_range: (0.0, 1.0),
+            signal_probe: false,
+            flash_gain: 1.0,
+            feedback_damp: 1.0,
+            loop_length: 0.0,
+            effect_mix: None,
+            aspect: 4.0 / 3.0,
+        }
+    }
+}
+
+/// Narrowest signal range auto-normalize stretches to 0-1 (caps gain at 8x)
+pub const MIN_SIGNAL_SPAN: f32 = 0.125;
+
+/// GPU-friendly packed uniforms (672 bytes)
 /// Aligned to 16-byte boundaries for GPU
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
@@ -315,8 +901,14 @@ pub struct SynthUniforms {
     // Input continued (16 bytes)
     pub input_phase: f32,
     pub input_rotation: f32,
-    pub _pad0: f32,
-    pub _pad1: f32,
+    pub input_phase_speed: f32,
+    pub input_noise_seed: u32,
+
+    // Input oscillator and shapes (16 bytes)
+    pub input_osc_waveform: u32,
+    pub input_osc_duty: f32,
+    pub input_shape_size: f32,
+    pub input_shape_softness: f32,
 
     // Geometry stage (32 bytes)
     pub geo_wobbulate_h: f32,
@@ -329,6 +921,12 @@ pub struct SynthUniforms {
     pub geo_rotation: f32,
     pub geo_scale: f32,
 
+    // Geometry continued (16 bytes)
+    pub geo_rotate_center_x: f32,
+    pub geo_rotate_center_y: f32,
+    pub geo_spin_speed: f32,
+    pub input_spin_speed: f32,
+
     // Amplitude stage (32 bytes)
     pub amp_fold_gain: f32,
     pub amp_fold_mix: f32,
@@ -338,7 +936,13 @@ pub struct SynthUniforms {
     pub amp_soft_clip: f32,
     pub amp_solarize: f32,
     pub amp_gate_threshold: f32,
-    pub amp_invert: f32,
+    pub amp_invert_mask: u32,
+
+    // Amplitude continued (16 bytes)
+    pub amp_solarize_mask: u32,
+    pub amp_dither: f32,
+    pub amp_norm_offset: f32,
+    pub amp_norm_scale: f32,
 
     // Colorize stage (32 bytes)
     pub color_mode: u32,
@@ -347,11 +951,15 @@ pub struct SynthUniforms {
     pub color_levels: f32,
 
     pub color_gradient_start: [f32; 3],
-    pub _pad2: f32,
+    pub color_palette_size: u32,
 
     // Colorize gradient end (16 bytes)
     pub color_gradient_end: [f32; 3],
-    pub _pad3: f32,
+    pub _pad0: f32,
+
+    // Colorize continued (16 bytes)
+    pub color_mono_tint: [f32; 3],
+    pub color_gradient_source: u32,
 
     // Mixer stage (16 bytes)
     pub mixer_feedback_mix: f32,
@@ -362,8 +970,8 @@ pub struct SynthUniforms {
     // Mixer continued (16 bytes)
     pub mixer_key_invert: f32,
     pub mixer_layer_opacity: f32,
-    pub _pad4: f32,
-    pub _pad5: f32,
+    pub mixer_normalize: f32,
+    pub mixer_hold: f32,
 
     // Feedback stage (32 bytes)
     pub fb_enabled: f32,
@@ -376,6 +984,17 @@ pub struct SynthUniforms {
     pub fb_offset_y: f32,
     pub fb_saturation: f32,
 
+    // Feedback second tap (32 bytes)
+    pub fb_tap2_zoom: f32,
+    pub fb_tap2_rotation: f32,
+    pub fb_tap2_offset_x: f32,
+    pub fb_tap2_offset_y: f32,
+
+    pub fb_tap_mix: f32,
+    pub fb_wrap: u32,
+    pub out_luma_bandwidth: f32,
+    pub out_chroma_bandwidth: f32,
+
     // Output stage (32 bytes)
     pub out_vhs_enabled: f32,
     pub out_cable_enabled: f32,
@@ -394,29 +1013,63 @@ pub struct SynthUniforms {
     pub out_ghosting: f32,
 
     pub out_cable_noise: f32,
-    pub _pad6a: f32,
-    pub _pad6b: f32,
-    pub _pad6c: f32,
+    pub out_order: u32,
+    pub pixel_scale: f32,
+    pub out_noise_persistence: f32,
+
+    // Output continued (16 bytes)
+    pub out_vignette_color: [f32; 3],
+    pub loop_length: f32,
 
-    // Timing (16 bytes)
+    // Timing + master (16 bytes)
     pub time: f32,
     pub frame: u32,
-    pub _pad7: f32,
-    pub _pad8: f32,
+    pub master_fade: f32,
+    pub signal_probe: f32,
+
+    // Synth buffer + feedback echo (16 bytes)
+    pub aspect: f32,
+    pub fb_echo_mix: f32,
+    pub output_height: f32, // output lines, one scanline each (set by the renderer)
+    pub _pad2: f32,
+
+    // Threshold palette (256 bytes, rgb + pad)
+    pub color_palette: [[f32; 4]; MAX_PALETTE_COLORS],
 }
 
+// Must match `struct Synth` in lite.wgsl
+const _: () = assert!(std::mem::size_of::<SynthUniforms>() == 672);
+
 impl SynthUniforms {
-    pub fn from_state(state: &SynthState, time: f32, frame: u32) -> Self {
+    pub fn from_state(state: &SynthState, params: &FrameParams) -> Self {
+        // Gain is capped so a nearly flat signal isn't blown up into noise
+        let (norm_offset, norm_scale) = if state.amplitude.normalize {
+            let (lo, hi) = params.signal_range;
+            (lo, 1.0 / (hi - lo).max(MIN_SIGNAL_SPAN))
+        } else {
+            (0.0, 1.0)
+        };
+
+        let full_chain = !params.signal_only;
+        let mix = params.effect_mix.unwrap_or_else(|| EffectMix::target(state));
         Self {
             // Input
             input_source_a: state.input.source_a as u32,
             input_source_b: state.input.source_b as u32,
-            input_mix: state.input.mix,
+            input_mix: match params.input_solo {
+                Some(InputSolo::A) => 0.0,
+                Some(InputSolo::B) => 1.0,
+                None => state.input.mix,
+            },
             input_frequency: state.input.frequency,
             input_phase: state.input.phase,
             input_rotation: state.input.rotation,
-            _pad0: 0.0,
-            _pad1: 0.0,
+            input_phase_speed: state.input.phase_speed,
+            input_noise_seed: state.input.noise_seed,
+            input_osc_waveform: state.input.osc_waveform as u32,
+            input_osc_duty: state.input.osc_duty,
+            input_shape_size: state.input.shape_size,
+            input_shape_softness: state.input.shape_softness,
 
             // Geometry
             geo_wobbulate_h: state.geometry.wobbulate_h,
@@ -427,6 +1080,10 @@ impl SynthUniforms {
             geo_lissajous_y: state.geometry.lissajous_y,
             geo_rotation: state.geometry.rotation,
             geo_scale: state.geometry.scale,
+            geo_rotate_center_x: state.geometry.rotate_center_x,
+            geo_rotate_center_y: state.geometry.rotate_center_y,
+            geo_spin_speed: state.geometry.spin_speed,
+            input_spin_speed: state.input.spin_speed,
 
             // Amplitude
             amp_fold_gain: state.amplitude.fold_gain,
@@ -436,7 +1093,11 @@ impl SynthUniforms {
             amp_soft_clip: state.amplitude.soft_clip,
             amp_solarize: state.amplitude.solarize,
             amp_gate_threshold: state.amplitude.gate_threshold,
-            amp_invert: state.amplitude.invert,
+            amp_invert_mask: channel_mask(state.amplitude.invert),
+            amp_solarize_mask: channel_mask(state.amplitude.solarize_channels),
+            amp_dither: state.amplitude.dither,
+            amp_norm_offset: norm_offset,
+            amp_norm_scale: norm_scale,
 
             // Colorize
             color_mode: state.colorize.mode as u32,
@@ -444,9 +1105,11 @@ impl SynthUniforms {
             color_saturation: state.colorize.saturation,
             color_levels: state.colorize.levels,
             color_gradient_start: state.colorize.gradient_start,
-            _pad2: 0.0,
+            color_palette_size: state.colorize.palette.len().min(MAX_PALETTE_COLORS) as u32,
             color_gradient_end: state.colorize.gradient_end,
-            _pad3: 0.0,
+            _pad0: 0.0,
+            color_mono_tint: state.colorize.mono_tint,
+            color_gradient_source: state.colorize.gradient_source as u32,
 
             // Mixer
             mixer_feedback_mix: state.mixer.feedback_mix,
@@ -455,23 +1118,31 @@ impl SynthUniforms {
             mixer_key_softness: state.mixer.key_softness,
             mixer_key_invert: if state.mixer.key_invert { 1.0 } else { 0.0 },
             mixer_layer_opacity: state.mixer.layer_opacity,
-            _pad4: 0.0,
-            _pad5: 0.0,
+            mixer_normalize: if state.mixer.normalize { 1.0 } else { 0.0 },
+            mixer_hold: if state.mixer.hold_backdrop && full_chain { 1.0 } else { 0.0 },
 
             // Feedback
-            fb_enabled: if state.feedback.enabled { 1.0 } else { 0.0 },
+            fb_enabled: if full_chain { mix.feedback } else { 0.0 },
             fb_zoom: state.feedback.zoom,
             fb_rotation: state.feedback.rotation,
             fb_hue_shift: state.feedback.hue_shift,
-            fb_decay: state.feedback.decay,
+            fb_decay: state.feedback.decay * params.feedback_damp,
             fb_offset_x: state.feedback.offset_x,
             fb_offset_y: state.feedback.offset_y,
             fb_saturation: state.feedback.saturation,
+            fb_tap2_zoom: state.feedback.tap2_zoom,
+            fb_tap2_rotation: state.feedback.tap2_rotation,
+            fb_tap2_offset_x: state.feedback.tap2_offset_x,
+            fb_tap2_offset_y: state.feedback.tap2_offset_y,
+            fb_tap_mix: state.feedback.tap_mix,
+            fb_wrap: state.feedback.wrap as u32,
+            out_luma_bandwidth: state.output.luma_bandwidth,
+            out_chroma_bandwidth: state.output.chroma_bandwidth,
 
             // Output
-            out_vhs_enabled: if state.output.vhs_enabled { 1.0 } else { 0.0 },
-            out_cable_enabled: if state.output.cable_enabled { 1.0 } else { 0.0 },
-            out_crt_enabled: if state.output.crt_enabled { 1.0 } else { 0.0 },
+            out_vhs_enabled: if full_chain { mix.vhs } else { 0.0 },
+            out_cable_enabled: if full_chain { mix.cable } else { 0.0 },
+            out_crt_enabled: if full_chain { mix.crt } else { 0.0 },
             out_scanlines: state.output.scanlines,
             out_bloom: state.output.bloom,
             out_vignette: state.output.vignette,
@@ -482,15 +1153,30 @@ impl SynthUniforms {
             out_bandwidth: state.output.bandwidth,
             out_ghosting: state.output.ghosting,
             out_cable_noise: state.output.cable_noise,
-            _pad6a: 0.0,
-            _pad6b: 0.0,
-            _pad6c: 0.0,
-
-            // Timing
-            time,
-            frame,
-            _pad7: 0.0,
-            _pad8: 0.0,
+            out_order: effect_order(state.output.order),
+            // Render target pixels per output pixel; the renderer sets this
+            // when supersampling
+            pixel_scale: 1.0,
+            out_noise_persistence: state.output.noise_persistence,
+            out_vignette_color: state.output.vignette_color,
+            loop_length: params.loop_length,
+
+            // Timing + master
+            time: params.time,
+            frame: params.frame,
+            master_fade: (params.master_fade * params.flash_gain).clamp(0.0, 1.0),
+            signal_probe: if params.signal_probe { 1.0 } else { 0.0 },
+
+            // Synth buffer + feedback echo
+            aspect: params.aspect,
+            fb_echo_mix: state.feedback.echo_mix,
+            output_height: 480.0,
+            _pad2: 0.0,
+
+            color_palette: std::array::from_fn(|i| {
+                let [r, g, b] = state.colorize.palette.get(i).copied().unwrap_or_default();
+                [r, g, b, 0.0]
+            }),
         }
     }
 }
@@ -0,0 +1,376 @@
+//! Texture sharing output for other apps, e.g. Resolume or OBS
+//!
+//! On Windows with the `spout` feature, each frame is published as a Spout
+//! sender: a shared Direct3D 11 texture, registered in Spout's sender list
+//! under the server name. Other builds can't share, and `open` says why.
+//! Syphon (macOS) is out of scope for now: it needs an Objective-C/Metal
+//! bridge this crate doesn't have.
+//!
+//! There's no Spout crate, so the sender speaks the protocol of the Spout2
+//! SDK 2.007 (github.com/leadedge/Spout2, SPOUTSDK/SpoutGL) directly:
+//!
+//! - `SpoutSenderNames`: shared memory of `MAX_SENDERS` 256-byte,
+//!   NUL-terminated name slots, guarded by the `SpoutSenderNames_mutex`
+//!   mutex (SpoutSenderNames.cpp, SpoutSharedMemory.cpp). 64 is the SDK's
+//!   default `MaxSenders`; a user who raised it in the Spout settings has a
+//!   larger map, of which only the first 64 slots are used here.
+//! - `ActiveSenderName`: one 256-byte slot naming the sender receivers pick
+//!   when none is chosen
+//! - `<sender name>`: shared memory holding the sender's `SharedTextureInfo`
+//!   (SpoutSenderNames.h): the 32-bit legacy share handle, width, height,
+//!   DXGI format, usage, a 128 wchar description and a partner id, 280 bytes
+//! - `<sender name>_SpoutAccessMutex`: held while the texture is written or
+//!   read (spoutDirectX.cpp, `CreateAccessMutex`)
+//!
+//! The texture is a legacy shared (`D3D11_RESOURCE_MISC_SHARED`)
+//! `DXGI_FORMAT_R8G8B8A8_UNORM` texture. None of this is tested here; check
+//! a change against SpoutReceiver or Resolume on Windows.
+
+/// Whether this build can share its output
+pub const AVAILABLE: bool = cfg!(all(windows, feature = "spout"));
+
+/// Server name offered by default
+pub const DEFAULT_NAME: &str = "Phosphlux Lite";
+
+pub use sender::TextureSender;
+
+#[cfg(all(windows, feature = "spout"))]
+mod sender {
+    use windows::core::{Interface, HSTRING};
+    use windows::Win32::Foundation::{CloseHandle, HANDLE, HMODULE, INVALID_HANDLE_VALUE, WAIT_ABANDONED, WAIT_OBJECT_0};
+    use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_HARDWARE;
+    use windows::Win32::Graphics::Direct3D11::{
+        D3D11CreateDevice, ID3D11Device, ID3D11DeviceContext, ID3D11Texture2D, D3D11_BIND_RENDER_TARGET,
+        D3D11_BIND_SHADER_RESOURCE, D3D11_CREATE_DEVICE_BGRA_SUPPORT, D3D11_RESOURCE_MISC_SHARED, D3D11_SDK_VERSION,
+        D3D11_TEXTURE2D_DESC, D3D11_USAGE_DEFAULT,
+    };
+    use windows::Win32::Graphics::Dxgi::Common::{DXGI_FORMAT_R8G8B8A8_UNORM, DXGI_SAMPLE_DESC};
+    use windows::Win32::Graphics::Dxgi::IDXGIResource;
+    use windows::Win32::System::Memory::{
+        CreateFileMappingW, MapViewOfFile, UnmapViewOfFile, FILE_MAP_ALL_ACCESS, MEMORY_MAPPED_VIEW_ADDRESS, PAGE_READWRITE,
+    };
+    use windows::Win32::System::Threading::{CreateMutexW, ReleaseMutex, WaitForSingleObject};
+
+    /// Spout's list of sender names, and the sender receivers pick by default
+    const NAMES_MAP: &str = "SpoutSenderNames";
+    const ACTIVE_MAP: &str = "ActiveSenderName";
+
+    /// Bytes per name slot, and slots in the list
+    const NAME_LEN: usize = 256;
+    const MAX_SENDERS: usize = 64;
+
+    /// Longest wait for a receiver to let go of the texture before the
+    /// frame is skipped
+    const LOCK_TIMEOUT_MS: u32 = 67;
+
+    /// Sender details receivers read from the shared memory named after it
+    /// (Spout2's `SharedTextureInfo`, 280 bytes)
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct SharedTextureInfo {
+        share_handle: u32,
+        width: u32,
+        height: u32,
+        /// DXGI format of the texture
+        format: u32,
+        usage: u32,
+        description: [u16; 128],
+        partner_id: u32,
+    }
+
+    const _: () = assert!(std::mem::size_of::<SharedTextureInfo>() == 280);
+
+    /// Publishes frames as a Spout sender; dropping it removes the sender
+    pub struct TextureSender {
+        /// Server name, as requested
+        pub name: String,
+        /// Name registered with Spout (trimmed)
+        registered_name: String,
+        device: ID3D11Device,
+        context: ID3D11DeviceContext,
+        /// Shared texture at the current frame size (None before the first frame)
+        texture: Option<(ID3D11Texture2D, u32, u32)>,
+        info: SharedMemory,
+        names: SharedMemory,
+        names_lock: NamedMutex,
+        access_lock: NamedMutex,
+        registered: bool,
+    }
+
+    impl TextureSender {
+        /// Create a sender called `name`; fails if the name is taken or
+        /// there's no Direct3D 11 device
+        pub fn open(name: &str) -> Result<Self, String> {
+            let registered_name = name.trim().to_string();
+            if registered_name.is_empty() || registered_name.len() >= NAME_LEN {
+                return Err(format!("the server name must be 1-{} characters", NAME_LEN - 1));
+            }
+
+            let (device, context) = create_device()?;
+            let mut names = SharedMemory::open(NAMES_MAP, NAME_LEN * MAX_SENDERS)?;
+            let names_lock = NamedMutex::open(&format!("{NAMES_MAP}_mutex"))?;
+            let taken = names_lock
+                .with(|| read_names(names.bytes()).contains(&registered_name))
+                .ok_or("the Spout sender list is busy")?;
+            if taken {
+                return Err(format!("a sender called \"{registered_name}\" already exists"));
+            }
+
+            let info = SharedMemory::open(&registered_name, std::mem::size_of::<SharedTextureInfo>())?;
+            let access_lock = NamedMutex::open(&format!("{registered_name}_SpoutAccessMutex"))?;
+            log::info!("Sharing output as Spout sender \"{registered_name}\"");
+
+            Ok(Self {
+                name: name.to_string(),
+                registered_name,
+                device,
+                context,
+                texture: None,
+                info,
+                names,
+                names_lock,
+                access_lock,
+                registered: false,
+            })
+        }
+
+        /// Publish one RGBA8 frame (sRGB, rows top to bottom, each
+        /// `bytes_per_row` apart)
+        pub fn send(&mut self, width: u32, height: u32, bytes_per_row: u32, rgba: &[u8]) {
+            if let Err(e) = self.try_send(width, height, bytes_per_row, rgba) {
+                log::warn!("Spout sender \"{}\": {e}", self.registered_name);
+            }
+        }
+
+        fn try_send(&mut self, width: u32, height: u32, bytes_per_row: u32, rgba: &[u8]) -> Result<(), String> {
+            let needed = bytes_per_row as usize * (height as usize).saturating_sub(1) + width as usize * 4;
+            if width == 0 || height == 0 || bytes_per_row < width * 4 || rgba.len() < needed {
+                return Err("frame is empty or truncated".into());
+            }
+
+            // A new size needs a new shared texture, which receivers find
+            // through the handle in the sender details
+            if !matches!(self.texture, Some((_, w, h)) if (w, h) == (width, height)) {
+                let texture = create_shared_texture(&self.device, width, height)?;
+                let handle = unsafe { texture.cast::<IDXGIResource>().and_then(|resource| resource.GetSharedHandle()) }
+                    .map_err(|e| e.to_string())?;
+                let info = SharedTextureInfo {
+                    // Legacy shared handles fit in 32 bits, which is all Spout stores
+                    share_handle: handle.0 as usize as u32,
+                    width,
+                    height,
+                    format: DXGI_FORMAT_R8G8B8A8_UNORM.0 as u32,
+                    usage: 0,
+                    description: [0; 128],
+                    partner_id: 0,
+                };
+                unsafe { std::ptr::write_unaligned(self.info.bytes().as_mut_ptr().cast(), info) };
+                self.texture = Some((texture, width, height));
+                if !self.registered {
+                    self.register(true)?;
+                    self.registered = true;
+                }
+            }
+
+            let Some((texture, _, _)) = &self.texture else {
+                return Ok(());
+            };
+            // Skip the frame rather than stall while a receiver is reading
+            self.access_lock
+                .with(|| unsafe {
+                    self.context.UpdateSubresource(texture, 0, None, rgba.as_ptr().cast(), bytes_per_row, 0);
+                    self.context.Flush();
+                })
+                .ok_or("texture busy, frame skipped")?;
+            Ok(())
+        }
+
+        /// Add the sender to Spout's list (and make it the active sender if
+        /// there isn't one), or take it off again
+        fn register(&mut self, add: bool) -> Result<(), String> {
+            let name = self.registered_name.clone();
+            let names = &mut self.names;
+            let listed = self
+                .names_lock
+                .with(|| {
+                    let mut list = read_names(names.bytes());
+                    list.retain(|listed| *listed != name);
+                    if add {
+                        list.push(name.clone());
+                    }
+                    list.sort();
+                    write_names(names.bytes(), &list)
+                })
+                .ok_or("the Spout sender list is busy")?;
+            if !listed {
+                return Err(format!("Spout's sender list is full ({MAX_SENDERS} senders)"));
+            }
+
+            let mut active = SharedMemory::open(ACTIVE_MAP, NAME_LEN)?;
+            let current = read_name(active.bytes());
+            if add && current.is_empty() {
+                write_name(active.bytes(), &name);
+            } else if !add && current == name {
+                active.bytes().fill(0);
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for TextureSender {
+        fn drop(&mut self) {
+            if self.registered {
+                let _ = self.register(false);
+            }
+        }
+    }
+
+    fn create_device() -> Result<(ID3D11Device, ID3D11DeviceContext), String> {
+        let mut device = None;
+        let mut context = None;
+        unsafe {
+            D3D11CreateDevice(
+                None,
+                D3D_DRIVER_TYPE_HARDWARE,
+                HMODULE::default(),
+                D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                None,
+                D3D11_SDK_VERSION,
+                Some(&mut device),
+                None,
+                Some(&mut context),
+            )
+        }
+        .map_err(|e| format!("no Direct3D 11 device ({e})"))?;
+        device.zip(context).ok_or_else(|| "no Direct3D 11 device".to_string())
+    }
+
+    fn create_shared_texture(device: &ID3D11Device, width: u32, height: u32) -> Result<ID3D11Texture2D, String> {
+        let desc = D3D11_TEXTURE2D_DESC {
+            Width: width,
+            Height: height,
+            MipLevels: 1,
+            ArraySize: 1,
+            Format: DXGI_FORMAT_R8G8B8A8_UNORM,
+            SampleDesc: DXGI_SAMPLE_DESC { Count: 1, Quality: 0 },
+            Usage: D3D11_USAGE_DEFAULT,
+            BindFlags: (D3D11_BIND_SHADER_RESOURCE.0 | D3D11_BIND_RENDER_TARGET.0) as u32,
+            CPUAccessFlags: 0,
+            MiscFlags: D3D11_RESOURCE_MISC_SHARED.0 as u32,
+        };
+        let mut texture = None;
+        unsafe { device.CreateTexture2D(&desc, None, Some(&mut texture)) }.map_err(|e| e.to_string())?;
+        texture.ok_or_else(|| "can't create the shared texture".to_string())
+    }
+
+    /// Names in a run of NUL-padded slots, up to the first empty one
+    fn read_names(slots: &[u8]) -> Vec<String> {
+        slots.chunks(NAME_LEN).map(read_name).take_while(|name| !name.is_empty()).collect()
+    }
+
+    /// Rewrite the slots with `names`; false if they don't all fit
+    fn write_names(slots: &mut [u8], names: &[String]) -> bool {
+        slots.fill(0);
+        for (slot, name) in slots.chunks_mut(NAME_LEN).zip(names) {
+            write_name(slot, name);
+        }
+        names.len() <= slots.len() / NAME_LEN
+    }
+
+    fn read_name(slot: &[u8]) -> String {
+        let slot = &slot[..slot.len().min(NAME_LEN)];
+        let end = slot.iter().position(|&b| b == 0).unwrap_or(slot.len());
+        String::from_utf8_lossy(&slot[..end]).into_owned()
+    }
+
+    fn write_name(slot: &mut [u8], name: &str) {
+        // Leave room for the terminating NUL
+        let len = name.len().min(slot.len().min(NAME_LEN) - 1);
+        slot[..len].copy_from_slice(&name.as_bytes()[..len]);
+        slot[len] = 0;
+    }
+
+    /// Named, pagefile-backed shared memory (opened if it already exists)
+    struct SharedMemory {
+        handle: HANDLE,
+        view: MEMORY_MAPPED_VIEW_ADDRESS,
+        size: usize,
+    }
+
+    impl SharedMemory {
+        fn open(name: &str, size: usize) -> Result<Self, String> {
+            unsafe {
+                let handle =
+                    CreateFileMappingW(INVALID_HANDLE_VALUE, None, PAGE_READWRITE, 0, size as u32, &HSTRING::from(name))
+                        .map_err(|e| format!("can't open shared memory {name} ({e})"))?;
+                let view = MapViewOfFile(handle, FILE_MAP_ALL_ACCESS, 0, 0, size);
+                if view.Value.is_null() {
+                    let _ = CloseHandle(handle);
+                    return Err(format!("can't map shared memory {name}"));
+                }
+                Ok(Self { handle, view, size })
+            }
+        }
+
+        fn bytes(&mut self) -> &mut [u8] {
+            unsafe { std::slice::from_raw_parts_mut(self.view.Value.cast(), self.size) }
+        }
+    }
+
+    impl Drop for SharedMemory {
+        fn drop(&mut self) {
+            unsafe {
+                let _ = UnmapViewOfFile(self.view);
+                let _ = CloseHandle(self.handle);
+            }
+        }
+    }
+
+    /// Named mutex shared with other Spout apps
+    struct NamedMutex(HANDLE);
+
+    impl NamedMutex {
+        fn open(name: &str) -> Result<Self, String> {
+            unsafe { CreateMutexW(None, false, &HSTRING::from(name)) }
+                .map(Self)
+                .map_err(|e| format!("can't open mutex {name} ({e})"))
+        }
+
+        /// Run `f` holding the mutex; None if it couldn't be taken in time
+        fn with<R>(&self, f: impl FnOnce() -> R) -> Option<R> {
+            unsafe {
+                let wait = WaitForSingleObject(self.0, LOCK_TIMEOUT_MS);
+                if wait != WAIT_OBJECT_0 && wait != WAIT_ABANDONED {
+                    return None;
+                }
+                let result = f();
+                let _ = ReleaseMutex(self.0);
+                Some(result)
+            }
+        }
+    }
+
+    impl Drop for NamedMutex {
+        fn drop(&mut self) {
+            unsafe {
+                let _ = CloseHandle(self.0);
+            }
+        }
+    }
+}
+
+/// Built without Spout (or not on Windows): never sharing
+#[cfg(not(all(windows, feature = "spout")))]
+mod sender {
+    pub struct TextureSender {
+        pub name: String,
+    }
+
+    impl TextureSender {
+        pub fn open(_name: &str) -> Result<Self, String> {
+            Err("texture sharing needs Windows and the spout feature".into())
+        }
+
+        pub fn send(&mut self, _width: u32, _height: u32, _bytes_per_row: u32, _rgba: &[u8]) {}
+    }
+}
@@ -1,13 +1,39 @@
 //! User interface using egui
 
-use crate::app::{App, SelectedStage};
-use crate::automation::AutomationState;
-use crate::synth::*;
 use egui::{Color32, RichText, Ui};
+use phosphlux_lite::app::{App, SelectedStage, GIF_MAX_SECS};
+use phosphlux_lite::audio::AudioBand;
+use phosphlux_lite::presets::{PRESET_EXTENSION, SESSION_EXTENSION};
+use phosphlux_lite::automation::{AutomationState, GestureMode, LfoShape, LfoState, ModRoute, MAX_MOD_ROUTES};
+use phosphlux_lite::synth::*;
+use phosphlux_lite::texture_share;
+
+/// Ask where to save the session, then save it
+pub fn save_session_dialog(app: &mut App) {
+    if let Some(path) = rfd::FileDialog::new()
+        .add_filter("Phosphlux session", &[SESSION_EXTENSION])
+        .set_file_name(format!("session.{SESSION_EXTENSION}"))
+        .save_file()
+    {
+        app.save_session(&path);
+    }
+}
+
+/// Ask for a saved session, then restore it
+pub fn open_session_dialog(app: &mut App) {
+    if let Some(path) = rfd::FileDialog::new()
+        .add_filter("Phosphlux session", &[SESSION_EXTENSION])
+        .pick_file()
+    {
+        app.load_session(&path);
+    }
+}
 
 /// Draw the complete UI
 pub fn draw_ui(ctx: &egui::Context, app: &mut App) {
-    // Top panel with title, presets, and BPM
+    crate::keys::handle_keys(ctx, app);
+
+    // Top panel with title, presets, and view toggles
     egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
         ui.horizontal(|ui| {
             ui.heading("PHOSPHLUX LITE");
@@ -39,42 +65,209 @@ pub fn draw_ui(ctx: &egui::Context, app: &mut App) {
                 app.load_preset(idx);
             }
 
+            // Single preset files to trade, anywhere on disk
+            let file_menu = ui.menu_button("File", |ui| {
+                if ui
+                    .button("Export Preset…")
+                    .on_hover_text("Save the patch with its tempo, LFOs and gestures as a .phxl file to share")
+                    .clicked()
+                {
+                    ui.close_menu();
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("Phosphlux preset", &[PRESET_EXTENSION])
+                        .set_file_name(format!("{}.{PRESET_EXTENSION}", if current.is_some() { &preset_name } else { "patch" }))
+                        .save_file()
+                    {
+                        app.export_preset(&path);
+                    }
+                }
+                if ui.button("Import Preset…").clicked() {
+                    ui.close_menu();
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("Phosphlux preset", &[PRESET_EXTENSION, "json"])
+                        .pick_file()
+                    {
+                        app.import_preset(&path);
+                    }
+                }
+                ui.separator();
+                if ui
+                    .button("Save Session…")
+                    .on_hover_text("Save everything: patch, LFOs, tempo, bezel and loaded files (Ctrl+S)")
+                    .clicked()
+                {
+                    ui.close_menu();
+                    save_session_dialog(app);
+                }
+                if ui.button("Open Session…").on_hover_text("Ctrl+O").clicked() {
+                    ui.close_menu();
+                    open_session_dialog(app);
+                }
+            });
+            let mut menu_open = file_menu.inner.is_some();
+
+            // Morph: glide to a preset instead of cutting to it
+            let morph_menu = ui.menu_button("Morph", |ui| {
+                for (i, name) in preset_names.iter().enumerate() {
+                    if ui.button(name).clicked() {
+                        app.morph_to(i, app.morph_secs);
+                        ui.close_menu();
+                    }
+                }
+            });
+            menu_open |= morph_menu.inner.is_some();
+            morph_menu
+                .response
+                .on_hover_text("Glide every parameter to a preset; touching a control stops the morph");
+            ui.add(
+                egui::DragValue::new(&mut app.morph_secs)
+                    .speed(0.5)
+                    .range(0.5..=600.0)
+                    .suffix(" s"),
+            );
+            if let Some(morph) = &app.morph {
+                ui.label(
+                    RichText::new(format!("{:.0}%", morph.progress() * 100.0))
+                        .monospace()
+                        .color(Color32::GRAY),
+                );
+            }
+
             if ui.button("Randomize").clicked() {
                 app.randomize();
             }
+            let mut seed = app.random_seed;
+            if ui
+                .add(egui::DragValue::new(&mut seed).speed(1.0).prefix("seed "))
+                .on_hover_text("Seed of the last random patch; type a seed to recall its patch")
+                .changed()
+            {
+                app.randomize_with_seed(seed);
+            }
 
-            ui.separator();
+            let automated = !app.automation.sources.is_empty() || !app.automation.steppers.is_empty();
+            if ui
+                .add_enabled(automated, egui::Button::new("Bake LFOs"))
+                .on_hover_text("Keep every LFO, stepper and audio bind's current value and remove them, freezing the patch as it is now")
+                .clicked()
+            {
+                app.bake_automation();
+            }
 
-            // Global BPM control
-            ui.label("BPM:");
-            let bpm_presets = [60.0, 90.0, 120.0, 140.0];
-            for bpm in bpm_presets {
-                let selected = (app.automation.global_bpm - bpm).abs() < 1.0;
-                if ui
-                    .selectable_label(selected, format!("{}", bpm as u32))
-                    .clicked()
-                {
-                    app.automation.global_bpm = bpm;
+            if ui
+                .button("Screenshot")
+                .on_hover_text("Save the current frame as a PNG next to the executable")
+                .clicked()
+            {
+                app.screenshot_requested = true;
+            }
+
+            if ui
+                .button("Export Shader")
+                .on_hover_text("Save the patch as a standalone WGSL shader with its values baked in")
+                .clicked()
+            {
+                app.export_shader();
+            }
+
+            // Offline export: fixed time steps, so the result doesn't depend
+            // on how fast frames render
+            match &app.sequence_export {
+                Some(export) => {
+                    ui.label(
+                        RichText::new(format!("Exporting {}/{}", export.frame, export.frames))
+                            .monospace()
+                            .color(Color32::from_rgb(255, 200, 80)),
+                    );
+                    if ui.button("Cancel").clicked() {
+                        app.sequence_export = None;
+                    }
+                }
+                None => {
+                    let export_menu = ui.menu_button("Export Sequence…", |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Duration:");
+                            ui.add(
+                                egui::DragValue::new(&mut app.sequence_secs)
+                                    .speed(0.5)
+                                    .range(0.1..=600.0)
+                                    .suffix(" s"),
+                            );
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("FPS:");
+                            ui.add(egui::DragValue::new(&mut app.sequence_fps).speed(1.0).range(1.0..=120.0));
+                        });
+                        let frames = (app.sequence_secs * app.sequence_fps).round().max(1.0) as u32;
+                        ui.label(
+                            RichText::new(format!("{frames} numbered PNGs, from a cleared clock and feedback"))
+                                .small()
+                                .color(Color32::GRAY),
+                        );
+                        if ui.button("Export").clicked() {
+                            app.start_sequence_export();
+                            ui.close_menu();
+                        }
+                    });
+                    menu_open |= export_menu.inner.is_some();
                 }
             }
 
-            // Custom BPM slider (compact)
-            ui.add(
-                egui::DragValue::new(&mut app.automation.global_bpm)
-                    .speed(1.0)
-                    .range(30.0..=240.0)
-                    .suffix(" BPM"),
-            );
+            // Escape should only close the menu, so shortcuts check this next frame
+            ctx.data_mut(|d| d.insert_temp(crate::keys::menu_open_id(), menu_open));
+
+            // GIF recording: frames held in memory, saved when stopped
+            match &app.gif_recording {
+                Some(recording) => {
+                    ui.label(
+                        RichText::new(format!("● GIF {:.1}s", recording.elapsed))
+                            .monospace()
+                            .color(Color32::from_rgb(255, 80, 80)),
+                    );
+                    if ui.button("Stop").clicked() {
+                        app.toggle_gif_recording();
+                    }
+                }
+                None => {
+                    if ui
+                        .button("Record GIF")
+                        .on_hover_text(format!("Record up to {GIF_MAX_SECS:.0} s as a looping GIF"))
+                        .clicked()
+                    {
+                        app.toggle_gif_recording();
+                    }
+                    ui.add(egui::DragValue::new(&mut app.gif_fps).speed(1.0).range(1.0..=50.0).suffix(" fps"));
+                }
+            }
 
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 // Settings button on the right
                 if ui.button("⚙").clicked() {
                     app.show_settings = !app.show_settings;
                 }
+                ui.toggle_value(&mut app.show_key_help, "?")
+                    .on_hover_text("Keyboard shortcuts (?)");
+                ui.toggle_value(&mut app.show_mod_matrix, "Mod Matrix");
+                let learn = ui
+                    .add_enabled(app.midi.is_some(), egui::SelectableLabel::new(app.midi_learn, "MIDI Learn"))
+                    .on_hover_text("Click a slider, then move a knob or fader to map it")
+                    .on_disabled_hover_text("No MIDI input");
+                if learn.clicked() {
+                    app.toggle_midi_learn();
+                }
+                ui.toggle_value(&mut app.show_histogram, "Levels");
+                ui.toggle_value(&mut app.scope.enabled, "Scope");
+                ui.toggle_value(&mut app.signal_only, "Signal Only")
+                    .on_hover_text("Preview without feedback and output effects (the patch is unchanged)");
             });
         });
     });
 
+    // Master panel: the live essentials, always in reach
+    egui::TopBottomPanel::top("master_panel").show(ctx, |ui| {
+        draw_master_panel(ui, app);
+    });
+
     // Right side panel with stage tabs and controls
     egui::SidePanel::right("stage_panel")
         .min_width(320.0)
@@ -105,12 +298,39 @@ pub fn draw_ui(ctx: &egui::Context, app: &mut App) {
                 }
             });
 
+            ui.horizontal(|ui| {
+                if ui.small_button("Copy").on_hover_text("Copy this stage's settings").clicked() {
+                    app.copy_stage();
+                }
+                let paste = app.stage_clipboard.as_ref().filter(|clip| clip.fits(app.selected_stage));
+                let hover = match paste {
+                    Some(clip) => format!("Replace this stage's settings with the copied {}", clip.name()),
+                    None => "Copy a stage of this kind first".to_string(),
+                };
+                let button = ui.add_enabled(paste.is_some(), egui::Button::new("Paste").small());
+                if button.on_hover_text(&hover).on_disabled_hover_text(&hover).clicked() {
+                    app.paste_stage();
+                }
+            });
+
             ui.separator();
 
+            // Sliders highlight and update the nudge target through egui's temp data
+            ctx.data_mut(|d| d.insert_temp(focused_param_id(), app.focused_param.clone()));
+
             // Stage-specific controls in a scroll area
             egui::ScrollArea::vertical().show(ui, |ui| {
                 let modified = match app.selected_stage {
-                    SelectedStage::Input => draw_input_stage(ui, &mut app.synth.input, &mut app.automation),
+                    SelectedStage::Input => {
+                        draw_input_stage(
+                            ui,
+                            &mut app.synth.input,
+                            &mut app.input_solo,
+                            &mut app.input_image,
+                            (&mut app.camera_device, app.camera_count),
+                            &mut app.automation,
+                        )
+                    }
                     SelectedStage::Geometry => draw_geometry_stage(ui, &mut app.synth.geometry, &mut app.automation),
                     SelectedStage::Amplitude => draw_amplitude_stage(ui, &mut app.synth.amplitude, &mut app.automation),
                     SelectedStage::Colorize => draw_colorize_stage(ui, &mut app.synth.colorize, &mut app.automation),
@@ -123,8 +343,147 @@ pub fn draw_ui(ctx: &egui::Context, app: &mut App) {
                     app.mark_modified();
                 }
             });
+
+            app.focused_param = ctx.data(|d| d.get_temp(focused_param_id())).flatten();
         });
 
+    // Scanline scope (floating)
+    if app.scope.enabled {
+        egui::Window::new("Scope")
+            .open(&mut app.scope.enabled)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let max_row = app.output_size[1].saturating_sub(1);
+                ui.add(egui::Slider::new(&mut app.scope.row, 0..=max_row).text("Scanline"));
+
+                egui_plot::Plot::new("scope_plot")
+                    .height(160.0)
+                    .include_x(0.0)
+                    .include_x(app.output_size[0] as f64)
+                    .include_y(0.0)
+                    .include_y(1.0)
+                    .allow_drag(false)
+                    .allow_zoom(false)
+                    .allow_scroll(false)
+                    .show(ui, |plot_ui| {
+                        plot_ui.line(
+                            egui_plot::Line::new(egui_plot::PlotPoints::from_ys_f32(&app.scope_samples))
+                                .color(Color32::from_rgb(0, 255, 120)),
+                        );
+                    });
+            });
+    }
+
+    // Frame rate and GPU time (floating)
+    if app.perf.enabled {
+        egui::Window::new("Performance")
+            .open(&mut app.perf.enabled)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-8.0, -8.0])
+            .show(ctx, |ui| {
+                let fps = app.perf.fps();
+                let ms = app.perf.frame_secs * 1000.0;
+                ui.label(RichText::new(format!("{fps:5.1} fps  {ms:5.2} ms")).monospace());
+                let average = app.perf.average_fps();
+                ui.label(RichText::new(format!("{average:5.1} fps average")).monospace());
+                let gpu = match app.perf.gpu_ms {
+                    Some(ms) => format!("GPU {ms:.2} ms"),
+                    None if app.perf.gpu_supported => "GPU …".to_string(),
+                    None => "GPU timing not supported".to_string(),
+                };
+                ui.label(RichText::new(gpu).monospace());
+            });
+    }
+
+    // Keyboard shortcuts (floating), listed from the binding table
+    if app.show_key_help {
+        egui::Window::new("Keyboard Shortcuts")
+            .open(&mut app.show_key_help)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("key_help").num_columns(2).spacing([16.0, 4.0]).show(ui, |ui| {
+                    for binding in crate::keys::BINDINGS {
+                        ui.label(RichText::new(crate::keys::keys_label(binding)).monospace().strong());
+                        ui.label(binding.help);
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+
+    // Mod matrix (floating)
+    if app.show_mod_matrix {
+        egui::Window::new("Mod Matrix")
+            .open(&mut app.show_mod_matrix)
+            .default_width(420.0)
+            .show(ctx, |ui| draw_mod_matrix(ui, &mut app.mod_routes));
+    }
+
+    // Output histogram (floating)
+    if app.show_histogram {
+        egui::Window::new("Levels")
+            .open(&mut app.show_histogram)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let hist = &app.histogram;
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut app.histogram_rgb, "RGB");
+                    ui.separator();
+                    let clip_color = |fraction: f32| {
+                        if fraction > 0.05 { Color32::from_rgb(255, 120, 80) } else { Color32::GRAY }
+                    };
+                    ui.label(
+                        RichText::new(format!("Black {:.1}%", hist.clipped_black * 100.0))
+                            .color(clip_color(hist.clipped_black)),
+                    );
+                    ui.label(
+                        RichText::new(format!("White {:.1}%", hist.clipped_white * 100.0))
+                            .color(clip_color(hist.clipped_white)),
+                    );
+                });
+
+                let bin_width = 1.0 / phosphlux_lite::app::HISTOGRAM_BINS as f64;
+                let bin_center = |i: usize| (i as f64 + 0.5) * bin_width;
+
+                egui_plot::Plot::new("histogram_plot")
+                    .height(160.0)
+                    .include_x(0.0)
+                    .include_x(1.0)
+                    .include_y(0.0)
+                    .allow_drag(false)
+                    .allow_zoom(false)
+                    .allow_scroll(false)
+                    .show_axes([true, false])
+                    .show(ui, |plot_ui| {
+                        let bars = hist
+                            .luma
+                            .iter()
+                            .enumerate()
+                            .map(|(i, &v)| egui_plot::Bar::new(bin_center(i), v as f64).width(bin_width))
+                            .collect();
+                        plot_ui.bar_chart(egui_plot::BarChart::new(bars).color(Color32::LIGHT_GRAY));
+
+                        if app.histogram_rgb {
+                            let channels = [
+                                Color32::from_rgb(255, 80, 80),
+                                Color32::from_rgb(80, 255, 80),
+                                Color32::from_rgb(80, 140, 255),
+                            ];
+                            for (levels, color) in hist.rgb.iter().zip(channels) {
+                                let points: Vec<[f64; 2]> = levels
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(i, &v)| [bin_center(i), v as f64])
+                                    .collect();
+                                plot_ui.line(egui_plot::Line::new(points).color(color));
+                            }
+                        }
+                    });
+            });
+    }
+
     // Settings window (floating)
     if app.show_settings {
         egui::Window::new("Settings")
@@ -134,6 +493,43 @@ pub fn draw_ui(ctx: &egui::Context, app: &mut App) {
                 ui.heading("Display");
 
                 ui.checkbox(&mut app.bezel.enabled, "Show Bezel");
+                ui.horizontal(|ui| {
+                    ui.label("Image:");
+                    let name = app
+                        .bezel_file
+                        .as_ref()
+                        .and_then(|path| path.file_name())
+                        .map_or("Built-in".into(), |name| name.to_string_lossy());
+                    ui.label(RichText::new(name).monospace());
+                    if ui.small_button("Load…").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("PNG", &["png"]).pick_file() {
+                            app.bezel_file = Some(path);
+                            app.bezel_error = None;
+                        }
+                    }
+                    if ui.small_button("Built-in").clicked() {
+                        app.bezel_file = None;
+                        app.bezel_error = None;
+                    }
+                });
+                if let Some(error) = &app.bezel_error {
+                    ui.label(RichText::new(error).color(Color32::from_rgb(255, 100, 100)));
+                }
+                ui.checkbox(&mut app.nearest_preview, "Crisp Pixels")
+                    .on_hover_text("Scale the preview with nearest-neighbor filtering instead of smoothing");
+                ui.checkbox(&mut app.perf.enabled, "Performance Overlay")
+                    .on_hover_text("Show frame rate and GPU render time");
+
+                ui.horizontal(|ui| {
+                    ui.label("Background:");
+                    ui.color_edit_button_srgb(&mut app.background);
+                    if ui.small_button("Black").clicked() {
+                        app.background = [0, 0, 0];
+                    }
+                    if ui.small_button("Default").clicked() {
+                        app.background = [25, 25, 25];
+                    }
+                });
 
                 ui.horizontal(|ui| {
                     ui.label("Zoom:");
@@ -187,51 +583,653 @@ pub fn draw_ui(ctx: &egui::Context, app: &mut App) {
                 ui.add_space(10.0);
 
                 if ui.button("Reset to Default").clicked() {
-                    app.bezel = crate::app::BezelSettings::default();
+                    app.bezel = phosphlux_lite::app::BezelSettings::default();
                 }
 
-                ui.add_space(10.0);
+                ui.add_space(10.0);
+                ui.separator();
+                ui.add_space(5.0);
+
+                ui.heading("Rendering");
+
+                ui.horizontal(|ui| {
+                    ui.label("Resolution:");
+                    let [width, height] = app.output_size;
+                    egui::ComboBox::from_id_salt("resolution")
+                        .selected_text(format!("{width}×{height}"))
+                        .show_ui(ui, |ui| {
+                            for [_, height] in phosphlux_lite::app::RESOLUTIONS {
+                                let size = app.aspect.size(height);
+                                let label = format!("{}×{}", size[0], size[1]);
+                                ui.selectable_value(&mut app.output_size, size, label);
+                            }
+                        });
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Aspect:");
+                    let before = app.aspect;
+                    egui::ComboBox::from_id_salt("synth_aspect")
+                        .selected_text(app.aspect.label())
+                        .show_ui(ui, |ui| {
+                            for aspect in phosphlux_lite::app::SynthAspect::ALL {
+                                ui.selectable_value(&mut app.aspect, aspect, aspect.label());
+                            }
+                        });
+                    if app.aspect != before {
+                        app.output_size = app.aspect.size(app.output_size[1]);
+                    }
+                });
+                ui.label(
+                    RichText::new("Lower resolutions keep heavy feedback smooth on slower GPUs; trails restart on change")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label("Feedback Res:");
+                    for (scale, label) in [(1.0, "Full"), (0.5, "1/2"), (0.25, "1/4")] {
+                        if ui.selectable_label(app.feedback_scale == scale, label).clicked() {
+                            app.feedback_scale = scale;
+                        }
+                    }
+                });
+                ui.label(
+                    RichText::new("Lower feedback resolution gives softer, cheaper trails")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.add_space(4.0);
+                ui.checkbox(&mut app.jump_guard.enabled, "Feedback Jump Guard");
+                ui.label(
+                    RichText::new("Briefly fades trails when a feedback control jumps in one frame, instead of blowing out")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label("Smoothing:");
+                    ui.add(egui::Slider::new(&mut app.smoothing.secs, 0.0..=1.0).suffix(" s"));
+                });
+                ui.label(
+                    RichText::new("Parameters glide to new values instead of jumping (0 = instant); switches still cut")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.add_space(4.0);
+                ui.checkbox(&mut app.supersample, "Supersample (2x)");
+                ui.label(
+                    RichText::new("Renders at twice the resolution and averages down: smoother shapes and bars, 4x the GPU work")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.add_space(4.0);
+                ui.checkbox(&mut app.float_feedback, "Float Feedback (16-bit)");
+                ui.label(
+                    RichText::new("Stores trails at half-float precision so slow zooms and drifts move smoothly instead of sticking")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label("Feedback Rate:");
+                    ui.add(
+                        egui::Slider::new(&mut app.feedback_rate, 5.0..=120.0)
+                            .step_by(1.0)
+                            .suffix(" steps/s"),
+                    );
+                    if ui.small_button("60").clicked() {
+                        app.feedback_rate = 60.0;
+                    }
+                });
+                ui.label(
+                    RichText::new("Feedback evolves at this rate on any display; lower it for slow motion")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    let mut looping = app.loop_frames.is_some();
+                    let mut frames = app.loop_frames.unwrap_or(120);
+                    let toggled = ui.checkbox(&mut looping, "Loop Mode:").changed();
+                    let resized = ui
+                        .add_enabled(
+                            looping,
+                            egui::DragValue::new(&mut frames).speed(1.0).range(2..=3600).suffix(" frames"),
+                        )
+                        .changed();
+                    if toggled || resized {
+                        app.set_loop(looping.then_some(frames));
+                    }
+                    if looping {
+                        ui.label(RichText::new(format!("{:.2}s", app.loop_length())).color(Color32::GRAY));
+                    }
+                });
+                ui.label(
+                    RichText::new("Time repeats every N frames; LFOs, spins and scrolls round to whole cycles so the loop is seamless")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.add_space(10.0);
+                ui.separator();
+                ui.add_space(5.0);
+
+                ui.heading("LFO Speeds");
+                ui.horizontal(|ui| {
+                    for (speed, label) in app.automation.lfo_speeds.iter_mut().zip(["S:", "M:", "F:"]) {
+                        ui.label(label);
+                        ui.add(egui::DragValue::new(speed).speed(0.01).range(0.01..=4.0).suffix("x"));
+                    }
+                    if ui.small_button("Default").clicked() {
+                        app.automation.lfo_speeds = phosphlux_lite::automation::DEFAULT_LFO_SPEEDS;
+                    }
+                });
+                ui.label(
+                    RichText::new("Speeds (x BPM) the S/M/F buttons step through")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.add_space(10.0);
+                ui.separator();
+                ui.add_space(5.0);
+
+                ui.heading("Stills");
+
+                ui.horizontal(|ui| {
+                    let mut hold = app.still_lfo_phase.is_some();
+                    if ui.checkbox(&mut hold, "Hold LFOs at phase:").changed() {
+                        app.still_lfo_phase = hold.then_some(0.0);
+                    }
+                    if let Some(phase) = &mut app.still_lfo_phase {
+                        ui.add(egui::Slider::new(phase, 0.0..=1.0));
+                    }
+                });
+                ui.label(
+                    RichText::new("Captured stills evaluate every LFO at this point in its cycle, so they're reproducible")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.add_space(10.0);
+                ui.separator();
+                ui.add_space(5.0);
+
+                ui.heading("MIDI");
+
+                match &app.midi {
+                    Some(midi) => ui.label(format!("Input: {}", midi.port_name)),
+                    None => ui.label(RichText::new("No MIDI input").color(Color32::GRAY)),
+                };
+                let mut unmap = None;
+                for (key, cc) in &app.midi_map {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(format!("CC {cc:>3}")).monospace());
+                        ui.label(key);
+                        if ui.small_button("✕").on_hover_text("Remove mapping").clicked() {
+                            unmap = Some(key.clone());
+                        }
+                    });
+                }
+                if let Some(key) = unmap {
+                    app.unmap_midi(&key);
+                }
+                ui.label(
+                    RichText::new("MIDI Learn (top bar), click a slider, then move a knob to map it")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.add_space(10.0);
+                ui.separator();
+                ui.add_space(5.0);
+
+                ui.heading("OSC");
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut app.osc_enabled, "Listen on port");
+                    ui.add(egui::DragValue::new(&mut app.osc_port).range(1024..=65535));
+                });
+                match &app.osc {
+                    Some(osc) => match osc.client() {
+                        Some(client) => ui.label(format!("Controller: {client}")),
+                        None => ui.label(RichText::new("Waiting for a controller").color(Color32::GRAY)),
+                    },
+                    None if app.osc_enabled => {
+                        ui.label(RichText::new("Port unavailable").color(Color32::from_rgb(255, 100, 100)))
+                    }
+                    None => ui.label(RichText::new("Off").color(Color32::GRAY)),
+                };
+                ui.label(
+                    RichText::new("Send /phosphlux/<stage>/<param> with a 0-1 float, e.g. /phosphlux/geometry/wobbulate_h")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.add_space(10.0);
+                ui.separator();
+                ui.add_space(5.0);
+
+                ui.heading("Texture Sharing");
+
+                ui.add_enabled_ui(texture_share::AVAILABLE, |ui| {
+                    ui.checkbox(&mut app.share_enabled, "Share output (Spout)");
+                    ui.horizontal(|ui| {
+                        ui.label("Server name:");
+                        // Takes effect when editing finishes, not on every keystroke
+                        let edit_id = egui::Id::new("share_name_edit");
+                        let mut name = ui.data(|d| d.get_temp::<String>(edit_id)).unwrap_or_else(|| app.share_name.clone());
+                        let response = ui.text_edit_singleline(&mut name);
+                        if response.lost_focus() {
+                            app.share_name = name;
+                            ui.data_mut(|d| d.remove::<String>(edit_id));
+                        } else if response.has_focus() {
+                            ui.data_mut(|d| d.insert_temp(edit_id, name));
+                        }
+                    });
+                });
+                match &app.share {
+                    Some(share) => ui.label(format!("Sending as \"{}\"", share.name.trim())),
+                    None if app.share_enabled => {
+                        ui.label(RichText::new("Can't share under this name").color(Color32::from_rgb(255, 100, 100)))
+                    }
+                    None if !texture_share::AVAILABLE => ui.label(
+                        RichText::new("Needs Windows and a build with the spout feature").color(Color32::GRAY),
+                    ),
+                    None => ui.label(RichText::new("Off").color(Color32::GRAY)),
+                };
+
+                ui.add_space(10.0);
+                ui.separator();
+                ui.add_space(5.0);
+
+                ui.heading("Accessibility");
+
+                ui.checkbox(&mut app.flash_limiter.enabled, "Flash Limiter");
+                ui.horizontal(|ui| {
+                    ui.label("Max Rise:");
+                    ui.add_enabled(
+                        app.flash_limiter.enabled,
+                        egui::Slider::new(&mut app.flash_limiter.max_rise, 0.25..=4.0).suffix(" /s"),
+                    );
+                });
+                ui.label(
+                    RichText::new(
+                        "Limits how fast overall brightness can rise, so strobes and cuts to bright \
+                         frames fade in instead of flashing. Recommended for public installations \
+                         and viewers sensitive to flashing light.",
+                    )
+                    .small()
+                    .color(Color32::GRAY),
+                );
+
+                ui.add_space(10.0);
+
+                if ui.button("Close").clicked() {
+                    app.show_settings = false;
+                }
+            });
+    }
+
+    // Shader compile error (stays up until the shader builds or is dismissed)
+    let mut dismiss_shader_error = false;
+    if let Some(error) = &app.shader_error {
+        egui::Window::new(RichText::new("Shader Error").color(Color32::from_rgb(255, 90, 90)))
+            .collapsible(false)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new("The last shader that compiled (or a plain ramp) keeps running")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    ui.label(RichText::new(error).monospace());
+                });
+                ui.add_space(5.0);
+                dismiss_shader_error = ui.button("Dismiss").clicked();
+            });
+    }
+    if dismiss_shader_error {
+        app.shader_error = None;
+    }
+
+    // Toast message (bottom center)
+    if let Some(toast) = &app.toast {
+        egui::Area::new(egui::Id::new("toast"))
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -24.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(&toast.message);
+                });
+            });
+    }
+}
+
+/// Draw a slider with LFO toggle button (range from the parameter table)
+/// Returns true if the value was manually modified (which should disable LFO)
+fn param_slider_with_lfo(
+    ui: &mut Ui,
+    label: &str,
+    param_key: &str,
+    value: &mut f32,
+    automation: &mut AutomationState,
+) -> bool {
+    slider_with_lfo(ui, label, param_key, value, param_range(param_key), None, automation)
+}
+
+/// Like `param_slider_with_lfo`, but shows the value in degrees
+/// (`degrees_per_unit` converts the stored value for display and typing)
+fn angle_slider_with_lfo(
+    ui: &mut Ui,
+    label: &str,
+    param_key: &str,
+    value: &mut f32,
+    degrees_per_unit: f64,
+    automation: &mut AutomationState,
+) -> bool {
+    slider_with_lfo(ui, label, param_key, value, param_range(param_key), Some(degrees_per_unit), automation)
+}
+
+/// Slider range for a parameter key
+fn param_range(param_key: &str) -> std::ops::RangeInclusive<f32> {
+    ParamSpec::find(param_key)
+        .unwrap_or_else(|| panic!("{param_key} is missing from the parameter table"))
+        .range()
+}
+
+/// Mod matrix rows: source -> destination, amount, remove
+fn draw_mod_matrix(ui: &mut Ui, routes: &mut Vec<ModRoute>) {
+    let param_combo = |ui: &mut Ui, id: (usize, &str), key: &mut String| {
+        let name = ParamSpec::find(key).map_or("?", |spec| spec.name);
+        egui::ComboBox::from_id_salt(id)
+            .selected_text(name)
+            .width(140.0)
+            .show_ui(ui, |ui| {
+                for spec in PARAMS {
+                    if ui.selectable_label(key == spec.key, spec.name).clicked() {
+                        *key = spec.key.to_string();
+                    }
+                }
+            });
+    };
+
+    let mut remove = None;
+    for (i, route) in routes.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            param_combo(ui, (i, "mod_source"), &mut route.source);
+            ui.label("→");
+            param_combo(ui, (i, "mod_dest"), &mut route.dest);
+            ui.add(egui::DragValue::new(&mut route.amount).speed(0.01).range(-1.0..=1.0))
+                .on_hover_text("Fraction of the destination's range added at the source's maximum");
+            if ui.small_button("✕").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        routes.remove(i);
+    }
+
+    if routes.is_empty() {
+        ui.label(RichText::new("No routes. Each route adds a scaled copy of one parameter to another.").small().color(Color32::GRAY));
+    }
+    if ui
+        .add_enabled(routes.len() < MAX_MOD_ROUTES, egui::Button::new("+ Add Route"))
+        .clicked()
+    {
+        routes.push(ModRoute {
+            source: "input.frequency".to_string(),
+            dest: "colorize.hue_offset".to_string(),
+            amount: 0.25,
+        });
+    }
+}
+
+/// Master panel: active preset, tempo, master fade, Sync, Clear FB and Panic
+fn draw_master_panel(ui: &mut Ui, app: &mut App) {
+    let big_button = |text: &str| {
+        egui::Button::new(RichText::new(text).strong().size(16.0)).min_size(egui::vec2(72.0, 28.0))
+    };
+
+    ui.horizontal(|ui| {
+        // Active preset name
+        let preset_name = app
+            .current_preset
+            .and_then(|i| app.presets.get(i))
+            .map_or("(modified)", |p| p.name.as_str());
+        ui.add_sized(
+            [160.0, 28.0],
+            egui::Label::new(RichText::new(preset_name).size(16.0).color(Color32::WHITE)).truncate(),
+        );
+
+        ui.separator();
+
+        // Global BPM control
+        ui.label("BPM:");
+        let bpm_presets = [60.0, 90.0, 120.0, 140.0];
+        for bpm in bpm_presets {
+            let selected = (app.automation.global_bpm - bpm).abs() < 1.0;
+            if ui
+                .selectable_label(selected, format!("{}", bpm as u32))
+                .clicked()
+            {
+                app.automation.global_bpm = bpm;
+            }
+        }
+
+        // Custom BPM slider (compact)
+        ui.add(
+            egui::DragValue::new(&mut app.automation.global_bpm)
+                .speed(1.0)
+                .range(30.0..=240.0)
+                .suffix(" BPM"),
+        );
+
+        if ui
+            .add(big_button("TAP"))
+            .on_hover_text("Tap along with the beat to set the BPM (averages the last 4 taps)")
+            .clicked()
+        {
+            app.tap_tempo();
+        }
+
+        if ui
+            .add(big_button("SYNC"))
+            .on_hover_text("Restart all LFOs on the downbeat")
+            .clicked()
+        {
+            app.automation.sync();
+        }
+        ui.checkbox(&mut app.automation.retrigger_on_bpm, "Snap")
+            .on_hover_text("Realign LFOs to the nearest beat after a tempo change");
+
+        ui.separator();
+
+        // Master fade
+        ui.label("Master:");
+        ui.spacing_mut().slider_width = 160.0;
+        if ui
+            .add(egui::Slider::new(&mut app.master_fade, 0.0..=1.0).show_value(false))
+            .changed()
+        {
+            // Grabbing the fader cancels a timed fade
+            app.fade = None;
+        }
+
+        if ui
+            .button(if app.next_fade_is_in() { "Fade In" } else { "Fade Out" })
+            .on_hover_text("F")
+            .clicked()
+        {
+            app.toggle_fade();
+        }
+        ui.add(
+            egui::DragValue::new(&mut app.fade_secs)
+                .speed(0.1)
+                .range(0.0..=60.0)
+                .suffix(" s"),
+        );
+
+        ui.separator();
+
+        // Chaos macro
+        ui.label("Chaos:");
+        ui.spacing_mut().slider_width = 100.0;
+        ui.add(egui::Slider::new(&mut app.chaos, 0.0..=1.0).show_value(false))
+            .on_hover_text("Push fold, feedback, wobble and noise toward extremes; 0 = the patch as set");
+
+        ui.separator();
+
+        ui.toggle_value(&mut app.paused, RichText::new("PAUSE").strong().size(16.0))
+            .on_hover_text("Freeze time, LFOs and feedback (P)");
+        if app.paused {
+            if ui.button("Step ▸").on_hover_text("Advance one frame (.)").clicked() {
+                app.request_frame_step();
+            }
+        }
+
+        // Elapsed time and frame count
+        let secs = app.time as u32;
+        ui.label(
+            RichText::new(format!("{:02}:{:02}  F{}", secs / 60, secs % 60, app.frame))
+                .monospace()
+                .color(Color32::GRAY),
+        )
+        .on_hover_text("Elapsed time and frame count");
+        if ui
+            .small_button("⟲")
+            .on_hover_text("Reset time, frame count and LFO clock to zero")
+            .clicked()
+        {
+            app.reset_clock();
+        }
+
+        ui.separator();
+
+        // Gesture recorder: REC captures hand moves, stopping it loops them
+        let recording = app.gestures.mode == GestureMode::Recording;
+        let rec_text = RichText::new("● REC").color(if recording { Color32::RED } else { Color32::GRAY });
+        if ui
+            .selectable_label(recording, rec_text)
+            .on_hover_text("Record slider moves; click again to loop them")
+            .clicked()
+        {
+            if recording {
+                app.gestures.stop_recording();
+            } else {
+                app.gestures.record(&app.synth);
+            }
+        }
+        let playing = app.gestures.mode == GestureMode::Playing;
+        if !app.gestures.recording.tracks.is_empty()
+            && ui
+                .selectable_label(playing, "▶ Loop")
+                .on_hover_text("Play the recorded moves on a loop")
+                .clicked()
+        {
+            if playing {
+                app.gestures.stop();
+            } else {
+                app.gestures.play();
+            }
+        }
+        if recording || playing {
+            ui.label(RichText::new(format!("{:.1}s", app.gestures.clock)).monospace().color(Color32::GRAY));
+        }
+
+        ui.separator();
+
+        // Scene slots: click recalls (or stores into an empty slot),
+        // Shift+click stores, right-click empties
+        ui.label("Scenes:");
+        for index in 0..app.scenes.len() {
+            let filled = app.scenes[index].is_some();
+            let text = RichText::new(format!("{}", index + 1))
+                .monospace()
+                .color(if filled { Color32::WHITE } else { Color32::DARK_GRAY });
+            let response = ui.selectable_label(filled, text).on_hover_text(format!(
+                "F{0}: recall, Shift+F{0} / Shift+click: store, right-click: empty",
+                index + 1
+            ));
+            if response.clicked() {
+                if filled && !ui.input(|i| i.modifiers.shift) {
+                    app.recall_scene(index);
+                } else {
+                    app.store_scene(index);
+                }
+            }
+            if response.secondary_clicked() {
+                app.clear_scene(index);
+            }
+        }
+
+        ui.separator();
+
+        if ui
+            .add(big_button("CLEAR FB"))
+            .on_hover_text("Wipe the feedback buffers")
+            .clicked()
+        {
+            app.clear_feedback = true;
+        }
+        if ui
+            .add(big_button("PANIC").fill(Color32::from_rgb(160, 40, 40)))
+            .on_hover_text("Reset the patch, clear LFOs and feedback (Esc / Backspace)")
+            .clicked()
+        {
+            app.panic_reset();
+        }
+    });
+}
+
+/// egui temp-data slot holding the keyboard nudge target
+fn focused_param_id() -> egui::Id {
+    egui::Id::new("focused_param")
+}
+
+/// Format a stored value as degrees
+fn format_degrees(value: f64, degrees_per_unit: f64) -> String {
+    format!("{:.1}°", value * degrees_per_unit)
+}
 
-                if ui.button("Close").clicked() {
-                    app.show_settings = false;
-                }
-            });
-    }
+/// Parse typed degrees ("12.5" or "12.5°") back to a stored value
+fn parse_degrees(text: &str, degrees_per_unit: f64) -> Option<f64> {
+    text.trim().trim_end_matches('°').trim().parse::<f64>().ok().map(|deg| deg / degrees_per_unit)
 }
 
-/// Draw a slider with LFO toggle button
-/// Returns true if the value was manually modified (which should disable LFO)
-fn param_slider_with_lfo(
+fn slider_with_lfo(
     ui: &mut Ui,
     label: &str,
     param_key: &str,
     value: &mut f32,
     range: std::ops::RangeInclusive<f32>,
+    degrees: Option<f64>,
     automation: &mut AutomationState,
 ) -> bool {
     let mut modified = false;
 
     ui.horizontal(|ui| {
         // LFO toggle button
-        let lfo_active = automation.has_lfo(param_key);
-        let button_text = if let Some(lfo) = automation.get_lfo(param_key) {
-            match lfo.speed {
-                s if s < 0.15 => "S",
-                s if s < 0.4 => "M",
-                _ => "F",
-            }
-        } else {
-            "~"
+        let (glyph, button_color) = match automation.lfo_tier(param_key) {
+            Some(0) => ("S", Color32::from_rgb(100, 200, 100)), // Green - Slow
+            Some(1) => ("M", Color32::from_rgb(200, 200, 100)), // Yellow - Medium
+            Some(_) => ("F", Color32::from_rgb(100, 200, 200)), // Cyan - Fast
+            None if automation.get_audio_bind(param_key).is_some() => ("A", Color32::from_rgb(200, 100, 200)), // Magenta - Audio
+            None => ("~", Color32::from_rgb(80, 80, 80)), // Gray - Off
         };
-
-        let button_color = if lfo_active {
-            match automation.get_lfo(param_key).map(|l| l.speed) {
-                Some(s) if s < 0.15 => Color32::from_rgb(100, 200, 100), // Green - Slow
-                Some(s) if s < 0.4 => Color32::from_rgb(200, 200, 100),  // Yellow - Medium
-                _ => Color32::from_rgb(100, 200, 200),                    // Cyan - Fast
-            }
-        } else {
-            Color32::from_rgb(80, 80, 80) // Gray - Off
+        // A typed-in speed shows as its multiplier, colored by the nearest tier
+        let button_text = match automation.get_lfo(param_key) {
+            Some(lfos) if automation.lfo_bucket(param_key).is_none() => format_speed(lfos.primary.speed),
+            _ => glyph.to_string(),
         };
 
         let response = ui.add(
@@ -251,39 +1249,83 @@ fn param_slider_with_lfo(
         }
 
         // Show tooltip
-        response.on_hover_text("Left-click: cycle S/M/F/Off\nRight-click: disable");
-
-        // Label
-        ui.label(label);
+        let cycle = if automation.audio_available { "S/M/F/Audio/Off" } else { "S/M/F/Off" };
+        response.on_hover_text(format!("Left-click: cycle {cycle}\nRight-click: disable"));
+
+        // Label (highlighted when it's the keyboard nudge target)
+        let focus_id = focused_param_id();
+        let focused = ui
+            .data(|d| d.get_temp::<Option<String>>(focus_id))
+            .flatten()
+            .is_some_and(|key| key == param_key);
+        if focused {
+            ui.label(RichText::new(label).color(Color32::from_rgb(255, 200, 80)));
+        } else {
+            ui.label(label);
+        }
 
         // Slider
-        let slider_response = ui.add(egui::Slider::new(value, range.clone()).show_value(true));
+        let (decimals, step) = range_precision(&range);
+        let logarithmic = ParamSpec::find(param_key).is_some_and(|spec| spec.logarithmic);
+        let slider = egui::Slider::new(value, range.clone())
+            .logarithmic(logarithmic)
+            .show_value(true)
+            .step_by(step)
+            .drag_value_speed(step * 2.0);
+        let slider = match degrees {
+            Some(deg) => slider
+                .custom_formatter(move |v, _| format_degrees(v, deg))
+                .custom_parser(move |text| parse_degrees(text, deg)),
+            None => slider.fixed_decimals(decimals),
+        };
+        let slider_response = ui.add(slider);
         if slider_response.changed() {
             // Manual adjustment disables LFO
             automation.remove_lfo(param_key);
             modified = true;
         }
+        if let Some(lfos) = automation.get_lfo(param_key) {
+            paint_lfo_sweep(ui, slider_response.rect, &range, logarithmic, &lfos.primary, *value, button_color);
+        }
+        if slider_response.drag_started() || slider_response.clicked() {
+            // Touched slider becomes the nudge target; drop egui focus so the
+            // arrow keys reach the app instead of the widget
+            slider_response.surrender_focus();
+            ui.data_mut(|d| d.insert_temp(focus_id, Some(param_key.to_string())));
+        }
     });
 
     // Show expanded LFO controls if active
-    if let Some(lfo) = automation.get_lfo_mut(param_key) {
+    let (min, max) = (*range.start(), *range.end());
+    let mut add_layer = false;
+    let mut custom_speed = None;
+    if let Some(lfos) = automation.get_lfo_mut(param_key) {
+        let (decimals, step) = range_precision(&range);
+        let lfo = &mut lfos.primary;
         ui.indent(param_key, |ui| {
             ui.horizontal(|ui| {
                 ui.label("Range:");
-                ui.add(
-                    egui::DragValue::new(&mut lfo.lo)
-                        .speed(0.01)
-                        .range(*range.start()..=lfo.hi)
-                        .prefix("lo: "),
-                );
-                ui.add(
-                    egui::DragValue::new(&mut lfo.hi)
-                        .speed(0.01)
-                        .range(lfo.lo..=*range.end())
-                        .prefix("hi: "),
-                );
+                // lo may sit above hi: the sweep then starts by falling
+                ui.add(lfo_bound_drag(&mut lfo.lo, range.clone(), "lo: ", step, decimals, degrees));
+                ui.add(lfo_bound_drag(&mut lfo.hi, range.clone(), "hi: ", step, decimals, degrees));
+                if ui.small_button("⇅").on_hover_text("Swap lo and hi (reverse sweep direction)").clicked() {
+                    std::mem::swap(&mut lfo.lo, &mut lfo.hi);
+                }
             });
             ui.horizontal(|ui| {
+                ui.label("Speed:");
+                let mut speed = lfo.speed;
+                if ui
+                    .add(egui::DragValue::new(&mut speed).speed(0.01).range(0.01..=4.0).suffix("x"))
+                    .on_hover_text("Any multiplier of the tempo, beyond S/M/F")
+                    .changed()
+                {
+                    custom_speed = Some(speed);
+                }
+
+                ui.checkbox(&mut lfo.free, "Free")
+                    .on_hover_text("Run on its own clock at Speed cycles per second, ignoring the tempo and SYNC");
+
                 ui.label("Phase:");
                 ui.add(egui::Slider::new(&mut lfo.offset, 0.0..=1.0).show_value(false));
 
@@ -304,6 +1346,93 @@ fn param_slider_with_lfo(
                             }
                         }
                     });
+
+                ui.label("Shape:");
+                egui::ComboBox::from_id_salt(format!("{}_shape", param_key))
+                    .selected_text(format!("{:?}", lfo.shape))
+                    .width(70.0)
+                    .show_ui(ui, |ui| {
+                        for shape in LfoShape::ALL {
+                            ui.selectable_value(&mut lfo.shape, shape, format!("{:?}", shape));
+                        }
+                    });
+            });
+            if lfo.shape == LfoShape::Random {
+                ui.horizontal(|ui| {
+                    ui.label("Seed:");
+                    ui.add(egui::DragValue::new(&mut lfo.seed).speed(1.0))
+                        .on_hover_text("Picks the sequence of values; the same seed always replays the same steps");
+                    ui.label("Slew:");
+                    ui.add(egui::Slider::new(&mut lfo.slew, 0.0..=1.0))
+                        .on_hover_text("Glide into each new value over this fraction of a step (0 = hard jumps)");
+                });
+            }
+
+            // Second layer, summed on top of the first
+            match &mut lfos.secondary {
+                Some(layer) => {
+                    let mut remove = false;
+                    ui.horizontal(|ui| {
+                        ui.label("Layer 2:");
+                        // Swings depth either side of the first layer's value
+                        if ui
+                            .add(lfo_bound_drag(&mut layer.hi, 0.0..=(max - min) / 2.0, "±", step, decimals, degrees))
+                            .changed()
+                        {
+                            layer.lo = -layer.hi;
+                        }
+                        ui.add(
+                            egui::DragValue::new(&mut layer.speed)
+                                .speed(0.01)
+                                .range(0.01..=4.0)
+                                .suffix("x"),
+                        );
+                        egui::ComboBox::from_id_salt(format!("{}_shape2", param_key))
+                            .selected_text(format!("{:?}", layer.shape))
+                            .width(70.0)
+                            .show_ui(ui, |ui| {
+                                for shape in LfoShape::ALL {
+                                    ui.selectable_value(&mut layer.shape, shape, format!("{:?}", shape));
+                                }
+                            });
+                        remove = ui.small_button("✕").on_hover_text("Remove layer 2").clicked();
+                    });
+                    if remove {
+                        lfos.secondary = None;
+                    }
+                }
+                None => {
+                    add_layer = ui
+                        .small_button("+")
+                        .on_hover_text("Add a second LFO layer, summed with this one")
+                        .clicked();
+                }
+            }
+        });
+    }
+    if add_layer {
+        automation.add_lfo_layer(param_key, min, max);
+    }
+    if let Some(speed) = custom_speed {
+        automation.set_custom_speed(param_key, speed);
+    }
+
+    // Audio bind controls: which measure to follow and the value range
+    if let Some(bind) = automation.get_audio_bind_mut(param_key) {
+        let (decimals, step) = range_precision(&range);
+        ui.indent(param_key, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Band:");
+                egui::ComboBox::from_id_salt(format!("{}_band", param_key))
+                    .selected_text(format!("{:?}", bind.band))
+                    .width(60.0)
+                    .show_ui(ui, |ui| {
+                        for band in AudioBand::ALL {
+                            ui.selectable_value(&mut bind.band, band, format!("{:?}", band));
+                        }
+                    });
+                ui.add(lfo_bound_drag(&mut bind.lo, range.clone(), "lo: ", step, decimals, degrees));
+                ui.add(lfo_bound_drag(&mut bind.hi, range.clone(), "hi: ", step, decimals, degrees));
             });
         });
     }
@@ -311,7 +1440,80 @@ fn param_slider_with_lfo(
     modified
 }
 
+/// Paint an LFO's sweep over its slider: a faint band on the track from lo
+/// to hi and a tick at the live value
+fn paint_lfo_sweep(
+    ui: &Ui,
+    slider_rect: egui::Rect,
+    range: &std::ops::RangeInclusive<f32>,
+    logarithmic: bool,
+    lfo: &LfoState,
+    value: f32,
+    color: Color32,
+) {
+    let height = slider_rect.height();
+    let rail = egui::Rect::from_min_size(slider_rect.min, egui::vec2(ui.spacing().slider_width, height));
+    // Handle travel is inset by egui's handle radius
+    let inset = height / 2.5;
+    let (min, max) = (*range.start(), *range.end());
+    let x = |v: f32| {
+        let t = if logarithmic && min > 0.0 {
+            (v.max(min).ln() - min.ln()) / (max.ln() - min.ln())
+        } else {
+            (v - min) / (max - min)
+        };
+        egui::lerp(rail.left() + inset..=rail.right() - inset, t.clamp(0.0, 1.0))
+    };
+
+    let (lo, hi) = (x(lfo.lo), x(lfo.hi));
+    let y = rail.center().y;
+    let band = egui::Rect::from_x_y_ranges(lo.min(hi)..=lo.max(hi), y - height * 0.25..=y + height * 0.25);
+    let painter = ui.painter();
+    painter.rect_filled(band, 2.0, color.gamma_multiply(0.35));
+    let tick = x(value);
+    painter.line_segment(
+        [egui::pos2(tick, rail.top()), egui::pos2(tick, rail.bottom())],
+        egui::Stroke::new(1.5, color),
+    );
+}
+
+/// DragValue for an LFO lo/hi bound, matching the slider's precision and units
+fn lfo_bound_drag<'a>(
+    value: &'a mut f32,
+    bounds: std::ops::RangeInclusive<f32>,
+    prefix: &str,
+    step: f64,
+    decimals: usize,
+    degrees: Option<f64>,
+) -> egui::DragValue<'a> {
+    let drag = egui::DragValue::new(value)
+        .speed(step * 2.0)
+        .range(bounds)
+        .prefix(prefix);
+    match degrees {
+        Some(deg) => drag
+            .custom_formatter(move |v, _| format_degrees(v, deg))
+            .custom_parser(move |text| parse_degrees(text, deg)),
+        None => drag.fixed_decimals(decimals),
+    }
+}
+
+/// Display decimals and step size for a parameter range, so narrow ranges
+/// (e.g. zoom 0.9-1.1, chroma shift 0-0.02) get proportionally finer control.
+/// Gives 1000-10000 steps across the range.
+fn range_precision(range: &std::ops::RangeInclusive<f32>) -> (usize, f64) {
+    let span = (*range.end() as f64 - *range.start() as f64).abs().max(1e-6);
+    let decimals = (3.0 - span.log10().floor()).clamp(0.0, 6.0) as usize;
+    (decimals, 10f64.powi(-(decimals as i32)))
+}
+
 /// Format subdivide value for display
+/// LFO speed multiplier for the LFO button, trimmed to fit ("0.08", "1.5")
+fn format_speed(speed: f32) -> String {
+    let text = format!("{speed:.2}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
 fn format_subdivide(val: f32) -> &'static str {
     if (val - 0.25).abs() < 0.01 {
         "1/4"
@@ -328,67 +1530,271 @@ fn format_subdivide(val: f32) -> &'static str {
     }
 }
 
-fn draw_input_stage(ui: &mut Ui, input: &mut InputStage, automation: &mut AutomationState) -> bool {
+/// Step lengths offered for steppers, in beats
+const STEP_LENGTHS: [(f32, &str); 6] = [
+    (0.25, "1/4 beat"),
+    (0.5, "1/2 beat"),
+    (1.0, "1 beat"),
+    (2.0, "2 beats"),
+    (4.0, "1 bar"),
+    (8.0, "2 bars"),
+];
+
+/// Combo box for an enum parameter, with a stepper to cycle it on the beat
+fn enum_combo_with_stepper<T: Copy + PartialEq + std::fmt::Debug>(
+    ui: &mut Ui,
+    label: &str,
+    param_key: &str,
+    value: &mut T,
+    options: &[T],
+    automation: &mut AutomationState,
+) -> bool {
     let mut modified = false;
 
-    ui.label("Source A:");
-    modified |= source_combo(ui, "source_a", &mut input.source_a);
+    ui.horizontal(|ui| {
+        stepper_button(ui, param_key, options.len() as u32, automation);
+        ui.label(label);
+        egui::ComboBox::from_id_salt(param_key)
+            .selected_text(format!("{:?}", value))
+            .show_ui(ui, |ui| {
+                for &option in options {
+                    if ui.selectable_label(*value == option, format!("{:?}", option)).clicked() {
+                        *value = option;
+                        // Manual choice disables stepper
+                        automation.remove_stepper(param_key);
+                        modified = true;
+                    }
+                }
+            });
+    });
 
-    ui.label("Source B:");
-    modified |= source_combo(ui, "source_b", &mut input.source_b);
+    let names: Vec<String> = options.iter().map(|option| format!("{:?}", option)).collect();
+    stepper_controls(ui, param_key, &names, automation);
 
-    ui.add_space(8.0);
+    modified
+}
+
+/// Checkbox for a bool parameter, with a stepper to flip it on the beat
+fn checkbox_with_stepper(
+    ui: &mut Ui,
+    label: &str,
+    param_key: &str,
+    value: &mut bool,
+    automation: &mut AutomationState,
+) -> bool {
+    let mut modified = false;
+
+    ui.horizontal(|ui| {
+        stepper_button(ui, param_key, 2, automation);
+        if ui.checkbox(value, label).changed() {
+            automation.remove_stepper(param_key);
+            modified = true;
+        }
+    });
 
-    modified |= param_slider_with_lfo(ui, "Mix A/B:", "input.mix", &mut input.mix, 0.0..=1.0, automation);
-    modified |= param_slider_with_lfo(ui, "Frequency:", "input.frequency", &mut input.frequency, 0.5..=20.0, automation);
-    modified |= param_slider_with_lfo(ui, "Phase:", "input.phase", &mut input.phase, 0.0..=1.0, automation);
-    modified |= param_slider_with_lfo(ui, "Rotation:", "input.rotation", &mut input.rotation, 0.0..=1.0, automation);
+    stepper_controls(ui, param_key, &["Off".to_string(), "On".to_string()], automation);
 
     modified
 }
 
-fn source_combo(ui: &mut Ui, id: &str, source: &mut InputSource) -> bool {
-    let mut changed = false;
-    egui::ComboBox::from_id_salt(id)
-        .selected_text(format!("{:?}", source))
-        .show_ui(ui, |ui| {
-            let sources = [
-                InputSource::RampH,
-                InputSource::RampV,
-                InputSource::OscH,
-                InputSource::OscV,
-                InputSource::NoiseWhite,
-                InputSource::NoisePink,
-                InputSource::NoiseBrown,
-                InputSource::ShapeCircle,
-                InputSource::ShapeRect,
-                InputSource::ShapeDiamond,
-                InputSource::Checkerboard,
-            ];
-            for s in sources {
-                if ui.selectable_label(*source == s, format!("{:?}", s)).clicked() {
-                    *source = s;
-                    changed = true;
+/// Stepper toggle button (orange when active)
+fn stepper_button(ui: &mut Ui, param_key: &str, option_count: u32, automation: &mut AutomationState) {
+    let fill = if automation.steppers.contains_key(param_key) {
+        Color32::from_rgb(220, 140, 60)
+    } else {
+        Color32::from_rgb(80, 80, 80)
+    };
+
+    let response = ui.add(
+        egui::Button::new(RichText::new("»").monospace())
+            .fill(fill)
+            .min_size(egui::vec2(22.0, 18.0)),
+    );
+    if response.clicked() {
+        automation.toggle_stepper(param_key, option_count);
+    }
+    response.on_hover_text("Step through options on the beat");
+}
+
+/// Expanded stepper controls: which options to visit and how long each lasts
+fn stepper_controls(ui: &mut Ui, param_key: &str, names: &[String], automation: &mut AutomationState) {
+    let Some(stepper) = automation.get_stepper_mut(param_key) else {
+        return;
+    };
+
+    ui.indent(format!("{}_stepper", param_key), |ui| {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Steps:");
+            for (index, name) in (0u32..).zip(names) {
+                let included = stepper.steps.contains(&index);
+                if ui.selectable_label(included, name).clicked() {
+                    if included {
+                        stepper.steps.retain(|&step| step != index);
+                    } else {
+                        stepper.steps.push(index);
+                        stepper.steps.sort_unstable();
+                    }
                 }
             }
         });
-    changed
+        ui.horizontal(|ui| {
+            ui.label("Every:");
+            let current = STEP_LENGTHS
+                .iter()
+                .find(|(beats, _)| (stepper.beats_per_step - beats).abs() < 0.01)
+                .map_or("1 bar", |&(_, name)| name);
+            egui::ComboBox::from_id_salt(format!("{}_every", param_key))
+                .selected_text(current)
+                .width(70.0)
+                .show_ui(ui, |ui| {
+                    for (beats, name) in STEP_LENGTHS {
+                        if ui
+                            .selectable_label((stepper.beats_per_step - beats).abs() < 0.01, name)
+                            .clicked()
+                        {
+                            stepper.beats_per_step = beats;
+                        }
+                    }
+                });
+        });
+    });
+}
+
+fn draw_input_stage(
+    ui: &mut Ui,
+    input: &mut InputStage,
+    solo: &mut Option<InputSolo>,
+    image_file: &mut Option<std::path::PathBuf>,
+    camera: (&mut u32, usize),
+    automation: &mut AutomationState,
+) -> bool {
+    let mut modified = false;
+
+    modified |= enum_combo_with_stepper(ui, "Source A:", "input.source_a", &mut input.source_a, &InputSource::ALL, automation);
+    modified |= enum_combo_with_stepper(ui, "Source B:", "input.source_b", &mut input.source_b, &InputSource::ALL, automation);
+
+    // Still for the Image source (Frequency zooms, Phase pans, Rotation turns)
+    ui.horizontal(|ui| {
+        ui.label("Image:");
+        let name = image_file
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map_or("(grey)".into(), |name| name.to_string_lossy());
+        ui.label(RichText::new(name).monospace());
+        if ui.small_button("Load Image…").clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("Images", &["png", "jpg", "jpeg", "bmp", "gif", "tga", "webp"])
+                .pick_file()
+            {
+                *image_file = Some(path);
+            }
+        }
+        if image_file.is_some() && ui.small_button("✕").on_hover_text("Back to grey").clicked() {
+            *image_file = None;
+        }
+    });
+
+    // Webcam device for the Camera source
+    if input.source_a == InputSource::Camera || input.source_b == InputSource::Camera {
+        let (device, count) = camera;
+        ui.horizontal(|ui| {
+            ui.label("Camera:");
+            if count == 0 {
+                ui.label(RichText::new("none found (source stays grey)").color(Color32::GRAY));
+                return;
+            }
+            egui::ComboBox::from_id_salt("camera_device")
+                .selected_text(format!("Device {device}"))
+                .show_ui(ui, |ui| {
+                    for index in 0..count as u32 {
+                        ui.selectable_value(device, index, format!("Device {index}"));
+                    }
+                });
+        });
+    }
+
+    // Oscillator shape, shared by OscH and OscV
+    let is_osc = |source: InputSource| matches!(source, InputSource::OscH | InputSource::OscV);
+    if is_osc(input.source_a) || is_osc(input.source_b) {
+        modified |= enum_combo_with_stepper(ui, "Waveform:", "input.osc_waveform", &mut input.osc_waveform, &OscWaveform::ALL, automation);
+        if input.osc_waveform == OscWaveform::Pulse {
+            modified |= param_slider_with_lfo(ui, "Duty:", "input.osc_duty", &mut input.osc_duty, automation);
+        }
+    }
+
+    // Shape size and edge, for the circle/rect/diamond sources
+    let is_shape = |source: InputSource| {
+        matches!(source, InputSource::ShapeCircle | InputSource::ShapeRect | InputSource::ShapeDiamond)
+    };
+    if is_shape(input.source_a) || is_shape(input.source_b) {
+        modified |= param_slider_with_lfo(ui, "Shape Size:", "input.shape_size", &mut input.shape_size, automation);
+        modified |= param_slider_with_lfo(ui, "Softness:", "input.shape_softness", &mut input.shape_softness, automation);
+    }
+
+    // Momentary solo: holding a button overrides the mix, releasing restores it
+    ui.horizontal(|ui| {
+        ui.label("Solo:");
+        let mut held = None;
+        for (source, label) in [(InputSolo::A, "A"), (InputSolo::B, "B")] {
+            let response = ui
+                .add(egui::Button::new(label).selected(*solo == Some(source)))
+                .on_hover_text(format!("Hold to see source {label} only"));
+            if response.is_pointer_button_down_on() {
+                held = Some(source);
+            }
+        }
+        *solo = held;
+    });
+
+    ui.add_space(8.0);
+
+    modified |= param_slider_with_lfo(ui, "Mix A/B:", "input.mix", &mut input.mix, automation);
+    modified |= param_slider_with_lfo(ui, "Frequency:", "input.frequency", &mut input.frequency, automation);
+    if input.source_a == InputSource::Checkerboard || input.source_b == InputSource::Checkerboard {
+        ui.label(
+            RichText::new(format!("Checkerboard: {:.0} squares across (Frequency)", input.frequency))
+                .small()
+                .color(Color32::GRAY),
+        );
+    }
+    modified |= param_slider_with_lfo(ui, "Phase:", "input.phase", &mut input.phase, automation);
+    modified |= param_slider_with_lfo(ui, "Scroll:", "input.phase_speed", &mut input.phase_speed, automation);
+    modified |= angle_slider_with_lfo(ui, "Rotation:", "input.rotation", &mut input.rotation, 360.0, automation);
+    modified |= param_slider_with_lfo(ui, "Spin:", "input.spin_speed", &mut input.spin_speed, automation);
+
+    ui.horizontal(|ui| {
+        ui.label("Noise Seed:");
+        modified |= ui
+            .add(egui::DragValue::new(&mut input.noise_seed).range(0..=9999))
+            .on_hover_text("Each seed gives the noise sources a different field (0 = classic)")
+            .changed();
+        if ui.small_button("New").clicked() {
+            // Cheap variety; reproducibility comes from the stored seed, not this
+            input.noise_seed = (ui.input(|i| i.time) * 1000.0) as u32 % 10000;
+            modified = true;
+        }
+    });
+
+    modified
 }
 
 fn draw_geometry_stage(ui: &mut Ui, geo: &mut GeometryStage, automation: &mut AutomationState) -> bool {
     let mut modified = false;
 
-    modified |= param_slider_with_lfo(ui, "Wobble H:", "geometry.wobbulate_h", &mut geo.wobbulate_h, 0.0..=1.0, automation);
-    modified |= param_slider_with_lfo(ui, "Wobble V:", "geometry.wobbulate_v", &mut geo.wobbulate_v, 0.0..=1.0, automation);
-    modified |= param_slider_with_lfo(ui, "Wobble Freq:", "geometry.wobble_freq", &mut geo.wobble_freq, 1.0..=20.0, automation);
-    modified |= param_slider_with_lfo(ui, "Z Displace:", "geometry.z_displacement", &mut geo.z_displacement, 0.0..=0.5, automation);
+    modified |= param_slider_with_lfo(ui, "Wobble H:", "geometry.wobbulate_h", &mut geo.wobbulate_h, automation);
+    modified |= param_slider_with_lfo(ui, "Wobble V:", "geometry.wobbulate_v", &mut geo.wobbulate_v, automation);
+    modified |= param_slider_with_lfo(ui, "Wobble Freq:", "geometry.wobble_freq", &mut geo.wobble_freq, automation);
+    modified |= param_slider_with_lfo(ui, "Z Displace:", "geometry.z_displacement", &mut geo.z_displacement, automation);
 
     ui.add_space(4.0);
 
-    modified |= param_slider_with_lfo(ui, "Lissajous X:", "geometry.lissajous_x", &mut geo.lissajous_x, 0.0..=1.0, automation);
-    modified |= param_slider_with_lfo(ui, "Lissajous Y:", "geometry.lissajous_y", &mut geo.lissajous_y, 0.0..=1.0, automation);
-    modified |= param_slider_with_lfo(ui, "Rotation:", "geometry.rotation", &mut geo.rotation, 0.0..=1.0, automation);
-    modified |= param_slider_with_lfo(ui, "Scale:", "geometry.scale", &mut geo.scale, 0.5..=2.0, automation);
+    modified |= param_slider_with_lfo(ui, "Lissajous X:", "geometry.lissajous_x", &mut geo.lissajous_x, automation);
+    modified |= param_slider_with_lfo(ui, "Lissajous Y:", "geometry.lissajous_y", &mut geo.lissajous_y, automation);
+    modified |= angle_slider_with_lfo(ui, "Rotation:", "geometry.rotation", &mut geo.rotation, 360.0, automation);
+    modified |= param_slider_with_lfo(ui, "Spin:", "geometry.spin_speed", &mut geo.spin_speed, automation);
+    modified |= param_slider_with_lfo(ui, "Scale:", "geometry.scale", &mut geo.scale, automation);
+    modified |= param_slider_with_lfo(ui, "Pivot X:", "geometry.rotate_center_x", &mut geo.rotate_center_x, automation);
+    modified |= param_slider_with_lfo(ui, "Pivot Y:", "geometry.rotate_center_y", &mut geo.rotate_center_y, automation);
 
     modified
 }
@@ -396,82 +1802,185 @@ fn draw_geometry_stage(ui: &mut Ui, geo: &mut GeometryStage, automation: &mut Au
 fn draw_amplitude_stage(ui: &mut Ui, amp: &mut AmplitudeStage, automation: &mut AutomationState) -> bool {
     let mut modified = false;
 
-    modified |= param_slider_with_lfo(ui, "Fold Gain:", "amplitude.fold_gain", &mut amp.fold_gain, 1.0..=8.0, automation);
-    modified |= param_slider_with_lfo(ui, "Fold Mix:", "amplitude.fold_mix", &mut amp.fold_mix, 0.0..=1.0, automation);
+    modified |= param_slider_with_lfo(ui, "Fold Gain:", "amplitude.fold_gain", &mut amp.fold_gain, automation);
+    modified |= param_slider_with_lfo(ui, "Fold Mix:", "amplitude.fold_mix", &mut amp.fold_mix, automation);
 
     ui.add_space(4.0);
 
-    modified |= param_slider_with_lfo(ui, "Quantize:", "amplitude.quantize_levels", &mut amp.quantize_levels, 2.0..=32.0, automation);
-    modified |= param_slider_with_lfo(ui, "Quant Mix:", "amplitude.quantize_mix", &mut amp.quantize_mix, 0.0..=1.0, automation);
+    modified |= param_slider_with_lfo(ui, "Quantize:", "amplitude.quantize_levels", &mut amp.quantize_levels, automation);
+    modified |= param_slider_with_lfo(ui, "Quant Mix:", "amplitude.quantize_mix", &mut amp.quantize_mix, automation);
+    modified |= param_slider_with_lfo(ui, "Dither:", "amplitude.dither", &mut amp.dither, automation);
 
     ui.add_space(4.0);
 
-    modified |= param_slider_with_lfo(ui, "Soft Clip:", "amplitude.soft_clip", &mut amp.soft_clip, 0.0..=1.0, automation);
-    modified |= param_slider_with_lfo(ui, "Solarize:", "amplitude.solarize", &mut amp.solarize, 0.0..=1.0, automation);
-    modified |= param_slider_with_lfo(ui, "Gate:", "amplitude.gate_threshold", &mut amp.gate_threshold, 0.0..=1.0, automation);
+    modified |= param_slider_with_lfo(ui, "Soft Clip:", "amplitude.soft_clip", &mut amp.soft_clip, automation);
+    modified |= param_slider_with_lfo(ui, "Solarize:", "amplitude.solarize", &mut amp.solarize, automation);
+    modified |= param_slider_with_lfo(ui, "Gate:", "amplitude.gate_threshold", &mut amp.gate_threshold, automation);
 
-    let mut invert_bool = amp.invert > 0.5;
-    if ui.checkbox(&mut invert_bool, "Invert").changed() {
-        amp.invert = if invert_bool { 1.0 } else { 0.0 };
-        modified = true;
+    modified |= channel_toggles(ui, "Solarize Ch:", &mut amp.solarize_channels);
+    modified |= channel_toggles(ui, "Invert:", &mut amp.invert);
+
+    ui.add_space(4.0);
+
+    modified |= ui
+        .checkbox(&mut amp.normalize, "Auto-Normalize")
+        .on_hover_text("Stretch the signal's measured range to full 0-1 before colorize")
+        .changed();
+    if amp.normalize {
+        modified |= param_slider_with_lfo(ui, "Adapt Rate:", "amplitude.normalize_rate", &mut amp.normalize_rate, automation);
     }
 
     modified
 }
 
+/// Row of R/G/B checkboxes plus an "All" shortcut
+fn channel_toggles(ui: &mut Ui, label: &str, channels: &mut [bool; 3]) -> bool {
+    let mut modified = false;
+
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let mut all = channels.iter().all(|&c| c);
+        if ui.checkbox(&mut all, "All").changed() {
+            *channels = [all; 3];
+            modified = true;
+        }
+        for (channel, name) in channels.iter_mut().zip(["R", "G", "B"]) {
+            modified |= ui.checkbox(channel, name).changed();
+        }
+    });
+
+    modified
+}
+
 fn draw_colorize_stage(ui: &mut Ui, color: &mut ColorizeStage, automation: &mut AutomationState) -> bool {
     let mut modified = false;
 
-    ui.label("Mode:");
-    egui::ComboBox::from_id_salt("color_mode")
-        .selected_text(format!("{:?}", color.mode))
-        .show_ui(ui, |ui| {
-            for mode in [ColorMode::Spectrum, ColorMode::Threshold, ColorMode::Gradient, ColorMode::Monochrome] {
-                if ui.selectable_label(color.mode == mode, format!("{:?}", mode)).clicked() {
-                    color.mode = mode;
-                    modified = true;
-                }
-            }
-        });
+    modified |= enum_combo_with_stepper(ui, "Mode:", "colorize.mode", &mut color.mode, &ColorMode::ALL, automation);
 
     ui.add_space(4.0);
 
-    modified |= param_slider_with_lfo(ui, "Hue Offset:", "colorize.hue_offset", &mut color.hue_offset, 0.0..=1.0, automation);
-    modified |= param_slider_with_lfo(ui, "Saturation:", "colorize.saturation", &mut color.saturation, 0.0..=2.0, automation);
-    modified |= param_slider_with_lfo(ui, "Levels:", "colorize.levels", &mut color.levels, 2.0..=32.0, automation);
+    modified |= param_slider_with_lfo(ui, "Hue Offset:", "colorize.hue_offset", &mut color.hue_offset, automation);
+    modified |= param_slider_with_lfo(ui, "Saturation:", "colorize.saturation", &mut color.saturation, automation);
+    modified |= param_slider_with_lfo(ui, "Levels:", "colorize.levels", &mut color.levels, automation);
 
     // Gradient colors (only show when gradient mode)
     if color.mode == ColorMode::Gradient {
         ui.add_space(4.0);
+        modified |= enum_combo_with_stepper(ui, "Lookup:", "colorize.gradient_source", &mut color.gradient_source, &GradientSource::ALL, automation);
         ui.horizontal(|ui| {
             ui.label("Start:");
-            let mut start_color = Color32::from_rgb(
-                (color.gradient_start[0] * 255.0) as u8,
-                (color.gradient_start[1] * 255.0) as u8,
-                (color.gradient_start[2] * 255.0) as u8,
-            );
-            if ui.color_edit_button_srgba(&mut start_color).changed() {
-                color.gradient_start = [
-                    start_color.r() as f32 / 255.0,
-                    start_color.g() as f32 / 255.0,
-                    start_color.b() as f32 / 255.0,
-                ];
-                modified = true;
-            }
+            modified |= color_edit_rgb(ui, &mut color.gradient_start);
 
             ui.label("End:");
-            let mut end_color = Color32::from_rgb(
-                (color.gradient_end[0] * 255.0) as u8,
-                (color.gradient_end[1] * 255.0) as u8,
-                (color.gradient_end[2] * 255.0) as u8,
-            );
-            if ui.color_edit_button_srgba(&mut end_color).changed() {
-                color.gradient_end = [
-                    end_color.r() as f32 / 255.0,
-                    end_color.g() as f32 / 255.0,
-                    end_color.b() as f32 / 255.0,
-                ];
-                modified = true;
+            modified |= color_edit_rgb(ui, &mut color.gradient_end);
+        });
+    }
+
+    // Imported palette (only show when threshold mode)
+    if color.mode == ColorMode::Threshold {
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label("Palette:");
+            match &color.palette_path {
+                Some(path) => {
+                    let name = std::path::Path::new(path).file_name().unwrap_or_default().to_string_lossy();
+                    ui.label(RichText::new(name).monospace()).on_hover_text(path.as_str());
+                    for &[r, g, b] in &color.palette {
+                        let (rect, _) = ui.allocate_exact_size(egui::vec2(8.0, 12.0), egui::Sense::hover());
+                        let swatch = Color32::from_rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8);
+                        ui.painter().rect_filled(rect, 0.0, swatch);
+                    }
+                    if ui.small_button("Clear").clicked() {
+                        color.palette_path = None;
+                        color.palette.clear();
+                        modified = true;
+                    }
+                }
+                None => {
+                    ui.label(RichText::new("Procedural (drop a .gpl/.hex file to import)").small().color(Color32::GRAY));
+                }
+            }
+        });
+        if color.palette_path.is_some() && color.palette.is_empty() {
+            ui.label(RichText::new("Palette file missing: using procedural colors").small().color(Color32::YELLOW));
+        }
+    }
+
+    // Lookup palette (only show when palette mode)
+    if color.mode == ColorMode::Palette {
+        ui.add_space(4.0);
+        let error_id = egui::Id::new("lut_error");
+        ui.horizontal(|ui| {
+            ui.label("Palette:");
+            let selected = match &color.lut_path {
+                Some(path) => std::path::Path::new(path).file_name().unwrap_or_default().to_string_lossy().into_owned(),
+                None => color.lut_name.clone(),
+            };
+            egui::ComboBox::from_id_salt("colorize_lut").selected_text(selected).show_ui(ui, |ui| {
+                for (name, _) in phosphlux_lite::palette::BUILTIN_LUTS {
+                    let current = color.lut_path.is_none() && color.lut_name == name;
+                    if ui.selectable_label(current, name).clicked() {
+                        color.lut_name = name.to_string();
+                        color.lut_path = None;
+                        color.lut.clear();
+                        modified = true;
+                    }
+                }
+            });
+            if ui.small_button("Load…").on_hover_text("PNG strip or .gpl/.hex palette").clicked() {
+                let picked = rfd::FileDialog::new().add_filter("Palette", &["png", "gpl", "hex"]).pick_file();
+                if let Some(path) = picked {
+                    match phosphlux_lite::palette::load_lut(&path) {
+                        Ok(colors) => {
+                            color.lut = colors;
+                            color.lut_path = Some(path.to_string_lossy().into_owned());
+                            ui.data_mut(|d| d.remove::<String>(error_id));
+                            modified = true;
+                        }
+                        Err(e) => {
+                            let name = path.file_name().unwrap_or_default().to_string_lossy();
+                            ui.data_mut(|d| d.insert_temp(error_id, format!("Can't load {name}: {e}")));
+                        }
+                    }
+                }
+            }
+        });
+
+        // The bands as they map onto the signal, dark to bright
+        let colors = color.lut_colors();
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width().min(240.0), 10.0), egui::Sense::hover());
+        let band = rect.width() / colors.len() as f32;
+        for (i, &[r, g, b]) in colors.iter().enumerate() {
+            let min = rect.left_top() + egui::vec2(band * i as f32, 0.0);
+            let swatch = Color32::from_rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8);
+            ui.painter().rect_filled(egui::Rect::from_min_size(min, egui::vec2(band, rect.height())), 0.0, swatch);
+        }
+
+        if let Some(error) = ui.data(|d| d.get_temp::<String>(error_id)) {
+            ui.label(RichText::new(error).small().color(Color32::from_rgb(255, 100, 100)));
+        } else if color.lut_path.is_some() && color.lut.is_empty() {
+            ui.label(RichText::new("Palette file missing: using the built-in colors").small().color(Color32::YELLOW));
+        }
+    }
+
+    // Phosphor tint (only show when monochrome mode)
+    if color.mode == ColorMode::Monochrome {
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label("Tint:");
+            modified |= color_edit_rgb(ui, &mut color.mono_tint);
+
+            let tints = [
+                ("White", [1.0, 1.0, 1.0]),
+                ("P1 Green", [0.2, 1.0, 0.3]),
+                ("Amber", [1.0, 0.7, 0.1]),
+                ("Blue", [0.4, 0.6, 1.0]),
+            ];
+            for (name, tint) in tints {
+                if ui.small_button(name).clicked() {
+                    color.mono_tint = tint;
+                    modified = true;
+                }
             }
         });
     }
@@ -479,55 +1988,99 @@ fn draw_colorize_stage(ui: &mut Ui, color: &mut ColorizeStage, automation: &mut
     modified
 }
 
+/// Color picker button for a 0-1 RGB triple
+fn color_edit_rgb(ui: &mut Ui, rgb: &mut [f32; 3]) -> bool {
+    let mut color = Color32::from_rgb(
+        (rgb[0] * 255.0) as u8,
+        (rgb[1] * 255.0) as u8,
+        (rgb[2] * 255.0) as u8,
+    );
+    if ui.color_edit_button_srgba(&mut color).changed() {
+        *rgb = [
+            color.r() as f32 / 255.0,
+            color.g() as f32 / 255.0,
+            color.b() as f32 / 255.0,
+        ];
+        return true;
+    }
+    false
+}
+
 fn draw_feedback_stage(ui: &mut Ui, fb: &mut FeedbackStage, mixer: &mut MixerStage, automation: &mut AutomationState) -> bool {
     let mut modified = false;
 
     modified |= ui.checkbox(&mut fb.enabled, "Enable Feedback").changed();
 
+    ui.add_space(4.0);
+
+    // Mix controls (from mixer stage). Without feedback they blend against
+    // black or a held frame.
     if fb.enabled {
-        ui.add_space(4.0);
+        modified |= param_slider_with_lfo(ui, "FB Mix:", "mixer.feedback_mix", &mut mixer.feedback_mix, automation);
+    } else {
+        modified |= param_slider_with_lfo(ui, "Backdrop:", "mixer.feedback_mix", &mut mixer.feedback_mix, automation);
+        modified |= ui
+            .checkbox(&mut mixer.hold_backdrop, "Hold Frame")
+            .on_hover_text("Blend against the current frame, frozen, instead of black")
+            .changed();
+    }
 
-        // Mix controls (from mixer stage)
-        modified |= param_slider_with_lfo(ui, "FB Mix:", "mixer.feedback_mix", &mut mixer.feedback_mix, 0.0..=1.0, automation);
+    modified |= enum_combo_with_stepper(ui, "Blend Mode:", "mixer.blend_mode", &mut mixer.blend_mode, &BlendMode::ALL, automation);
 
-        ui.label("Blend Mode:");
-        egui::ComboBox::from_id_salt("blend_mode")
-            .selected_text(format!("{:?}", mixer.blend_mode))
-            .show_ui(ui, |ui| {
-                for mode in [BlendMode::Mix, BlendMode::Add, BlendMode::Multiply, BlendMode::Screen, BlendMode::Overlay, BlendMode::Difference, BlendMode::LumaKeyA, BlendMode::LumaKeyB] {
-                    if ui.selectable_label(mixer.blend_mode == mode, format!("{:?}", mode)).clicked() {
-                        mixer.blend_mode = mode;
-                        modified = true;
-                    }
-                }
-            });
+    modified |= param_slider_with_lfo(ui, "Opacity:", "mixer.layer_opacity", &mut mixer.layer_opacity, automation);
+    modified |= ui
+        .checkbox(&mut mixer.normalize, "Normalize")
+        .on_hover_text("Crossfade new signal and feedback (weights sum to 1), with opacity as output gain")
+        .changed();
+
+    ui.add_space(4.0);
+    ui.separator();
+    ui.label("Luma Key:");
 
-        modified |= param_slider_with_lfo(ui, "Opacity:", "mixer.layer_opacity", &mut mixer.layer_opacity, 0.0..=1.0, automation);
+    modified |= param_slider_with_lfo(ui, "Threshold:", "mixer.key_threshold", &mut mixer.key_threshold, automation);
+    modified |= param_slider_with_lfo(ui, "Softness:", "mixer.key_softness", &mut mixer.key_softness, automation);
+    modified |= checkbox_with_stepper(ui, "Invert Key", "mixer.key_invert", &mut mixer.key_invert, automation);
 
+    if fb.enabled {
         ui.add_space(4.0);
         ui.separator();
-        ui.label("Luma Key:");
+        ui.label("Transform:");
 
-        modified |= param_slider_with_lfo(ui, "Threshold:", "mixer.key_threshold", &mut mixer.key_threshold, 0.0..=1.0, automation);
-        modified |= param_slider_with_lfo(ui, "Softness:", "mixer.key_softness", &mut mixer.key_softness, 0.0..=0.5, automation);
-        modified |= ui.checkbox(&mut mixer.key_invert, "Invert Key").changed();
+        modified |= param_slider_with_lfo(ui, "Zoom:", "feedback.zoom", &mut fb.zoom, automation);
+        modified |= angle_slider_with_lfo(ui, "Rotation:", "feedback.rotation", &mut fb.rotation, 45.0, automation);
+        modified |= param_slider_with_lfo(ui, "Offset X:", "feedback.offset_x", &mut fb.offset_x, automation);
+        modified |= param_slider_with_lfo(ui, "Offset Y:", "feedback.offset_y", &mut fb.offset_y, automation);
+        modified |= enum_combo_with_stepper(ui, "Edges:", "feedback.wrap", &mut fb.wrap, &FeedbackWrap::ALL, automation);
 
         ui.add_space(4.0);
         ui.separator();
-        ui.label("Transform:");
+        ui.label("Second Tap:");
+
+        modified |= param_slider_with_lfo(ui, "Tap Mix:", "feedback.tap_mix", &mut fb.tap_mix, automation);
+        if fb.tap_mix > 0.0 {
+            modified |= param_slider_with_lfo(ui, "Zoom:", "feedback.tap2_zoom", &mut fb.tap2_zoom, automation);
+            modified |= angle_slider_with_lfo(ui, "Rotation:", "feedback.tap2_rotation", &mut fb.tap2_rotation, 45.0, automation);
+            modified |= param_slider_with_lfo(ui, "Offset X:", "feedback.tap2_offset_x", &mut fb.tap2_offset_x, automation);
+            modified |= param_slider_with_lfo(ui, "Offset Y:", "feedback.tap2_offset_y", &mut fb.tap2_offset_y, automation);
+        }
+
+        ui.add_space(4.0);
+        ui.separator();
+        ui.label("Echo:");
 
-        modified |= param_slider_with_lfo(ui, "Zoom:", "feedback.zoom", &mut fb.zoom, 0.9..=1.1, automation);
-        modified |= param_slider_with_lfo(ui, "Rotation:", "feedback.rotation", &mut fb.rotation, -0.1..=0.1, automation);
-        modified |= param_slider_with_lfo(ui, "Offset X:", "feedback.offset_x", &mut fb.offset_x, -0.1..=0.1, automation);
-        modified |= param_slider_with_lfo(ui, "Offset Y:", "feedback.offset_y", &mut fb.offset_y, -0.1..=0.1, automation);
+        modified |= param_slider_with_lfo(ui, "Echo Mix:", "feedback.echo_mix", &mut fb.echo_mix, automation);
+        modified |= ui
+            .add(egui::Slider::new(&mut fb.trail_length, 2..=MAX_TRAIL_LENGTH).text("Trail Length"))
+            .on_hover_text("Frames kept; the echo is the oldest (changing it clears the trail)")
+            .changed();
 
         ui.add_space(4.0);
         ui.separator();
         ui.label("Color:");
 
-        modified |= param_slider_with_lfo(ui, "Hue Shift:", "feedback.hue_shift", &mut fb.hue_shift, 0.0..=0.1, automation);
-        modified |= param_slider_with_lfo(ui, "Decay:", "feedback.decay", &mut fb.decay, 0.8..=1.0, automation);
-        modified |= param_slider_with_lfo(ui, "Saturation:", "feedback.saturation", &mut fb.saturation, 0.0..=2.0, automation);
+        modified |= param_slider_with_lfo(ui, "Hue Shift:", "feedback.hue_shift", &mut fb.hue_shift, automation);
+        modified |= param_slider_with_lfo(ui, "Decay:", "feedback.decay", &mut fb.decay, automation);
+        modified |= param_slider_with_lfo(ui, "Saturation:", "feedback.saturation", &mut fb.saturation, automation);
     }
 
     modified
@@ -536,40 +2089,77 @@ fn draw_feedback_stage(ui: &mut Ui, fb: &mut FeedbackStage, mixer: &mut MixerSta
 fn draw_output_stage(ui: &mut Ui, out: &mut OutputStage, automation: &mut AutomationState) -> bool {
     let mut modified = false;
 
-    // Effect chain: VHS -> Cable -> CRT (toggleable)
-    ui.horizontal(|ui| {
-        modified |= ui.checkbox(&mut out.vhs_enabled, "VHS").changed();
-        modified |= ui.checkbox(&mut out.cable_enabled, "Cable").changed();
-        modified |= ui.checkbox(&mut out.crt_enabled, "CRT").changed();
-    });
+    // Effect chain in processing order: toggle each, move it up or down
+    let mut move_up = None;
+    for (slot, &effect) in out.order.iter().enumerate() {
+        ui.horizontal(|ui| {
+            let enabled = match effect {
+                OutputEffect::Vhs => &mut out.vhs_enabled,
+                OutputEffect::Cable => &mut out.cable_enabled,
+                OutputEffect::Crt => &mut out.crt_enabled,
+            };
+            modified |= ui.checkbox(enabled, effect.label()).changed();
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.add_enabled(slot + 1 < out.order.len(), egui::Button::new("▼").small()).clicked() {
+                    move_up = Some(slot + 1);
+                }
+                if ui.add_enabled(slot > 0, egui::Button::new("▲").small()).clicked() {
+                    move_up = Some(slot);
+                }
+            });
+        });
+    }
+    if let Some(slot) = move_up {
+        out.order.swap(slot - 1, slot);
+        modified = true;
+    }
 
     ui.separator();
 
     // VHS controls
     if out.vhs_enabled {
         ui.collapsing("VHS", |ui| {
-            modified |= param_slider_with_lfo(ui, "Tracking:", "output.tracking", &mut out.tracking, 0.0..=1.0, automation);
-            modified |= param_slider_with_lfo(ui, "Chroma:", "output.chroma_shift", &mut out.chroma_shift, 0.0..=0.02, automation);
-            modified |= param_slider_with_lfo(ui, "Wobble:", "output.tape_wobble", &mut out.tape_wobble, 0.0..=1.0, automation);
-            modified |= param_slider_with_lfo(ui, "Noise:", "output.vhs_noise", &mut out.vhs_noise, 0.0..=0.5, automation);
+            modified |= param_slider_with_lfo(ui, "Tracking:", "output.tracking", &mut out.tracking, automation);
+            modified |= param_slider_with_lfo(ui, "Chroma:", "output.chroma_shift", &mut out.chroma_shift, automation);
+            modified |= param_slider_with_lfo(ui, "Wobble:", "output.tape_wobble", &mut out.tape_wobble, automation);
+            modified |= param_slider_with_lfo(ui, "Noise:", "output.vhs_noise", &mut out.vhs_noise, automation);
         });
     }
 
     // Cable controls
     if out.cable_enabled {
         ui.collapsing("Cable", |ui| {
-            modified |= param_slider_with_lfo(ui, "Bandwidth:", "output.bandwidth", &mut out.bandwidth, 0.5..=1.0, automation);
-            modified |= param_slider_with_lfo(ui, "Ghosting:", "output.ghosting", &mut out.ghosting, 0.0..=0.3, automation);
-            modified |= param_slider_with_lfo(ui, "Noise:", "output.cable_noise", &mut out.cable_noise, 0.0..=0.2, automation);
+            modified |= param_slider_with_lfo(ui, "Bandwidth:", "output.bandwidth", &mut out.bandwidth, automation);
+            modified |= param_slider_with_lfo(ui, "Luma BW:", "output.luma_bandwidth", &mut out.luma_bandwidth, automation);
+            modified |= param_slider_with_lfo(ui, "Chroma BW:", "output.chroma_bandwidth", &mut out.chroma_bandwidth, automation);
+            modified |= param_slider_with_lfo(ui, "Ghosting:", "output.ghosting", &mut out.ghosting, automation);
+            modified |= param_slider_with_lfo(ui, "Noise:", "output.cable_noise", &mut out.cable_noise, automation);
         });
     }
 
+    // Shared by the VHS and cable noise
+    if out.vhs_enabled || out.cable_enabled {
+        modified |= param_slider_with_lfo(ui, "Noise Persist:", "output.noise_persistence", &mut out.noise_persistence, automation);
+    }
+
     // CRT controls
     if out.crt_enabled {
         ui.collapsing("CRT", |ui| {
-            modified |= param_slider_with_lfo(ui, "Scanlines:", "output.scanlines", &mut out.scanlines, 0.0..=0.5, automation);
-            modified |= param_slider_with_lfo(ui, "Bloom:", "output.bloom", &mut out.bloom, 0.0..=1.0, automation);
-            modified |= param_slider_with_lfo(ui, "Vignette:", "output.vignette", &mut out.vignette, 0.0..=1.0, automation);
+            modified |= param_slider_with_lfo(ui, "Scanlines:", "output.scanlines", &mut out.scanlines, automation);
+            modified |= param_slider_with_lfo(ui, "Bloom:", "output.bloom", &mut out.bloom, automation);
+            modified |= param_slider_with_lfo(ui, "Vignette:", "output.vignette", &mut out.vignette, automation);
+            ui.horizontal(|ui| {
+                ui.label("Vignette Color:");
+                modified |= color_edit_rgb(ui, &mut out.vignette_color);
+                if ui.small_button("Black").clicked() {
+                    out.vignette_color = [0.0, 0.0, 0.0];
+                    modified = true;
+                }
+                if ui.small_button("Amber").clicked() {
+                    out.vignette_color = [0.35, 0.18, 0.02];
+                    modified = true;
+                }
+            });
         });
     }
 
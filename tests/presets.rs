@@ -0,0 +1,20 @@
+use phosphlux_lite::presets::{Preset, PRESET_VERSION};
+use phosphlux_lite::synth::BlendMode;
+use std::path::Path;
+
+#[test]
+fn v1_preset_migrates() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/preset_v1.json");
+    let preset = Preset::load(&path).expect("v1 fixture loads");
+
+    assert_eq!(preset.version, PRESET_VERSION);
+    assert_eq!(preset.name, "Violet Drift");
+
+    // A single invert number became per-channel flags
+    assert_eq!(preset.state.amplitude.invert, [true; 3]);
+
+    // The mixer keeps the crossfade it had, opacity included
+    assert!(preset.state.mixer.normalize);
+    assert_eq!(preset.state.mixer.blend_mode, BlendMode::Screen);
+    assert_eq!(preset.state.mixer.layer_opacity, 0.8);
+}